@@ -0,0 +1,28 @@
+/* C ABI for the lincolns crate, built with the `ffi` feature */
+#ifndef LINCOLNS_H
+#define LINCOLNS_H
+
+#include <stdbool.h>
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* An opaque lookup table of positions */
+typedef struct lincolns_index lincolns_index;
+
+/* Loads a table from NUL-terminated UTF-8 text, returning NULL when it can't be loaded */
+lincolns_index *lincolns_index_new(const char *text);
+
+/* Writes the line and column of a JSON Pointer path, returning false when it isn't found */
+bool lincolns_get(const lincolns_index *index, const char *pointer, size_t *out_line, size_t *out_col);
+
+/* Releases a table returned by lincolns_index_new */
+void lincolns_free(lincolns_index *index);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
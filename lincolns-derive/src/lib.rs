@@ -0,0 +1,114 @@
+//! Derive macro for [lincolns](https://docs.rs/lincolns)' `Located` trait
+//!
+//! This crate is re-exported by lincolns behind its `derive` feature and isn't intended to be used directly
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Fields, LitStr};
+
+/// Implements `lincolns::Located` for a struct with named fields
+///
+/// Fields are populated according to their `#[located(...)]` attributes, where each key is
+/// a pointer relative to the struct, defaulting to the struct itself for positions and spans
+/// and to the field's name for nested values
+///
+/// * `#[located(position = "key")]` an `Option<Position>` set to the position of `key`
+/// * `#[located(span = "key")]` an `Option<Span>` set to the span of `key`
+/// * `#[located(nested = "key")]` a value implementing `Located` which is located at `key`
+#[proc_macro_derive(Located, attributes(located))]
+pub fn derive_located(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+/// What a field is populated with
+enum Target {
+    Position(Option<String>),
+    Span(Option<String>),
+    Nested(Option<String>),
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "Located can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "Located can only be derived for structs",
+            ))
+        }
+    };
+    let mut assignments = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("located"))
+        {
+            let mut target = None;
+            attr.parse_nested_meta(|meta| {
+                let key = if meta.input.peek(syn::Token![=]) {
+                    Some(meta.value()?.parse::<LitStr>()?.value())
+                } else {
+                    None
+                };
+                target = Some(if meta.path.is_ident("position") {
+                    Target::Position(key)
+                } else if meta.path.is_ident("span") {
+                    Target::Span(key)
+                } else if meta.path.is_ident("nested") {
+                    Target::Nested(key.or_else(|| Some(ident.to_string())))
+                } else {
+                    return Err(meta.error("expected `position`, `span`, or `nested`"));
+                });
+                Ok(())
+            })?;
+            let pointer = |key: &Option<String>| match key {
+                Some(key) => quote! { &format!("{}/{}", pointer, #key) },
+                None => quote! { pointer },
+            };
+            assignments.push(match target {
+                Some(Target::Position(key)) => {
+                    let pointer = pointer(&key);
+                    quote! { self.#ident = positions.get(#pointer).cloned(); }
+                }
+                Some(Target::Span(key)) => {
+                    let pointer = pointer(&key);
+                    quote! { self.#ident = positions.span(#pointer).cloned(); }
+                }
+                Some(Target::Nested(key)) => {
+                    let pointer = pointer(&key);
+                    quote! { ::lincolns::Located::locate(&mut self.#ident, positions, #pointer); }
+                }
+                None => return Err(syn::Error::new(attr.span(), "expected a located target")),
+            });
+        }
+    }
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::lincolns::Located for #name #ty_generics #where_clause {
+            fn locate(
+                &mut self,
+                positions: &::lincolns::Positions,
+                pointer: &str,
+            ) {
+                #(#assignments)*
+            }
+        }
+    })
+}
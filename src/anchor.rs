@@ -0,0 +1,157 @@
+use crate::{source::Source, Comment, Span};
+use std::ops::Range;
+
+/// An anchor, `&name`, marking a node which aliases may refer to
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anchor {
+    /// Name of the anchor, excluding the `&`
+    pub name: String,
+    /// Region of the anchor, from its `&` to the end of its name
+    pub span: Span,
+}
+
+/// An alias, `*name`, referring to an anchored node
+///
+/// ```rust,edition2018
+/// # fn main() -> lincolns::Result<()>  {
+/// let positions = lincolns::from_str("base: &base {a: 1}\nprod: *base\n")?;
+/// let anchor = &positions.anchors()[0];
+/// let alias = &positions.aliases()[0];
+/// assert_eq!((anchor.name.as_str(), anchor.span.start.line), ("base", 1));
+/// assert_eq!((alias.name.as_str(), alias.span.start.line), ("base", 2));
+/// assert_eq!(alias.anchor.as_ref(), Some(&anchor.span));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alias {
+    /// Name of the anchor referred to, excluding the `*`
+    pub name: String,
+    /// Region of the alias, from its `*` to the end of its name
+    pub span: Span,
+    /// Region of the anchor referred to, being the last defined with the same name before the alias
+    pub anchor: Option<Span>,
+}
+
+/// Finds the anchors in a source given the byte ranges of its scalars and comments,
+/// within which `&` does not start an anchor
+pub(crate) fn scan(
+    source: &Source,
+    scalars: &[Range<usize>],
+    comments: &[Comment],
+) -> Vec<Anchor> {
+    let text = source.text();
+    let mut masked = scalars
+        .iter()
+        .cloned()
+        .chain(comments.iter().map(|comment| comment.span.bytes.clone()))
+        .collect::<Vec<_>>();
+    masked.sort_by_key(|range| range.start);
+    let mut masked = masked.into_iter().peekable();
+    let mut anchors = Vec::new();
+    let mut prev = '\n';
+    for (idx, c) in text.char_indices() {
+        while masked.peek().is_some_and(|range| range.end <= idx) {
+            masked.next();
+        }
+        let skipped = masked.peek().is_some_and(|range| range.contains(&idx));
+        if c == '&' && !skipped && (prev.is_whitespace() || "[{,".contains(prev)) {
+            let name = &text[idx + 1..];
+            let name = &name[..name.find(is_boundary).unwrap_or(name.len())];
+            if !name.is_empty() {
+                anchors.push(Anchor {
+                    name: name.into(),
+                    span: source.span(idx..idx + 1 + name.len()),
+                });
+            }
+        }
+        prev = c;
+    }
+    anchors
+}
+
+/// Locates the alias at each offset, resolving it to the last anchor defined before it by the same name
+pub(crate) fn resolve(
+    source: &Source,
+    offsets: impl IntoIterator<Item = usize>,
+    anchors: &[Anchor],
+) -> Vec<Alias> {
+    offsets
+        .into_iter()
+        .map(|offset| {
+            let bytes = source.alias(offset);
+            let name = source.text()[offset + 1..bytes.end].to_owned();
+            let anchor = anchors
+                .iter()
+                .rev()
+                .find(|anchor| anchor.span.bytes.start < offset && anchor.name == name)
+                .map(|anchor| anchor.span.clone());
+            Alias {
+                name,
+                span: source.span(bytes),
+                anchor,
+            }
+        })
+        .collect()
+}
+
+/// Whether a character ends an anchor or alias name
+fn is_boundary(c: char) -> bool {
+    c.is_whitespace() || ",[]{}".contains(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_str, Position, Result};
+
+    #[test]
+    fn indexes_anchors_and_aliases() -> Result<()> {
+        let positions =
+            from_str("a: &x 1 # &not\nb: 'also &not'\nc: [&y 2, *x]\nd: &x\n  e: *y\nf: *x\n")?;
+        let anchors = positions
+            .anchors()
+            .iter()
+            .map(|anchor| (anchor.name.as_str(), anchor.span.start))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            anchors,
+            vec![
+                ("x", Position { line: 1, col: 3 }),
+                ("y", Position { line: 3, col: 4 }),
+                ("x", Position { line: 4, col: 3 })
+            ]
+        );
+        let aliases = positions
+            .aliases()
+            .iter()
+            .map(|alias| {
+                (
+                    alias.name.as_str(),
+                    alias.span.start,
+                    alias.anchor.as_ref().map(|span| span.start),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            aliases,
+            vec![
+                (
+                    "x",
+                    Position { line: 3, col: 10 },
+                    Some(Position { line: 1, col: 3 })
+                ),
+                (
+                    "y",
+                    Position { line: 5, col: 5 },
+                    Some(Position { line: 3, col: 4 })
+                ),
+                (
+                    "x",
+                    Position { line: 6, col: 3 },
+                    Some(Position { line: 4, col: 3 })
+                )
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+use crate::Positions;
+use ariadne::{Color, Config, IndexType, Label, Report, ReportKind, Source};
+use std::{fmt::Display, hash::Hash, ops::Range};
+
+/// Orders report kinds from most to least severe
+fn rank(kind: &ReportKind) -> u8 {
+    match kind {
+        ReportKind::Error => 0,
+        ReportKind::Warning => 1,
+        ReportKind::Custom(..) => 2,
+        ReportKind::Advice => 3,
+    }
+}
+
+fn color(kind: &ReportKind) -> Color {
+    match kind {
+        ReportKind::Error => Color::Red,
+        ReportKind::Warning => Color::Yellow,
+        ReportKind::Advice => Color::Fixed(147),
+        ReportKind::Custom(_, color) => *color,
+    }
+}
+
+impl Positions {
+    /// Builds an [ariadne](https://docs.rs/ariadne) report labeling the value of each
+    /// `(pointer, message, kind)` finding within the source identified by `id`
+    ///
+    /// The report takes the kind and message of its most severe finding. Findings
+    /// whose pointers are not indexed are skipped, and `None` is returned when no
+    /// findings remain
+    pub fn ariadne_report<'a, Id, I, P, M>(
+        &self,
+        id: Id,
+        findings: I,
+    ) -> Option<Report<'a, (Id, Range<usize>)>>
+    where
+        Id: std::fmt::Debug + Hash + Eq + ToOwned + Clone,
+        I: IntoIterator<Item = (P, M, ReportKind<'a>)>,
+        P: AsRef<str>,
+        M: Display,
+    {
+        let mut labeled = findings
+            .into_iter()
+            .filter_map(|(ptr, message, kind)| {
+                self.span(ptr)
+                    .map(|span| (span.bytes.clone(), message.to_string(), kind))
+            })
+            .collect::<Vec<_>>();
+        labeled.sort_by_key(|(bytes, _, kind)| (rank(kind), bytes.start));
+        let (bytes, message, kind) = labeled.first()?.clone();
+        Some(
+            Report::build(kind, (id.clone(), bytes))
+                .with_config(Config::default().with_index_type(IndexType::Byte))
+                .with_message(message)
+                .with_labels(labeled.into_iter().map(|(bytes, message, kind)| {
+                    Label::new((id.clone(), bytes))
+                        .with_message(message)
+                        .with_color(color(&kind))
+                }))
+                .finish(),
+        )
+    }
+
+    /// Returns the retained source for rendering reports built by
+    /// [`ariadne_report`](#method.ariadne_report)
+    pub fn ariadne_source(&self) -> Option<Source<&str>> {
+        self.source().map(Source::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_str_with, Options, Result};
+    use ariadne::ReportKind;
+
+    #[test]
+    fn report_labels_findings() -> Result<()> {
+        let positions = from_str_with(
+            "image: app:latest\nreplicas: 0\n",
+            Options::default().keep_source(true),
+        )?;
+        let report = positions
+            .ariadne_report(
+                "deploy.yml",
+                vec![
+                    (
+                        "/replicas",
+                        "expected at least one replica",
+                        ReportKind::Warning,
+                    ),
+                    ("/image", "avoid the latest tag", ReportKind::Error),
+                    ("/missing", "ignored", ReportKind::Error),
+                ],
+            )
+            .expect("missing report");
+        let source = positions.ariadne_source().expect("missing source");
+        let mut out = Vec::new();
+        report.write(("deploy.yml", source), &mut out)?;
+        let out = String::from_utf8_lossy(&out);
+        assert!(out.contains("avoid the latest tag"));
+        assert!(out.contains("expected at least one replica"));
+        assert!(!out.contains("ignored"));
+        Ok(())
+    }
+}
@@ -0,0 +1,258 @@
+use crate::{kind, source::LineIndex, Result, ScalarStyle};
+use yaml_rust::{
+    parser::{Event as YamlEvent, MarkedEventReceiver, Parser},
+    scanner::Marker,
+};
+
+/// A parser producing the events a [`Positions`](struct.Positions.html) index is built from
+///
+/// Implementing this for other formats makes them indexable by JSON Pointer without
+/// reimplementing any of the indexing
+///
+/// ```rust,edition2018
+/// use lincolns::{from_str_using, Backend, Event, Options, Position, ScalarStyle};
+///
+/// /// A format of `key=value` lines
+/// struct Properties;
+///
+/// impl Backend for Properties {
+///     fn parse(
+///         &self,
+///         text: &str,
+///         sink: &mut dyn FnMut(Event, usize),
+///     ) -> lincolns::Result<()> {
+///         let scalar = |value: &str| Event::Scalar {
+///             value: value.into(),
+///             style: ScalarStyle::Plain,
+///             tag: None,
+///         };
+///         sink(Event::DocumentStart, 0);
+///         sink(Event::MappingStart { anchor: 0 }, 0);
+///         let mut offset = 0;
+///         for line in text.split_inclusive('\n') {
+///             if let Some((key, value)) = line.trim_end().split_once('=') {
+///                 sink(scalar(key), offset);
+///                 sink(scalar(value), offset + key.len() + 1);
+///             }
+///             offset += line.len();
+///         }
+///         sink(Event::MappingEnd, text.len());
+///         sink(Event::DocumentEnd, text.len());
+///         Ok(())
+///     }
+/// }
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let positions = from_str_using(&Properties, "a=1\nb=2\n", Options::default())?;
+/// assert_eq!(positions.get("/b"), Some(&Position { line: 2, col: 0 }));
+/// # Ok(())
+/// # }
+/// ```
+pub trait Backend {
+    /// Parses text, passing each event to `sink` along with a byte offset
+    ///
+    /// Start events and scalars are located at the first byte of their node, and end events
+    /// at the byte just past the end of their node or, for flow collections, at their closing bracket
+    fn parse(
+        &self,
+        text: &str,
+        sink: &mut dyn FnMut(Event, usize),
+    ) -> Result<()>;
+}
+
+/// An event produced by a [`Backend`](trait.Backend.html)
+///
+/// Anchors identify nodes which aliases may refer to, with `0` meaning no anchor
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    DocumentStart,
+    DocumentEnd,
+    Scalar {
+        value: String,
+        style: ScalarStyle,
+        tag: Option<String>,
+    },
+    Alias {
+        anchor: usize,
+    },
+    SequenceStart {
+        anchor: usize,
+    },
+    SequenceEnd,
+    MappingStart {
+        anchor: usize,
+    },
+    MappingEnd,
+}
+
+/// The default [`Backend`](trait.Backend.html), built on [yaml-rust](https://docs.rs/yaml-rust),
+/// which reads both YAML and JSON content
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Yaml;
+
+impl Backend for Yaml {
+    fn parse(
+        &self,
+        text: &str,
+        sink: &mut dyn FnMut(Event, usize),
+    ) -> Result<()> {
+        let mut receiver = Receiver {
+            text,
+            lines: LineIndex::new(text),
+            sink,
+        };
+        Parser::new(text.chars()).load(&mut receiver, true)?;
+        Ok(())
+    }
+}
+
+struct Receiver<'a, 'b> {
+    text: &'a str,
+    lines: LineIndex,
+    sink: &'b mut dyn FnMut(Event, usize),
+}
+
+impl<'a, 'b> MarkedEventReceiver for Receiver<'a, 'b> {
+    fn on_event(
+        &mut self,
+        event: YamlEvent,
+        marker: Marker,
+    ) {
+        let event = match event {
+            YamlEvent::Nothing | YamlEvent::StreamStart | YamlEvent::StreamEnd => return,
+            YamlEvent::DocumentStart => Event::DocumentStart,
+            YamlEvent::DocumentEnd => Event::DocumentEnd,
+            YamlEvent::Alias(anchor) => Event::Alias { anchor },
+            YamlEvent::Scalar(value, style, _, tag) => Event::Scalar {
+                value,
+                style: style.into(),
+                tag: tag.as_ref().and_then(kind::tag),
+            },
+            YamlEvent::SequenceStart(anchor) => Event::SequenceStart { anchor },
+            YamlEvent::SequenceEnd => Event::SequenceEnd,
+            YamlEvent::MappingStart(anchor) => Event::MappingStart { anchor },
+            YamlEvent::MappingEnd => Event::MappingEnd,
+        };
+        let offset = offset(self.text, &self.lines, marker.line(), marker.col());
+        (self.sink)(event, offset)
+    }
+}
+
+/// A [`Backend`](trait.Backend.html) built on [yaml-rust2](https://docs.rs/yaml-rust2),
+/// a maintained fork of yaml-rust with fuller YAML 1.2 support
+///
+/// Requires the `yaml-rust2` feature
+#[cfg(feature = "yaml-rust2")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct YamlRust2;
+
+#[cfg(feature = "yaml-rust2")]
+impl Backend for YamlRust2 {
+    fn parse(
+        &self,
+        text: &str,
+        sink: &mut dyn FnMut(Event, usize),
+    ) -> Result<()> {
+        let mut receiver = Receiver2 {
+            text,
+            lines: LineIndex::new(text),
+            sink,
+        };
+        yaml_rust2::parser::Parser::new_from_str(text)
+            .load(&mut receiver, true)
+            .map_err(|err| crate::Error::Backend(Box::new(err)))
+    }
+}
+
+#[cfg(feature = "yaml-rust2")]
+struct Receiver2<'a, 'b> {
+    text: &'a str,
+    lines: LineIndex,
+    sink: &'b mut dyn FnMut(Event, usize),
+}
+
+#[cfg(feature = "yaml-rust2")]
+impl<'a, 'b> yaml_rust2::parser::MarkedEventReceiver for Receiver2<'a, 'b> {
+    fn on_event(
+        &mut self,
+        event: yaml_rust2::Event,
+        marker: yaml_rust2::scanner::Marker,
+    ) {
+        use yaml_rust2::{scanner::TScalarStyle, Event as Yaml2Event};
+        let event = match event {
+            Yaml2Event::Nothing | Yaml2Event::StreamStart | Yaml2Event::StreamEnd => return,
+            Yaml2Event::DocumentStart => Event::DocumentStart,
+            Yaml2Event::DocumentEnd => Event::DocumentEnd,
+            Yaml2Event::Alias(anchor) => Event::Alias { anchor },
+            // empty nodes are reported as an empty string rather than yaml-rust's `~`
+            Yaml2Event::Scalar(value, TScalarStyle::Plain, _, None) if value.is_empty() => {
+                Event::Scalar {
+                    value: "~".into(),
+                    style: ScalarStyle::Plain,
+                    tag: None,
+                }
+            }
+            Yaml2Event::Scalar(value, style, _, tag) => Event::Scalar {
+                value,
+                style: style.into(),
+                tag: tag.map(|tag| kind::tag_name(&tag.handle, &tag.suffix)),
+            },
+            Yaml2Event::SequenceStart(anchor, _) => Event::SequenceStart { anchor },
+            Yaml2Event::SequenceEnd => Event::SequenceEnd,
+            Yaml2Event::MappingStart(anchor, _) => Event::MappingStart { anchor },
+            Yaml2Event::MappingEnd => Event::MappingEnd,
+        };
+        let offset = offset(self.text, &self.lines, marker.line(), marker.col());
+        (self.sink)(event, offset)
+    }
+}
+
+/// Byte offset of a one-based line and a zero-based column counted in chars
+pub(crate) fn offset(
+    text: &str,
+    lines: &LineIndex,
+    line: usize,
+    col: usize,
+) -> usize {
+    let start = lines.start(line.saturating_sub(1)).unwrap_or_default();
+    text[start..]
+        .char_indices()
+        .nth(col)
+        .map(|(idx, _)| start + idx)
+        .unwrap_or(text.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yaml_offsets_are_bytes() -> Result<()> {
+        let mut events = Vec::new();
+        Yaml.parse("é: ü\n", &mut |event, offset| {
+            events.push((event, offset))
+        })?;
+        let scalars = events
+            .into_iter()
+            .filter_map(|(event, offset)| match event {
+                Event::Scalar { value, .. } => Some((value, offset)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(scalars, vec![("é".into(), 0), ("ü".into(), 4)]);
+        Ok(())
+    }
+
+    #[cfg(feature = "yaml-rust2")]
+    #[test]
+    fn yaml_rust2_indexes_like_yaml() -> Result<()> {
+        let text = include_str!("../tests/data/example.yml");
+        let expected = crate::from_str(text)?;
+        let positions = crate::from_str_using(&YamlRust2, text, Default::default())?;
+        assert_eq!(
+            positions.iter().collect::<Vec<_>>(),
+            expected.iter().collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,19 @@
+//! A language server for JSON and YAML documents, speaking LSP over stdio
+use lincolns::lsp_server::Server;
+use lsp_server::Connection;
+use std::process::exit;
+
+fn main() {
+    let (connection, io_threads) = Connection::stdio();
+    let served = Server::new()
+        .run(&connection)
+        .map_err(|err| err.to_string())
+        .and_then(|_| {
+            drop(connection);
+            io_threads.join().map_err(|err| err.to_string())
+        });
+    if let Err(err) = served {
+        eprintln!("error: {}", err.trim_end());
+        exit(2);
+    }
+}
@@ -0,0 +1,742 @@
+mod output;
+
+use lincolns::{
+    edit::Editor, from_str_with, NodeKind, Options, Pointer, Position, Positions, Requirement,
+};
+#[cfg(feature = "simd")]
+use lincolns::{from_str_using, SimdJson};
+use output::{Finding, Level, Output, Paint};
+use std::{
+    env,
+    error::Error,
+    fs,
+    io::{self, IsTerminal, Read},
+    path::{Path, PathBuf},
+    process::exit,
+    str::FromStr,
+    thread,
+    time::{Duration, SystemTime},
+};
+use structopt::StructOpt;
+
+/// Look up line and column information for JSON Pointer paths within JSON and YAML files
+///
+/// Exits with 0 on success, 1 when nothing was found, and 2 on errors
+#[derive(StructOpt)]
+#[structopt(name = "lincolns")]
+struct Opts {
+    /// Output format
+    #[structopt(long, short, default_value = "text", possible_values = Output::NAMES)]
+    output: Output,
+    /// Input format. auto treats files with a .json extension as JSON and everything else as YAML
+    #[structopt(long, short, default_value = "auto", possible_values = Format::NAMES)]
+    format: Format,
+    /// When to color text output. auto colors it when writing to a terminal and NO_COLOR isn't set
+    #[structopt(long, global = true, default_value = "auto", possible_values = Color::NAMES)]
+    color: Color,
+    /// JSON Pointer path which pointer arguments are relative to, e.g. /spec/template
+    #[structopt(long, parse(try_from_str = Pointer::parse))]
+    relative_to: Option<Pointer>,
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+#[derive(StructOpt)]
+enum Command {
+    /// Prints the line and column of one or more JSON Pointer paths
+    Get {
+        /// JSON or YAML file, or - to read from stdin
+        file: PathBuf,
+        /// JSON Pointer paths, e.g. /foo/0/bar
+        #[structopt(required_unless = "pointers-from")]
+        pointers: Vec<String>,
+        /// File listing additional JSON Pointer paths, one per line
+        #[structopt(long)]
+        pointers_from: Option<PathBuf>,
+        /// Look the pointers up again each time the file changes, until interrupted
+        #[structopt(long, short)]
+        watch: bool,
+    },
+    /// Prints every indexed JSON Pointer path with its line and column
+    Dump {
+        /// JSON or YAML file, or - to read from stdin
+        file: PathBuf,
+        /// Order entries by pointer or by their position in the file
+        #[structopt(
+            long,
+            default_value = "pointer",
+            possible_values = &["pointer", "position"]
+        )]
+        sort: Sort,
+        /// Only print pointers starting with this prefix
+        #[structopt(long)]
+        prefix: Option<String>,
+    },
+    /// Prints the JSON Pointer path of the node at, or enclosing, a line and column
+    At {
+        /// JSON or YAML file, or - to read from stdin
+        file: PathBuf,
+        /// Location formatted as line:col
+        location: Location,
+    },
+    /// Prints the indexed JSON Pointer paths containing a pattern
+    Find {
+        /// JSON or YAML file, or - to read from stdin
+        file: PathBuf,
+        /// Text to search pointers for
+        pattern: String,
+    },
+    /// Reports messages at the positions of JSON Pointer paths
+    ///
+    /// Use with --output github to annotate pull requests from GitHub Actions workflows
+    Report {
+        /// JSON or YAML file, or - to read from stdin
+        file: PathBuf,
+        /// Severity of the reported messages
+        #[structopt(
+            long,
+            default_value = "error",
+            possible_values = &["error", "warning", "notice"]
+        )]
+        level: Level,
+        /// Messages formatted as pointer:message, e.g. "/spec/image:avoid latest tags". Write
+        /// pointers containing : as URI fragments, e.g. "#/ports/8080%3A80:too open"
+        #[structopt(required = true)]
+        findings: Vec<Finding>,
+    },
+    /// Prints the source lines of JSON Pointer paths, underlining them with messages
+    Annotate {
+        /// JSON or YAML file, or - to read from stdin
+        file: PathBuf,
+        /// Severity of the messages
+        #[structopt(
+            long,
+            default_value = "error",
+            possible_values = &["error", "warning", "notice"]
+        )]
+        level: Level,
+        /// Messages formatted as pointer:message, e.g. "/spec/image:avoid latest tags". Write
+        /// pointers containing : as URI fragments, e.g. "#/ports/8080%3A80:too open"
+        #[structopt(required = true)]
+        findings: Vec<Finding>,
+    },
+    /// Reports required JSON Pointer paths which are missing or of the wrong kind
+    ///
+    /// Missing paths are reported at their nearest existing ancestor
+    Validate {
+        /// JSON or YAML file, or - to read from stdin
+        file: PathBuf,
+        /// Required paths, optionally with the kind of node required, e.g. /spec/ports:sequence.
+        /// Kinds are string, integer, float, boolean, null, mapping, sequence, or alias
+        #[structopt(required_unless = "required-from")]
+        required: Vec<Required>,
+        /// File listing additional required paths, one per line
+        #[structopt(long)]
+        required_from: Option<PathBuf>,
+    },
+    /// Prints statistics about a file: its node count, depth, nodes of each kind, and duplicate keys
+    ///
+    /// Duplicate keys are reported as warnings with --output github or sarif
+    Stats {
+        /// JSON or YAML file, or - to read from stdin
+        file: PathBuf,
+    },
+    /// Sets the value of a scalar, or adds an entry to a mapping, preserving the rest of the file
+    ///
+    /// Prints the edited file unless --in-place is given
+    Set {
+        /// JSON or YAML file, or - to read from stdin
+        file: PathBuf,
+        /// JSON Pointer path of the scalar or new mapping entry, e.g. /spec/replicas
+        pointer: String,
+        /// The new value
+        value: String,
+        /// Write the edited file rather than printing it
+        #[structopt(long, short)]
+        in_place: bool,
+    },
+    /// Removes a mapping entry or sequence item, preserving the rest of the file
+    ///
+    /// Prints the edited file unless --in-place is given
+    Unset {
+        /// JSON or YAML file, or - to read from stdin
+        file: PathBuf,
+        /// JSON Pointer path of the entry or item, e.g. /spec/debug
+        pointer: String,
+        /// Write the edited file rather than printing it
+        #[structopt(long, short)]
+        in_place: bool,
+    },
+}
+
+struct Location {
+    line: usize,
+    col: usize,
+}
+
+impl FromStr for Location {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("expected a location formatted as line:col but found {}", s);
+        let (line, col) = s.split_once(':').ok_or_else(invalid)?;
+        Ok(Location {
+            line: line.parse().map_err(|_| invalid())?,
+            col: col.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+struct Required(Requirement);
+
+impl FromStr for Required {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let kind = |name: &str| match name {
+            "string" => Some(NodeKind::String),
+            "integer" => Some(NodeKind::Integer),
+            "float" => Some(NodeKind::Float),
+            "boolean" => Some(NodeKind::Bool),
+            "null" => Some(NodeKind::Null),
+            "mapping" => Some(NodeKind::Mapping),
+            "sequence" => Some(NodeKind::Sequence),
+            "alias" => Some(NodeKind::Alias),
+            _ => None,
+        };
+        // pointers may themselves contain `:`, which is only followed by a kind's name
+        Ok(Required(
+            match s
+                .rsplit_once(':')
+                .and_then(|(pointer, name)| Some((pointer, kind(name)?)))
+            {
+                Some((pointer, kind)) => Requirement::new(pointer).kind(kind),
+                None => Requirement::new(s),
+            },
+        ))
+    }
+}
+
+enum Sort {
+    Pointer,
+    Position,
+}
+
+impl FromStr for Sort {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pointer" => Ok(Sort::Pointer),
+            "position" => Ok(Sort::Position),
+            other => Err(format!("unsupported sort {}", other)),
+        }
+    }
+}
+
+/// When text output is colored with ANSI escape codes
+#[derive(Clone, Copy)]
+enum Color {
+    Auto,
+    Always,
+    Never,
+}
+
+impl Color {
+    const NAMES: &'static [&'static str] = &["auto", "always", "never"];
+
+    /// Decides whether to paint output given whether stdout is a terminal and NO_COLOR is set
+    fn paint(
+        self,
+        terminal: bool,
+        no_color: bool,
+    ) -> Paint {
+        Paint(match self {
+            Color::Auto => terminal && !no_color,
+            Color::Always => true,
+            Color::Never => false,
+        })
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Color::Auto),
+            "always" => Ok(Color::Always),
+            "never" => Ok(Color::Never),
+            other => Err(format!("unsupported color {}", other)),
+        }
+    }
+}
+
+/// The format of an input document
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Auto,
+    Yaml,
+    Json,
+}
+
+impl Format {
+    const NAMES: &'static [&'static str] = &["auto", "yaml", "json"];
+
+    fn resolve(
+        self,
+        file: &Path,
+    ) -> Format {
+        match self {
+            Format::Auto if file.extension().is_some_and(|ext| ext == "json") => Format::Json,
+            Format::Auto => Format::Yaml,
+            explicit => explicit,
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Format::Auto),
+            "yaml" => Ok(Format::Yaml),
+            "json" => Ok(Format::Json),
+            other => Err(format!("unsupported format {}", other)),
+        }
+    }
+}
+
+fn load(
+    file: &Path,
+    format: Format,
+) -> Result<Positions, Box<dyn Error>> {
+    load_with(file, format, Options::default())
+}
+
+fn load_with(
+    file: &Path,
+    format: Format,
+    options: Options,
+) -> Result<Positions, Box<dyn Error>> {
+    let content = read(file)?;
+    if format.resolve(file) == Format::Json {
+        return load_json(file, &content, options);
+    }
+    Ok(from_str_with(&content, options)?)
+}
+
+/// Reads a file, or stdin given -
+fn read(file: &Path) -> Result<String, Box<dyn Error>> {
+    if file == Path::new("-") {
+        let mut content = String::new();
+        io::stdin()
+            .read_to_string(&mut content)
+            .map_err(|err| format!("failed to read stdin: {}", err))?;
+        Ok(content)
+    } else {
+        Ok(fs::read_to_string(file)
+            .map_err(|err| format!("failed to read {}: {}", file.display(), err))?)
+    }
+}
+
+/// Applies an edit to a file, printing the result or writing it back in place
+fn edit<F>(
+    file: &Path,
+    in_place: bool,
+    apply: F,
+) -> Result<bool, Box<dyn Error>>
+where
+    F: FnOnce(&mut Editor) -> lincolns::Result<()>,
+{
+    if in_place && file == Path::new("-") {
+        return Err("stdin can't be edited in place".into());
+    }
+    let mut editor = Editor::new(read(file)?)?;
+    apply(&mut editor)?;
+    if in_place {
+        fs::write(file, editor.text())
+            .map_err(|err| format!("failed to write {}: {}", file.display(), err))?;
+    } else {
+        print!("{}", editor.text());
+    }
+    Ok(true)
+}
+
+/// How often a watched file is checked for changes
+const POLL: Duration = Duration::from_millis(250);
+/// How long a changed file must go unchanged before it's read, so a burst of writes,
+/// as editors saving a file make, is only read once
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Runs a command, then runs it again each time a file changes. Errors, such as those of
+/// a file saved midway through an edit, are printed without ending the watch
+fn watch<F>(
+    file: &Path,
+    mut run: F,
+) -> Result<bool, Box<dyn Error>>
+where
+    F: FnMut() -> Result<bool, Box<dyn Error>>,
+{
+    if file == Path::new("-") {
+        return Err("stdin can't be watched".into());
+    }
+    // modification times alone may be too coarse to tell writes within a second apart
+    let stamp = || -> Option<(SystemTime, u64)> {
+        let metadata = fs::metadata(file).ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
+    };
+    let mut seen = stamp();
+    loop {
+        if let Err(err) = run() {
+            eprintln!("error: {}", err.to_string().trim_end());
+        }
+        seen = settle(seen, stamp, thread::sleep);
+    }
+}
+
+/// Polls a stamp until it differs from the one `seen`, then until it stops changing,
+/// returning the stamp it settled on
+fn settle<T, S, W>(
+    seen: T,
+    mut stamp: S,
+    mut sleep: W,
+) -> T
+where
+    T: PartialEq,
+    S: FnMut() -> T,
+    W: FnMut(Duration),
+{
+    loop {
+        sleep(POLL);
+        if stamp() != seen {
+            break;
+        }
+    }
+    loop {
+        let seen = stamp();
+        sleep(DEBOUNCE);
+        if stamp() == seen {
+            return seen;
+        }
+    }
+}
+
+/// Loads strict JSON content, which simd-json indexes directly when available
+#[cfg(feature = "simd")]
+fn load_json(
+    file: &Path,
+    content: &str,
+    options: Options,
+) -> Result<Positions, Box<dyn Error>> {
+    from_str_using(&SimdJson, content, options)
+        .map_err(|err| format!("invalid JSON in {}: {}", file.display(), err).into())
+}
+
+/// Loads strict JSON content
+#[cfg(not(feature = "simd"))]
+fn load_json(
+    file: &Path,
+    content: &str,
+    options: Options,
+) -> Result<Positions, Box<dyn Error>> {
+    // YAML parsers are more forgiving than JSON ones, reject what JSON doesn't allow
+    serde_json::from_str::<serde_json::Value>(content)
+        .map_err(|err| format!("invalid JSON in {}: {}", file.display(), err))?;
+    Ok(from_str_with(content, options)?)
+}
+
+/// Appends a pointer argument to the --relative-to base, if any, so /containers/0 relative to
+/// /spec/template reads /spec/template/containers/0
+fn relative(
+    base: Option<&Pointer>,
+    pointer: String,
+) -> String {
+    match (base, Pointer::parse(&pointer)) {
+        (Some(base), Ok(parsed)) => parsed
+            .tokens()
+            .fold(base.clone(), |base, token| base.join(token))
+            .into(),
+        // invalid pointers are left as they are to be reported as such
+        _ => pointer,
+    }
+}
+
+/// Runs a command, returning whether anything was found
+fn run(
+    Opts {
+        output,
+        format,
+        color,
+        relative_to,
+        command,
+    }: Opts
+) -> Result<bool, Box<dyn Error>> {
+    let paint = color.paint(
+        io::stdout().is_terminal(),
+        env::var_os("NO_COLOR").is_some(),
+    );
+    let rebase = |pointer: String| relative(relative_to.as_ref(), pointer);
+    match command {
+        Command::Get {
+            file,
+            mut pointers,
+            pointers_from,
+            watch,
+        } => {
+            if let Some(path) = pointers_from {
+                let listed = fs::read_to_string(&path)
+                    .map_err(|err| format!("failed to read {}: {}", path.display(), err))?;
+                pointers.extend(
+                    listed
+                        .lines()
+                        .map(str::trim)
+                        .filter(|line| !line.is_empty())
+                        .map(String::from),
+                );
+            }
+            let pointers = pointers.into_iter().map(rebase).collect::<Vec<_>>();
+            let get = || {
+                let positions = load(&file, format)?;
+                let lookups = pointers
+                    .iter()
+                    .map(|pointer| {
+                        let position = positions.get(pointer);
+                        if position.is_none() {
+                            eprintln!("could not find {} in {}", pointer, file.display());
+                        }
+                        (pointer.clone(), position)
+                    })
+                    .collect::<Vec<_>>();
+                Ok(output.lookups(&file, &lookups, paint))
+            };
+            if watch {
+                self::watch(&file, get)
+            } else {
+                get()
+            }
+        }
+        Command::Dump { file, sort, prefix } => {
+            let positions = load(&file, format)?;
+            let mut entries = positions
+                .iter()
+                .filter(|(pointer, _)| {
+                    prefix
+                        .as_ref()
+                        .is_none_or(|prefix| pointer.starts_with(prefix.as_str()))
+                })
+                .collect::<Vec<_>>();
+            match sort {
+                // sequence items are listed in numeric order
+                Sort::Pointer => entries.sort_by(|(a, _), (b, _)| Pointer::compare(a, b)),
+                Sort::Position => entries.sort_by_key(|(_, Position { line, col })| (*line, *col)),
+            }
+            Ok(output.entries(&file, entries, paint))
+        }
+        Command::At { file, location } => {
+            let positions = load(&file, format)?;
+            match positions.enclosing(location.line, location.col) {
+                Some((pointer, position)) => {
+                    output.entry(&file, pointer, position, paint);
+                    Ok(true)
+                }
+                None => {
+                    eprintln!(
+                        "could not find a node at {}:{} in {}",
+                        location.line,
+                        location.col,
+                        file.display()
+                    );
+                    Ok(false)
+                }
+            }
+        }
+        Command::Find { file, pattern } => Ok(output.entries(
+            &file,
+            load(&file, format)?
+                .iter()
+                .filter(|(pointer, _)| pointer.contains(&pattern)),
+            paint,
+        )),
+        Command::Report {
+            file,
+            level,
+            findings,
+        } => {
+            let positions = load(&file, format)?;
+            let mut all = true;
+            let located = findings
+                .into_iter()
+                .map(|finding| Finding {
+                    pointer: rebase(finding.pointer),
+                    ..finding
+                })
+                .filter_map(|finding| {
+                    let position = positions.get(&finding.pointer);
+                    if position.is_none() {
+                        eprintln!("could not find {} in {}", finding.pointer, file.display());
+                        all = false;
+                    }
+                    position.map(|position| (finding, position))
+                })
+                .collect::<Vec<_>>();
+            output.findings(&file, level, &located);
+            Ok(all)
+        }
+        Command::Annotate {
+            file,
+            level,
+            findings,
+        } => {
+            let positions = load_with(&file, format, Options::default().keep_source(true))?;
+            let mut all = true;
+            let located = findings
+                .into_iter()
+                .map(|finding| Finding {
+                    pointer: rebase(finding.pointer),
+                    ..finding
+                })
+                .filter_map(|finding| {
+                    let position = positions.get(&finding.pointer);
+                    if position.is_none() {
+                        eprintln!("could not find {} in {}", finding.pointer, file.display());
+                        all = false;
+                    }
+                    position.map(|position| (finding, position))
+                })
+                .collect::<Vec<_>>();
+            output.annotations(&file, &positions, level, &located, paint);
+            Ok(all)
+        }
+        Command::Validate {
+            file,
+            mut required,
+            required_from,
+        } => {
+            if let Some(path) = required_from {
+                let listed = fs::read_to_string(&path)
+                    .map_err(|err| format!("failed to read {}: {}", path.display(), err))?;
+                for line in listed
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty())
+                {
+                    required.push(line.parse()?);
+                }
+            }
+            let positions = load(&file, format)?;
+            let requirements = required
+                .into_iter()
+                .map(|Required(requirement)| Requirement {
+                    pointer: rebase(requirement.pointer),
+                    ..requirement
+                })
+                .collect::<Vec<_>>();
+            let violations = positions.validate(&requirements);
+            let located = violations
+                .iter()
+                .filter_map(|violation| match &violation.nearest {
+                    Some((pointer, position)) => {
+                        let finding = Finding {
+                            pointer: pointer.clone(),
+                            message: violation.to_string(),
+                        };
+                        Some((finding, position))
+                    }
+                    None => {
+                        eprintln!("{} in {}", violation, file.display());
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+            output.findings(&file, Level::Error, &located);
+            Ok(violations.is_empty())
+        }
+        Command::Stats { file } => {
+            let positions = load(&file, format)?;
+            output.stats(&file, &positions.stats(), positions.duplicates());
+            Ok(true)
+        }
+        Command::Set {
+            file,
+            pointer,
+            value,
+            in_place,
+        } => {
+            let pointer = Pointer::parse(&rebase(pointer))?;
+            edit(&file, in_place, |editor| {
+                if editor.positions().contains(&pointer) {
+                    return editor.set(&pointer, &value);
+                }
+                // a missing pointer adds an entry to its parent
+                match (pointer.parent(), pointer.last_token()) {
+                    (Some(parent), Some(key)) => editor.insert(parent, key, &value),
+                    _ => editor.set(&pointer, &value),
+                }
+            })
+        }
+        Command::Unset {
+            file,
+            pointer,
+            in_place,
+        } => edit(&file, in_place, |editor| editor.remove(rebase(pointer))),
+    }
+}
+
+fn main() {
+    match run(Opts::from_args()) {
+        Ok(true) => (),
+        Ok(false) => exit(1),
+        Err(err) => {
+            eprintln!("error: {}", err.to_string().trim_end());
+            exit(2);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_terminals_without_no_color() {
+        let painted = |color: Color, terminal, no_color| color.paint(terminal, no_color).0;
+        assert!(painted(Color::Auto, true, false));
+        assert!(!painted(Color::Auto, true, true));
+        assert!(!painted(Color::Auto, false, false));
+        assert!(painted(Color::Always, false, true));
+        assert!(!painted(Color::Never, true, false));
+    }
+
+    #[test]
+    fn settles_on_the_last_of_a_burst_of_changes() {
+        let mut stamps = vec![1, 1, 2, 3, 4, 4, 4].into_iter();
+        let mut slept = Vec::new();
+        let settled = settle(
+            1,
+            || stamps.next().expect("polled past the last stamp"),
+            |duration| slept.push(duration),
+        );
+        assert_eq!(settled, 4);
+        assert_eq!(slept, vec![POLL, POLL, POLL, DEBOUNCE, DEBOUNCE]);
+        assert_eq!(stamps.next(), None);
+    }
+
+    #[test]
+    fn rebases_pointers_relative_to_a_base() -> Result<(), String> {
+        let base = Pointer::parse("/spec/template").map_err(|err| err.to_string())?;
+        assert_eq!(
+            relative(Some(&base), "/containers/0".into()),
+            "/spec/template/containers/0"
+        );
+        assert_eq!(relative(Some(&base), "".into()), "/spec/template");
+        assert_eq!(relative(Some(&base), "/a~1b".into()), "/spec/template/a~1b");
+        assert_eq!(relative(Some(&base), "containers".into()), "containers");
+        assert_eq!(relative(None, "/containers/0".into()), "/containers/0");
+        assert!(
+            Opts::from_iter_safe(&["lincolns", "--relative-to", "spec", "stats", "-"]).is_err()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,668 @@
+use lincolns::{Duplicate, NodeKind, Pointer, Position, Positions, Stats};
+use serde_json::{json, Value};
+use std::{path::Path, str::FromStr};
+
+/// Severity of a reported finding
+#[derive(Clone, Copy)]
+pub enum Level {
+    Error,
+    Warning,
+    Notice,
+}
+
+impl Level {
+    fn as_str(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Notice => "notice",
+        }
+    }
+
+    /// SARIF result level
+    fn sarif(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Notice => "note",
+        }
+    }
+
+    /// ANSI escape code of the color this level is printed in
+    fn color(self) -> &'static str {
+        match self {
+            Level::Error => "\x1b[1;31m",
+            Level::Warning => "\x1b[1;33m",
+            Level::Notice => "\x1b[1;36m",
+        }
+    }
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "error" => Ok(Level::Error),
+            "warning" => Ok(Level::Warning),
+            "notice" => Ok(Level::Notice),
+            other => Err(format!("unsupported level {}", other)),
+        }
+    }
+}
+
+/// A message to report at a JSON Pointer path, formatted as pointer:message
+///
+/// The pointer ends at the first `:`, so pointers containing one are written in their URI
+/// fragment form with it percent-encoded, as in `#/ports/8080%3A80:message`
+pub struct Finding {
+    pub pointer: String,
+    pub message: String,
+}
+
+impl FromStr for Finding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (pointer, message) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected pointer:message but found {}", s))?;
+        let pointer = Pointer::normalize(pointer).map_err(|err| {
+            format!(
+                "{} in {}. Write pointers containing : as URI fragments, e.g. #/a%3Ab",
+                err, s
+            )
+        })?;
+        Ok(Finding {
+            pointer: pointer.as_str().into(),
+            message: message.into(),
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Output {
+    Text,
+    Json,
+    Github,
+    Sarif,
+}
+
+impl FromStr for Output {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Output::Text),
+            "json" => Ok(Output::Json),
+            "github" => Ok(Output::Github),
+            "sarif" => Ok(Output::Sarif),
+            other => Err(format!("unsupported output {}", other)),
+        }
+    }
+}
+
+impl Output {
+    pub const NAMES: &'static [&'static str] = &["text", "json", "github", "sarif"];
+
+    /// Prints the results of looking up pointers, returning whether all were found
+    pub fn lookups(
+        self,
+        file: &Path,
+        lookups: &[(String, Option<&Position>)],
+        paint: Paint,
+    ) -> bool {
+        match (self, lookups) {
+            (Output::Text, [(_, Some(position))]) => {
+                println!("{}", paint.paint(Paint::POSITION, &position.to_string()))
+            }
+            (Output::Json, _) => println!("{}", lookups_json(lookups)),
+            _ => {
+                self.entries(
+                    file,
+                    lookups
+                        .iter()
+                        .filter_map(|(pointer, position)| position.map(|pos| (pointer, pos))),
+                    paint,
+                );
+            }
+        }
+        lookups.iter().all(|(_, position)| position.is_some())
+    }
+
+    /// Prints a list of results, returning whether there were any
+    pub fn entries<'a>(
+        self,
+        file: &Path,
+        entries: impl IntoIterator<Item = (&'a String, &'a Position)>,
+        paint: Paint,
+    ) -> bool {
+        let entries = entries.into_iter().collect::<Vec<_>>();
+        match self {
+            Output::Text => {
+                for (pointer, position) in &entries {
+                    println!(
+                        "{} {}",
+                        paint.paint(Paint::POINTER, pointer),
+                        paint.paint(Paint::POSITION, &position.to_string())
+                    );
+                }
+            }
+            Output::Json => println!(
+                "{}",
+                Value::Array(
+                    entries
+                        .iter()
+                        .map(|(pointer, position)| to_json(pointer, position))
+                        .collect()
+                )
+            ),
+            Output::Github => {
+                for (pointer, position) in &entries {
+                    println!("{}", annotation(Level::Notice, file, position, pointer));
+                }
+            }
+            Output::Sarif => println!(
+                "{}",
+                sarif(
+                    Level::Notice,
+                    file,
+                    entries.iter().map(|(pointer, position)| (
+                        pointer.as_str(),
+                        pointer.as_str(),
+                        *position
+                    ))
+                )
+            ),
+        }
+        !entries.is_empty()
+    }
+
+    /// Prints a single result
+    pub fn entry(
+        self,
+        file: &Path,
+        pointer: &str,
+        position: &Position,
+        paint: Paint,
+    ) {
+        match self {
+            Output::Text => println!("{}", paint.paint(Paint::POINTER, pointer)),
+            Output::Json => println!("{}", to_json(pointer, position)),
+            Output::Github => println!("{}", annotation(Level::Notice, file, position, pointer)),
+            Output::Sarif => println!(
+                "{}",
+                sarif(Level::Notice, file, Some((pointer, pointer, position)))
+            ),
+        }
+    }
+
+    /// Prints findings with the source lines they refer to, falling back on
+    /// [`findings`](#method.findings) for machine-readable outputs
+    pub fn annotations(
+        self,
+        file: &Path,
+        positions: &Positions,
+        level: Level,
+        findings: &[(Finding, &Position)],
+        paint: Paint,
+    ) {
+        match self {
+            Output::Text => {
+                let snippets = findings
+                    .iter()
+                    .map(|finding| snippet(file, positions, level, finding, paint))
+                    .collect::<Vec<_>>();
+                if !snippets.is_empty() {
+                    println!("{}", snippets.join("\n\n"));
+                }
+            }
+            _ => self.findings(file, level, findings),
+        }
+    }
+
+    /// Prints the statistics of a file, with the duplicate keys found as warnings
+    /// for outputs which report findings
+    pub fn stats(
+        self,
+        file: &Path,
+        stats: &Stats,
+        duplicates: &[Duplicate],
+    ) {
+        // kinds are listed in a fixed order, leaving out those with no nodes
+        let kinds = [
+            NodeKind::Mapping,
+            NodeKind::Sequence,
+            NodeKind::String,
+            NodeKind::Integer,
+            NodeKind::Float,
+            NodeKind::Bool,
+            NodeKind::Null,
+            NodeKind::Alias,
+        ]
+        .iter()
+        .filter_map(|kind| Some((kind.to_string(), *stats.kinds.get(kind)?)))
+        .collect::<Vec<_>>();
+        match self {
+            Output::Text => {
+                println!("nodes: {}", stats.nodes);
+                for (kind, count) in &kinds {
+                    println!("  {}: {}", kind, count);
+                }
+                println!("max depth: {}", stats.max_depth);
+                if let Some(longest) = &stats.longest_pointer {
+                    println!("longest pointer: {}", longest);
+                }
+                println!("lines: {}", stats.lines);
+                println!("duplicate keys: {}", duplicates.len());
+                for Duplicate {
+                    pointer, second, ..
+                } in duplicates
+                {
+                    println!("  {} {}", pointer, second);
+                }
+            }
+            Output::Json => println!(
+                "{}",
+                json!({
+                    "nodes": stats.nodes,
+                    "kinds": kinds
+                        .into_iter()
+                        .map(|(kind, count)| (kind, Value::from(count)))
+                        .collect::<serde_json::Map<_, _>>(),
+                    "max_depth": stats.max_depth,
+                    "longest_pointer": stats.longest_pointer,
+                    "lines": stats.lines,
+                    "duplicates": duplicates
+                        .iter()
+                        .map(|Duplicate { pointer, first, second }| {
+                            json!({
+                                "pointer": pointer,
+                                "first": { "line": first.line, "col": first.col },
+                                "second": { "line": second.line, "col": second.col }
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                })
+            ),
+            _ => {
+                let findings = duplicates
+                    .iter()
+                    .map(|duplicate| {
+                        let finding = Finding {
+                            pointer: duplicate.pointer.clone(),
+                            message: format!(
+                                "duplicate key {}, first defined at {}",
+                                duplicate.pointer, duplicate.first
+                            ),
+                        };
+                        (finding, &duplicate.second)
+                    })
+                    .collect::<Vec<_>>();
+                self.findings(file, Level::Warning, &findings)
+            }
+        }
+    }
+
+    /// Prints findings at the positions they were resolved to
+    pub fn findings(
+        self,
+        file: &Path,
+        level: Level,
+        findings: &[(Finding, &Position)],
+    ) {
+        match self {
+            Output::Text => {
+                for (Finding { pointer, message }, Position { line, col }) in findings {
+                    println!(
+                        "{}:{}:{}: {}: {} ({})",
+                        file.display(),
+                        line,
+                        col,
+                        level.as_str(),
+                        message,
+                        pointer
+                    );
+                }
+            }
+            Output::Json => println!("{}", findings_json(level, findings)),
+            Output::Github => {
+                for (Finding { message, .. }, position) in findings {
+                    println!("{}", annotation(level, file, position, message));
+                }
+            }
+            Output::Sarif => println!(
+                "{}",
+                sarif(
+                    level,
+                    file,
+                    findings
+                        .iter()
+                        .map(|(Finding { pointer, message }, position)| {
+                            (pointer.as_str(), message.as_str(), *position)
+                        })
+                )
+            ),
+        }
+    }
+}
+
+/// Styles text in ANSI colors when enabled
+#[derive(Clone, Copy)]
+pub struct Paint(pub bool);
+
+impl Paint {
+    const GUTTER: &'static str = "\x1b[1;34m";
+    const POINTER: &'static str = "\x1b[1m";
+    const POSITION: &'static str = "\x1b[32m";
+
+    fn paint(
+        self,
+        color: &str,
+        text: &str,
+    ) -> String {
+        if self.0 {
+            format!("{}{}\x1b[0m", color, text)
+        } else {
+            text.into()
+        }
+    }
+}
+
+/// Renders the source line a finding refers to, underlining its value
+///
+/// ```text
+/// error: avoid latest tags
+///  --> deploy.yml:2:2 (/image)
+///   |
+/// 2 | image: nginx:latest
+///   |        ^~~~~~~~~~~~ avoid latest tags
+/// ```
+fn snippet(
+    file: &Path,
+    positions: &Positions,
+    level: Level,
+    (Finding { pointer, message }, position): &(Finding, &Position),
+    paint: Paint,
+) -> String {
+    // values of block collections start on the lines after their keys, which are underlined instead
+    let span = positions
+        .span(pointer)
+        .filter(|span| span.start.line == position.line);
+    let line = position.line;
+    let text = positions.line_text(line).unwrap_or_default();
+    let start = span.map_or(position.col, |span| span.start.col);
+    let end = match span {
+        Some(span) if span.end.line == line => span.end.col,
+        _ => text.chars().count(),
+    };
+    let indent = text
+        .chars()
+        .take(start)
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect::<String>();
+    let underline = format!("^{}", "~".repeat(end.saturating_sub(start + 1)));
+    let number = line.to_string();
+    let gutter = " ".repeat(number.len());
+    format!(
+        "{}: {}\n{}{} {}:{} ({})\n{}\n{} {}\n{}{} {}",
+        paint.paint(level.color(), level.as_str()),
+        message,
+        gutter,
+        paint.paint(Paint::GUTTER, "-->"),
+        file.display(),
+        paint.paint(
+            Paint::POSITION,
+            &format!("{}:{}", position.line, position.col)
+        ),
+        paint.paint(Paint::POINTER, pointer),
+        paint.paint(Paint::GUTTER, &format!("{} |", gutter)),
+        paint.paint(Paint::GUTTER, &format!("{} |", number)),
+        text,
+        paint.paint(Paint::GUTTER, &format!("{} |", gutter)),
+        indent,
+        paint.paint(level.color(), &format!("{} {}", underline, message)),
+    )
+}
+
+fn to_json(
+    pointer: &str,
+    Position { line, col }: &Position,
+) -> Value {
+    json!({ "pointer": pointer, "line": line, "col": col })
+}
+
+/// Formats the results of looking up pointers, a single found pointer as an object and
+/// others as an array in which missing pointers have null positions
+fn lookups_json(lookups: &[(String, Option<&Position>)]) -> Value {
+    match lookups {
+        [(pointer, Some(position))] => to_json(pointer, position),
+        _ => Value::Array(
+            lookups
+                .iter()
+                .map(|(pointer, position)| match position {
+                    Some(position) => to_json(pointer, position),
+                    None => json!({ "pointer": pointer, "line": null, "col": null }),
+                })
+                .collect(),
+        ),
+    }
+}
+
+/// Formats findings at the positions they were resolved to
+fn findings_json(
+    level: Level,
+    findings: &[(Finding, &Position)],
+) -> Value {
+    Value::Array(
+        findings
+            .iter()
+            .map(|(Finding { pointer, message }, Position { line, col })| {
+                json!({
+                    "pointer": pointer,
+                    "line": line,
+                    "col": col,
+                    "level": level.as_str(),
+                    "message": message
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Formats a GitHub Actions workflow command annotating a position
+///
+/// See [workflow commands](https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions)
+fn annotation(
+    level: Level,
+    file: &Path,
+    Position { line, col }: &Position,
+    message: &str,
+) -> String {
+    format!(
+        "::{} file={},line={},col={}::{}",
+        level.as_str(),
+        escape_property(&file.display().to_string()),
+        line,
+        // annotation columns start at 1
+        col + 1,
+        escape_data(message)
+    )
+}
+
+/// Formats a [SARIF](https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html) log
+/// of a run's results, given as the pointer, message, and position of each
+fn sarif<'a>(
+    level: Level,
+    file: &Path,
+    results: impl IntoIterator<Item = (&'a str, &'a str, &'a Position)>,
+) -> Value {
+    // SARIF locations are URI references, which always separate segments with /
+    let uri = file.display().to_string().replace('\\', "/");
+    let results = results
+        .into_iter()
+        .map(|(pointer, message, Position { line, col })| {
+            json!({
+                "level": level.sarif(),
+                "message": { "text": message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": uri },
+                        // SARIF columns start at 1
+                        "region": { "startLine": line, "startColumn": col + 1 }
+                    },
+                    "logicalLocations": [{ "fullyQualifiedName": pointer }]
+                }]
+            })
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "lincolns",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://github.com/softprops/lincolns"
+                }
+            },
+            // positions count chars, as SARIF's unicodeCodePoints do
+            "columnKind": "unicodeCodePoints",
+            "results": results
+        }]
+    })
+}
+
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(property: &str) -> String {
+    escape_data(property)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_findings() -> Result<(), String> {
+        let finding = "/spec/image:avoid latest: use a digest".parse::<Finding>()?;
+        assert_eq!(finding.pointer, "/spec/image");
+        assert_eq!(finding.message, "avoid latest: use a digest");
+        let finding = "#/ports/8080%3A80:too open".parse::<Finding>()?;
+        assert_eq!(finding.pointer, "/ports/8080:80");
+        assert_eq!(finding.message, "too open");
+        assert!(":whole document".parse::<Finding>().is_ok());
+        assert!("no message".parse::<Finding>().is_err());
+        match "ports/8080:80:too open".parse::<Finding>() {
+            Err(err) => assert!(err.contains("must be empty or start with /"), "{}", err),
+            Ok(finding) => panic!("parsed {}", finding.pointer),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn escapes_github_commands() {
+        assert_eq!(escape_data("100%\r\nnext: a, b"), "100%25%0D%0Anext: a, b");
+        assert_eq!(escape_property("dir:a,b%\n"), "dir%3Aa%2Cb%25%0A");
+        assert_eq!(
+            annotation(
+                Level::Error,
+                Path::new("c:,d.yml"),
+                &Position { line: 2, col: 0 },
+                "bad: 50%\nvalue"
+            ),
+            "::error file=c%3A%2Cd.yml,line=2,col=1::bad: 50%25%0Avalue"
+        );
+    }
+
+    #[test]
+    fn formats_sarif_logs() {
+        let position = Position { line: 2, col: 7 };
+        assert_eq!(
+            sarif(
+                Level::Notice,
+                Path::new("deploy\\web.yml"),
+                Some(("/image", "avoid latest tags", &position))
+            ),
+            json!({
+                "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+                "version": "2.1.0",
+                "runs": [{
+                    "tool": {
+                        "driver": {
+                            "name": "lincolns",
+                            "version": env!("CARGO_PKG_VERSION"),
+                            "informationUri": "https://github.com/softprops/lincolns"
+                        }
+                    },
+                    "columnKind": "unicodeCodePoints",
+                    "results": [{
+                        "level": "note",
+                        "message": { "text": "avoid latest tags" },
+                        "locations": [{
+                            "physicalLocation": {
+                                "artifactLocation": { "uri": "deploy/web.yml" },
+                                "region": { "startLine": 2, "startColumn": 8 }
+                            },
+                            "logicalLocations": [{ "fullyQualifiedName": "/image" }]
+                        }]
+                    }]
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn paints_when_enabled() {
+        assert_eq!(
+            Paint(true).paint(Paint::POSITION, "3:6"),
+            "\x1b[32m3:6\x1b[0m"
+        );
+        assert_eq!(Paint(false).paint(Paint::POSITION, "3:6"), "3:6");
+    }
+
+    #[test]
+    fn formats_lookups_as_json() {
+        let position = Position { line: 3, col: 6 };
+        assert_eq!(
+            lookups_json(&[("/a".into(), Some(&position))]),
+            json!({ "pointer": "/a", "line": 3, "col": 6 })
+        );
+        assert_eq!(
+            lookups_json(&[("/a".into(), Some(&position)), ("/b".into(), None)]),
+            json!([
+                { "pointer": "/a", "line": 3, "col": 6 },
+                { "pointer": "/b", "line": null, "col": null }
+            ])
+        );
+        assert_eq!(
+            lookups_json(&[("/b".into(), None)]),
+            json!([{ "pointer": "/b", "line": null, "col": null }])
+        );
+    }
+
+    #[test]
+    fn formats_findings_as_json() {
+        let finding = Finding {
+            pointer: "/image".into(),
+            message: "avoid latest tags".into(),
+        };
+        assert_eq!(
+            findings_json(Level::Warning, &[(finding, &Position { line: 2, col: 7 })]),
+            json!([{
+                "pointer": "/image",
+                "line": 2,
+                "col": 7,
+                "level": "warning",
+                "message": "avoid latest tags"
+            }])
+        );
+    }
+}
@@ -0,0 +1,566 @@
+use crate::{
+    backend::Event, load, Backend, ColumnUnit, Error, Options, Positions, Result, ScalarStyle,
+};
+use std::{convert::TryFrom, ops::Range};
+
+/// Load a lookup table of byte offsets from a [CBOR](https://cbor.io) document
+///
+/// Binary content has no lines, so every [`Position`](struct.Position.html) is on line 1 and its
+/// `col` is the byte offset of the node, with [`Span::bytes`](struct.Span.html#structfield.bytes)
+/// holding the bytes each node occupies. Sequences of concatenated items are loaded as documents.
+/// Scalars are tagged as the YAML core schema type they were encoded as,
+/// with byte strings listed in hex and tagged `!!binary`
+///
+/// ```rust,edition2018
+/// use lincolns::{from_cbor_slice, Position};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// // {"a": [1, 1000]}
+/// let positions = from_cbor_slice(&[0xa1, 0x61, 0x61, 0x82, 0x01, 0x19, 0x03, 0xe8])?;
+/// assert_eq!(positions.get("/a/1"), Some(&Position { line: 1, col: 5 }));
+/// assert_eq!(positions.span("/a/1").map(|span| span.bytes.clone()), Some(5..8));
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_cbor_slice(bytes: &[u8]) -> Result<Positions> {
+    let mut decoder = Decoder::new(bytes);
+    while decoder.pos < bytes.len() {
+        decoder.document(Decoder::cbor)?;
+    }
+    decoder.load()
+}
+
+/// Load a lookup table of byte offsets from a [MessagePack](https://msgpack.org) document
+///
+/// Positions are reported as they are by [`from_cbor_slice`](fn.from_cbor_slice.html),
+/// with extension types listed in hex and tagged `!!binary`
+///
+/// ```rust,edition2018
+/// use lincolns::{from_msgpack_slice, NodeKind, Position};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// // {"a": "bc"}
+/// let positions = from_msgpack_slice(&[0x81, 0xa1, 0x61, 0xa2, 0x62, 0x63])?;
+/// assert_eq!(positions.get_value("/a"), Some(("bc", &Position { line: 1, col: 1 })));
+/// assert_eq!(positions.kind("/a"), Some(NodeKind::String));
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_msgpack_slice(bytes: &[u8]) -> Result<Positions> {
+    let mut decoder = Decoder::new(bytes);
+    while decoder.pos < bytes.len() {
+        decoder.document(Decoder::msgpack)?;
+    }
+    decoder.load()
+}
+
+/// Replays events decoded ahead of time
+struct Replay(Vec<(Event, usize)>);
+
+impl Backend for Replay {
+    fn parse(
+        &self,
+        _: &str,
+        sink: &mut dyn FnMut(Event, usize),
+    ) -> Result<()> {
+        for (event, offset) in &self.0 {
+            sink(event.clone(), *offset);
+        }
+        Ok(())
+    }
+}
+
+/// How deep items may nest, matching the limit yaml-rust places on flow collections
+const MAX_DEPTH: usize = u8::MAX as usize;
+
+struct Decoder<'b> {
+    bytes: &'b [u8],
+    pos: usize,
+    events: Vec<(Event, usize)>,
+    scalars: Vec<Range<usize>>,
+    /// How many collections and tags enclose the item being decoded
+    depth: usize,
+}
+
+impl<'b> Decoder<'b> {
+    fn new(bytes: &'b [u8]) -> Self {
+        Decoder {
+            bytes,
+            pos: 0,
+            events: Vec::new(),
+            scalars: Vec::new(),
+            depth: 0,
+        }
+    }
+
+    /// Indexes the decoded events against a single line of text standing in for the bytes,
+    /// marked so that each scalar spans exactly the bytes it was decoded from
+    fn load(self) -> Result<Positions> {
+        let mut text = vec![b' '; self.bytes.len()];
+        let mut events = self.events;
+        let mut scalars = self.scalars.into_iter();
+        for (event, offset) in &mut events {
+            if let Event::Scalar { value, style, .. } = event {
+                let bytes = scalars.next().unwrap_or(*offset..*offset);
+                if bytes.len() > 1 {
+                    text[bytes.start] = b'"';
+                    text[bytes.end - 1] = b'"';
+                    *style = ScalarStyle::DoubleQuoted;
+                } else if let Some(first) = value.bytes().next().filter(u8::is_ascii_graphic) {
+                    text[bytes.start] = first;
+                }
+            }
+        }
+        let text = String::from_utf8(text).expect("stand in text is ascii");
+        load(
+            &Replay(events),
+            &text,
+            Options::default().column_unit(ColumnUnit::Utf8),
+            Positions::default(),
+        )
+    }
+
+    fn error(
+        &self,
+        reason: &str,
+    ) -> Error {
+        Error::Backend(format!("{} at byte {}", reason, self.pos).into())
+    }
+
+    fn document(
+        &mut self,
+        item: fn(&mut Self) -> Result<()>,
+    ) -> Result<()> {
+        self.events.push((Event::DocumentStart, self.pos));
+        item(self)?;
+        self.events.push((Event::DocumentEnd, self.pos));
+        Ok(())
+    }
+
+    /// Decodes items nested within another, failing rather than overflowing the stack
+    /// on deeply nested content
+    fn nested<F>(
+        &mut self,
+        decode: F,
+    ) -> Result<()>
+    where
+        F: FnOnce(&mut Self) -> Result<()>,
+    {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("recursion limit exceeded"));
+        }
+        self.depth += 1;
+        decode(self)?;
+        self.depth -= 1;
+        Ok(())
+    }
+
+    fn take(
+        &mut self,
+        len: u64,
+    ) -> Result<&'b [u8]> {
+        let bytes = usize::try_from(len)
+            .ok()
+            .and_then(|len| self.bytes.get(self.pos..self.pos.checked_add(len)?))
+            .ok_or_else(|| self.error("unexpected end of content"))?;
+        self.pos += bytes.len();
+        Ok(bytes)
+    }
+
+    /// Reads a big endian unsigned integer of `len` bytes
+    fn uint(
+        &mut self,
+        len: u64,
+    ) -> Result<u64> {
+        Ok(self
+            .take(len)?
+            .iter()
+            .fold(0, |value, byte| value << 8 | u64::from(*byte)))
+    }
+
+    /// Reads a big endian signed integer of `len` bytes
+    fn int(
+        &mut self,
+        len: u64,
+    ) -> Result<i64> {
+        let shift = 64 - 8 * len;
+        Ok(((self.uint(len)? << shift) as i64) >> shift)
+    }
+
+    /// Records a scalar which started at `start` and ends at the current byte
+    fn scalar(
+        &mut self,
+        value: String,
+        tag: &str,
+        start: usize,
+    ) {
+        self.events.push((
+            Event::Scalar {
+                value,
+                style: ScalarStyle::Plain,
+                tag: Some(tag.into()),
+            },
+            start,
+        ));
+        self.scalars.push(start..self.pos);
+    }
+
+    fn text(
+        &self,
+        bytes: &[u8],
+    ) -> Result<String> {
+        String::from_utf8(bytes.to_vec()).map_err(|_| self.error("invalid utf8 string"))
+    }
+
+    fn cbor(&mut self) -> Result<()> {
+        let start = self.pos;
+        let initial = self.uint(1)?;
+        let (major, info) = (initial >> 5, initial & 0x1f);
+        let argument = match info {
+            0..=23 => Some(info),
+            24..=27 => Some(self.uint(1 << (info - 24))?),
+            31 if major >= 2 => None,
+            _ => return Err(self.error("invalid argument")),
+        };
+        match (major, argument) {
+            (0, Some(value)) => self.scalar(value.to_string(), "!!int", start),
+            (1, Some(value)) => self.scalar((-1 - i128::from(value)).to_string(), "!!int", start),
+            (2, _) => {
+                let bytes = self.cbor_string(major, argument)?;
+                self.scalar(hex(&bytes), "!!binary", start);
+            }
+            (3, _) => {
+                let bytes = self.cbor_string(major, argument)?;
+                let value = self.text(&bytes)?;
+                self.scalar(value, "!!str", start);
+            }
+            (4, _) | (5, _) => {
+                self.events.push(match major {
+                    4 => (Event::SequenceStart { anchor: 0 }, start),
+                    _ => (Event::MappingStart { anchor: 0 }, start),
+                });
+                let per_item = if major == 4 { 1 } else { 2 };
+                self.nested(|decoder| {
+                    match argument {
+                        Some(len) => {
+                            for _ in 0..len.saturating_mul(per_item) {
+                                decoder.cbor()?;
+                            }
+                        }
+                        None => {
+                            while !decoder.cbor_break()? {
+                                decoder.cbor()?;
+                            }
+                        }
+                    }
+                    Ok(())
+                })?;
+                let end = if major == 4 {
+                    Event::SequenceEnd
+                } else {
+                    Event::MappingEnd
+                };
+                self.events.push((end, self.pos));
+            }
+            // tags annotate the item that follows them, which is indexed in their place
+            (6, _) => self.nested(Self::cbor)?,
+            (7, _) => match info {
+                20 | 21 => self.scalar((info == 21).to_string(), "!!bool", start),
+                22 | 23 => self.scalar("null".into(), "!!null", start),
+                25 => {
+                    let bits = self.bytes[start + 1..self.pos]
+                        .iter()
+                        .fold(0, |value, byte| value << 8 | u16::from(*byte));
+                    self.scalar(float(half(bits)), "!!float", start)
+                }
+                26 => {
+                    let value = f32::from_bits(argument.unwrap_or_default() as u32);
+                    self.scalar(float(f64::from(value)), "!!float", start)
+                }
+                27 => {
+                    let value = f64::from_bits(argument.unwrap_or_default());
+                    self.scalar(float(value), "!!float", start)
+                }
+                31 => return Err(self.error("unexpected break")),
+                _ => self.scalar(argument.unwrap_or_default().to_string(), "!!int", start),
+            },
+            _ => return Err(self.error("invalid argument")),
+        }
+        Ok(())
+    }
+
+    /// Consumes the break ending an indefinite length item, if it's next
+    fn cbor_break(&mut self) -> Result<bool> {
+        match self.bytes.get(self.pos) {
+            Some(0xff) => {
+                self.pos += 1;
+                Ok(true)
+            }
+            Some(_) => Ok(false),
+            None => Err(self.error("unexpected end of content")),
+        }
+    }
+
+    /// Reads a byte or text string, joining the chunks of indefinite length strings
+    fn cbor_string(
+        &mut self,
+        major: u64,
+        argument: Option<u64>,
+    ) -> Result<Vec<u8>> {
+        if let Some(len) = argument {
+            return Ok(self.take(len)?.to_vec());
+        }
+        let mut bytes = Vec::new();
+        while !self.cbor_break()? {
+            let initial = self.uint(1)?;
+            let info = initial & 0x1f;
+            if initial >> 5 != major || info > 27 {
+                return Err(self.error("invalid string chunk"));
+            }
+            let len = match info {
+                0..=23 => info,
+                _ => self.uint(1 << (info - 24))?,
+            };
+            bytes.extend_from_slice(self.take(len)?);
+        }
+        Ok(bytes)
+    }
+
+    fn msgpack(&mut self) -> Result<()> {
+        let start = self.pos;
+        let marker = self.uint(1)?;
+        match marker {
+            0x00..=0x7f => self.scalar(marker.to_string(), "!!int", start),
+            0x80..=0x8f => self.msgpack_map(marker & 0x0f, start)?,
+            0x90..=0x9f => self.msgpack_seq(marker & 0x0f, start)?,
+            0xa0..=0xbf => self.msgpack_str(marker & 0x1f, start)?,
+            0xc0 => self.scalar("null".into(), "!!null", start),
+            0xc2 | 0xc3 => self.scalar((marker == 0xc3).to_string(), "!!bool", start),
+            0xc4..=0xc6 => {
+                let len = self.uint(1 << (marker - 0xc4))?;
+                let bytes = self.take(len)?;
+                self.scalar(hex(bytes), "!!binary", start);
+            }
+            0xc7..=0xc9 | 0xd4..=0xd8 => {
+                let len = match marker {
+                    0xc7..=0xc9 => self.uint(1 << (marker - 0xc7))?,
+                    _ => 1 << (marker - 0xd4),
+                };
+                // the extension type precedes its data
+                let bytes = self.take(len + 1)?;
+                self.scalar(hex(&bytes[1..]), "!!binary", start);
+            }
+            0xca => {
+                let value = f32::from_bits(self.uint(4)? as u32);
+                self.scalar(float(f64::from(value)), "!!float", start)
+            }
+            0xcb => {
+                let value = f64::from_bits(self.uint(8)?);
+                self.scalar(float(value), "!!float", start)
+            }
+            0xcc..=0xcf => {
+                let value = self.uint(1 << (marker - 0xcc))?;
+                self.scalar(value.to_string(), "!!int", start)
+            }
+            0xd0..=0xd3 => {
+                let value = self.int(1 << (marker - 0xd0))?;
+                self.scalar(value.to_string(), "!!int", start)
+            }
+            0xd9..=0xdb => {
+                let len = self.uint(1 << (marker - 0xd9))?;
+                self.msgpack_str(len, start)?
+            }
+            0xdc | 0xdd => {
+                let len = self.uint(2 << (marker - 0xdc))?;
+                self.msgpack_seq(len, start)?
+            }
+            0xde | 0xdf => {
+                let len = self.uint(2 << (marker - 0xde))?;
+                self.msgpack_map(len, start)?
+            }
+            0xe0..=0xff => self.scalar((marker as u8 as i8).to_string(), "!!int", start),
+            _ => return Err(self.error("invalid marker")),
+        }
+        Ok(())
+    }
+
+    fn msgpack_str(
+        &mut self,
+        len: u64,
+        start: usize,
+    ) -> Result<()> {
+        let bytes = self.take(len)?;
+        let value = self.text(bytes)?;
+        self.scalar(value, "!!str", start);
+        Ok(())
+    }
+
+    fn msgpack_seq(
+        &mut self,
+        len: u64,
+        start: usize,
+    ) -> Result<()> {
+        self.events
+            .push((Event::SequenceStart { anchor: 0 }, start));
+        self.nested(|decoder| {
+            for _ in 0..len {
+                decoder.msgpack()?;
+            }
+            Ok(())
+        })?;
+        self.events.push((Event::SequenceEnd, self.pos));
+        Ok(())
+    }
+
+    fn msgpack_map(
+        &mut self,
+        len: u64,
+        start: usize,
+    ) -> Result<()> {
+        self.events.push((Event::MappingStart { anchor: 0 }, start));
+        self.nested(|decoder| {
+            for _ in 0..len.saturating_mul(2) {
+                decoder.msgpack()?;
+            }
+            Ok(())
+        })?;
+        self.events.push((Event::MappingEnd, self.pos));
+        Ok(())
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Formats a float as YAML would
+fn float(value: f64) -> String {
+    if value.is_nan() {
+        ".nan".into()
+    } else if value.is_infinite() {
+        if value > 0.0 { ".inf" } else { "-.inf" }.into()
+    } else {
+        format!("{:?}", value)
+    }
+}
+
+/// Widens an IEEE 754 half precision float
+fn half(bits: u16) -> f64 {
+    let sign = if bits >> 15 == 1 { -1.0 } else { 1.0 };
+    let exponent = i32::from((bits >> 10) & 0x1f);
+    let fraction = f64::from(bits & 0x3ff);
+    sign * match exponent {
+        0 => fraction * 2f64.powi(-24),
+        0x1f if fraction == 0.0 => f64::INFINITY,
+        0x1f => f64::NAN,
+        _ => (1.0 + fraction / 1024.0) * 2f64.powi(exponent - 15),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NodeKind, Position};
+
+    #[test]
+    fn indexes_cbor() -> Result<()> {
+        // [{"k": h'0102'}, -500, 1.5, true, null, _ ["a", ""]]
+        let bytes = [
+            0x86, 0xa1, 0x61, 0x6b, 0x42, 0x01, 0x02, 0x39, 0x01, 0xf3, 0xf9, 0x3e, 0x00, 0xf5,
+            0xf6, 0x9f, 0x61, 0x61, 0x60, 0xff,
+        ];
+        let positions = from_cbor_slice(&bytes)?;
+        assert_eq!(
+            positions.get_value("/0/k"),
+            Some(("0102", &Position { line: 1, col: 2 }))
+        );
+        assert_eq!(positions.tag("/0/k"), Some("!!binary"));
+        assert_eq!(positions.kind("/0/k"), Some(NodeKind::String));
+        assert_eq!(
+            positions.get_value("/1").map(|(value, _)| value),
+            Some("-500")
+        );
+        assert_eq!(positions.kind("/1"), Some(NodeKind::Integer));
+        assert_eq!(
+            positions.span("/1").map(|span| span.bytes.clone()),
+            Some(7..10)
+        );
+        assert_eq!(
+            positions.get_value("/2").map(|(value, _)| value),
+            Some("1.5")
+        );
+        assert_eq!(positions.kind("/3"), Some(NodeKind::Bool));
+        assert_eq!(positions.kind("/4"), Some(NodeKind::Null));
+        assert_eq!(positions.kind("/5/1"), Some(NodeKind::String));
+        assert_eq!(positions.get("/5/1"), Some(&Position { line: 1, col: 18 }));
+        assert_eq!(
+            positions.span("/0").map(|span| span.bytes.clone()),
+            Some(1..7)
+        );
+        assert!(matches!(
+            from_cbor_slice(&[0x82, 0x01]),
+            Err(Error::Backend(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn indexes_msgpack() -> Result<()> {
+        // {"a": [-1, 300, 2.5], "b": {}}
+        let bytes = [
+            0x82, 0xa1, 0x61, 0x93, 0xff, 0xcd, 0x01, 0x2c, 0xcb, 0x40, 0x04, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0xa1, 0x62, 0x80,
+        ];
+        let positions = from_msgpack_slice(&bytes)?;
+        assert_eq!(
+            positions.get_value("/a/0").map(|(value, _)| value),
+            Some("-1")
+        );
+        assert_eq!(
+            positions.get_value("/a/1"),
+            Some(("300", &Position { line: 1, col: 5 }))
+        );
+        assert_eq!(
+            positions.span("/a/2").map(|span| span.bytes.clone()),
+            Some(8..17)
+        );
+        assert_eq!(
+            positions.get_value("/a/2").map(|(value, _)| value),
+            Some("2.5")
+        );
+        assert_eq!(positions.kind("/b"), Some(NodeKind::Mapping));
+        assert_eq!(positions.get("/b"), Some(&Position { line: 1, col: 17 }));
+        assert!(matches!(
+            from_msgpack_slice(&[0xc1]),
+            Err(Error::Backend(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn limits_nesting() -> Result<()> {
+        let nested = |open: u8, depth: usize| {
+            let mut bytes = vec![open; depth];
+            bytes.push(0x01);
+            bytes
+        };
+        for (open, decode) in [
+            (0x81, from_cbor_slice as fn(&[u8]) -> Result<Positions>),
+            (0x91, from_msgpack_slice),
+        ] {
+            let positions = decode(&nested(open, MAX_DEPTH))?;
+            assert_eq!(positions.len(), MAX_DEPTH);
+            match decode(&nested(open, 100_000)) {
+                Err(err @ Error::Backend(_)) => assert_eq!(
+                    err.to_string().trim_end(),
+                    "recursion limit exceeded at byte 256"
+                ),
+                other => panic!(
+                    "expected a recursion limit error but found {:?}",
+                    other.err()
+                ),
+            }
+        }
+        Ok(())
+    }
+}
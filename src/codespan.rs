@@ -0,0 +1,65 @@
+use crate::Positions;
+use codespan_reporting::{
+    diagnostic::{Diagnostic, Label, Severity},
+    files::SimpleFile,
+};
+use std::fmt::Display;
+
+impl Positions {
+    /// Builds a [codespan-reporting](https://docs.rs/codespan-reporting) diagnostic
+    /// with a primary label under the value a JSON Pointer path refers to
+    ///
+    /// Labels are byte spans into the loaded content, which may be retained
+    /// for rendering with [`Options::keep_source`](struct.Options.html#method.keep_source)
+    /// and [`codespan_file`](#method.codespan_file)
+    pub fn codespan_diagnostic<P, F, M>(
+        &self,
+        ptr: P,
+        file_id: F,
+        severity: Severity,
+        message: M,
+    ) -> Option<Diagnostic<F>>
+    where
+        P: AsRef<str>,
+        M: Display,
+    {
+        let ptr = ptr.as_ref();
+        self.span(ptr).map(|span| {
+            Diagnostic::new(severity)
+                .with_message(message)
+                .with_labels(vec![
+                    Label::primary(file_id, span.bytes.clone()).with_message(ptr)
+                ])
+        })
+    }
+
+    /// Returns the retained source as a codespan-reporting file for use with the
+    /// diagnostics built by [`codespan_diagnostic`](#method.codespan_diagnostic)
+    pub fn codespan_file<N>(
+        &self,
+        name: N,
+    ) -> Option<SimpleFile<N, &str>>
+    where
+        N: Display + Clone,
+    {
+        self.source().map(|source| SimpleFile::new(name, source))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_str_with, Options, Result};
+    use codespan_reporting::diagnostic::Severity;
+
+    #[test]
+    fn diagnostic_labels_value() -> Result<()> {
+        let positions = from_str_with("foo:\n  bar: 'baz'", Options::default().keep_source(true))?;
+        let diagnostic = positions
+            .codespan_diagnostic("/foo/bar", (), Severity::Error, "unexpected value")
+            .expect("missing diagnostic");
+        assert_eq!(diagnostic.message, "unexpected value");
+        assert_eq!(diagnostic.labels[0].range, 12..17);
+        assert!(positions.codespan_file("config.yml").is_some());
+        Ok(())
+    }
+}
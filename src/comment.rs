@@ -0,0 +1,64 @@
+use crate::{source::Source, Span};
+use std::ops::Range;
+
+/// A `#` comment
+#[derive(Debug, PartialEq)]
+pub struct Comment {
+    /// Text following the `#`, excluding trailing whitespace
+    pub text: String,
+    /// Region of the comment, from its `#` to the end of its text
+    pub span: Span,
+    /// True when the comment follows content on the same line rather than standing on its own
+    pub trailing: bool,
+}
+
+/// Finds the comments in a source given the byte ranges of its scalars,
+/// within which `#` does not start a comment
+pub(crate) fn scan(
+    source: &Source,
+    scalars: &[Range<usize>],
+) -> Vec<Comment> {
+    let text = source.text();
+    let mut masked = scalars
+        .iter()
+        .map(|range| {
+            // block scalar headers may be followed by a comment
+            let start = if text[range.start..].starts_with(['|', '>']) {
+                text[range.start..range.end]
+                    .find('\n')
+                    .map_or(range.end, |idx| range.start + idx)
+            } else {
+                range.start
+            };
+            start..range.end
+        })
+        .filter(|range| !range.is_empty())
+        .collect::<Vec<_>>();
+    masked.sort_by_key(|range| range.start);
+    let mut masked = masked.into_iter().peekable();
+    let mut comments = Vec::new();
+    let mut line_start = 0;
+    let mut comment_end = 0;
+    let mut prev = '\n';
+    for (idx, c) in text.char_indices() {
+        while masked.peek().is_some_and(|range| range.end <= idx) {
+            masked.next();
+        }
+        let skipped = idx < comment_end || masked.peek().is_some_and(|range| range.contains(&idx));
+        match c {
+            '\n' => line_start = idx + 1,
+            '#' if !skipped && prev.is_whitespace() => {
+                comment_end = text[idx..].find('\n').map_or(text.len(), |len| idx + len);
+                let body = text[idx + 1..comment_end].trim_end();
+                comments.push(Comment {
+                    text: body.into(),
+                    span: source.span(idx..idx + 1 + body.len()),
+                    trailing: !text[line_start..idx].trim().is_empty(),
+                });
+            }
+            _ => (),
+        }
+        prev = c;
+    }
+    comments
+}
@@ -0,0 +1,791 @@
+//! A lossless concrete syntax tree of YAML and JSON text, keeping the whitespace, comments,
+//! and indicators the pointer index leaves out
+//!
+//! Trees are built as [rowan](https://docs.rs/rowan) builds them, in two layers. Immutable
+//! [`GreenNode`](struct.GreenNode.html)s know only their kind, their length, and their children,
+//! so unchanged subtrees can be shared between revisions. [`SyntaxNode`](struct.SyntaxNode.html)s
+//! wrap them with their offset and parent for navigation. Every byte of the text belongs to
+//! exactly one token, so a tree always displays as the text it was parsed from
+//!
+//! ```rust,edition2018
+//! use lincolns::cst::{SyntaxKind, SyntaxTree};
+//!
+//! # fn main() -> lincolns::Result<()>  {
+//! let yaml = "# web service\nimage: nginx # pinned\n";
+//! let tree = SyntaxTree::parse(yaml)?;
+//! assert_eq!(tree.to_string(), yaml);
+//! let image = tree.node("/image").expect("no image");
+//! assert_eq!(image.kind(), SyntaxKind::Scalar);
+//! assert_eq!(image.text_range(), 21..26);
+//! let entry = image.parent().expect("no entry");
+//! assert_eq!(entry.kind(), SyntaxKind::Entry);
+//! let comments = tree
+//!     .root()
+//!     .descendant_tokens()
+//!     .filter(|token| token.kind() == SyntaxKind::Comment)
+//!     .map(|token| token.text().to_owned())
+//!     .collect::<Vec<_>>();
+//! assert_eq!(comments, vec!["# web service", "# pinned"]);
+//! # Ok(())
+//! # }
+//! ```
+use crate::{Positions, Result, SpannedEvent, Yaml};
+use std::{collections::BTreeMap, fmt, ops::Range, rc::Rc, sync::Arc};
+
+/// The kind of a node or token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SyntaxKind {
+    /// The whole text, holding its documents and anything between them
+    Stream,
+    /// A document, from its `---` marker or content to its `...` marker or the end of its content
+    Document,
+    Mapping,
+    Sequence,
+    /// A mapping entry, from its key to the end of its value
+    Entry,
+    /// A mapping key
+    Key,
+    Scalar,
+    /// An alias, `*name`
+    Alias,
+    /// The text of a scalar, key, or alias, including any quoting or block scalar header
+    Text,
+    /// Spaces and tabs
+    Whitespace,
+    /// A line break, `\n` or `\r\n`
+    Newline,
+    /// A comment, from its `#` to the end of its line
+    Comment,
+    /// A `%` directive, such as `%YAML 1.2`
+    Directive,
+    /// A `---` marker
+    DocumentStart,
+    /// A `...` marker
+    DocumentEnd,
+    /// A byte order mark
+    Bom,
+    /// An anchor, `&name`
+    Anchor,
+    /// A tag, such as `!!str` or `!custom`
+    Tag,
+    /// `-` introducing a block sequence item
+    Dash,
+    /// `?` introducing a complex key
+    QuestionMark,
+    /// `:` separating a key from its value
+    Colon,
+    Comma,
+    LBracket,
+    RBracket,
+    LBrace,
+    RBrace,
+    /// Text the parser accepted which none of the other kinds describe
+    Unknown,
+}
+
+impl SyntaxKind {
+    /// Returns true for tokens which carry no meaning: whitespace, line breaks,
+    /// comments, and byte order marks
+    pub fn is_trivia(self) -> bool {
+        matches!(
+            self,
+            SyntaxKind::Whitespace | SyntaxKind::Newline | SyntaxKind::Comment | SyntaxKind::Bom
+        )
+    }
+}
+
+/// An immutable token: a kind and its text
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GreenToken {
+    kind: SyntaxKind,
+    text: String,
+}
+
+impl GreenToken {
+    pub fn new<T>(
+        kind: SyntaxKind,
+        text: T,
+    ) -> Self
+    where
+        T: Into<String>,
+    {
+        GreenToken {
+            kind,
+            text: text.into(),
+        }
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+/// A child of a [`GreenNode`](struct.GreenNode.html)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GreenElement {
+    Node(Arc<GreenNode>),
+    Token(Arc<GreenToken>),
+}
+
+impl GreenElement {
+    /// The length of the element's text in bytes
+    pub fn text_len(&self) -> usize {
+        match self {
+            GreenElement::Node(node) => node.text_len(),
+            GreenElement::Token(token) => token.text.len(),
+        }
+    }
+}
+
+impl From<GreenNode> for GreenElement {
+    fn from(node: GreenNode) -> Self {
+        GreenElement::Node(Arc::new(node))
+    }
+}
+
+impl From<GreenToken> for GreenElement {
+    fn from(token: GreenToken) -> Self {
+        GreenElement::Token(Arc::new(token))
+    }
+}
+
+/// An immutable node: a kind and its children, without any knowledge of where it is
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GreenNode {
+    kind: SyntaxKind,
+    len: usize,
+    children: Vec<GreenElement>,
+}
+
+impl GreenNode {
+    pub fn new<I>(
+        kind: SyntaxKind,
+        children: I,
+    ) -> Self
+    where
+        I: IntoIterator<Item = GreenElement>,
+    {
+        let children = children.into_iter().collect::<Vec<_>>();
+        GreenNode {
+            kind,
+            len: children.iter().map(GreenElement::text_len).sum(),
+            children,
+        }
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    /// The length of the node's text in bytes
+    pub fn text_len(&self) -> usize {
+        self.len
+    }
+
+    pub fn children(&self) -> &[GreenElement] {
+        &self.children
+    }
+}
+
+impl fmt::Display for GreenNode {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        for child in &self.children {
+            match child {
+                GreenElement::Node(node) => write!(f, "{}", node)?,
+                GreenElement::Token(token) => f.write_str(&token.text)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+struct NodeData {
+    green: Arc<GreenNode>,
+    offset: usize,
+    /// The node's parent and its index amongst the parent's children
+    parent: Option<(SyntaxNode, usize)>,
+}
+
+/// A [`GreenNode`](struct.GreenNode.html) located within its tree, knowing its offset and parent
+#[derive(Clone)]
+pub struct SyntaxNode(Rc<NodeData>);
+
+/// A [`GreenToken`](struct.GreenToken.html) located within its tree
+#[derive(Clone)]
+pub struct SyntaxToken {
+    green: Arc<GreenToken>,
+    offset: usize,
+    parent: SyntaxNode,
+}
+
+/// A child of a [`SyntaxNode`](struct.SyntaxNode.html)
+#[derive(Clone, Debug, PartialEq)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+impl SyntaxNode {
+    /// Wraps a green node as the root of a tree
+    pub fn new_root(green: Arc<GreenNode>) -> Self {
+        SyntaxNode(Rc::new(NodeData {
+            green,
+            offset: 0,
+            parent: None,
+        }))
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.0.green.kind
+    }
+
+    pub fn green(&self) -> &Arc<GreenNode> {
+        &self.0.green
+    }
+
+    /// The byte range of the node's text
+    pub fn text_range(&self) -> Range<usize> {
+        self.0.offset..self.0.offset + self.0.green.len
+    }
+
+    pub fn parent(&self) -> Option<SyntaxNode> {
+        self.0.parent.as_ref().map(|(parent, _)| parent.clone())
+    }
+
+    /// Returns this node's parent, its parent's parent, and so on
+    pub fn ancestors(&self) -> impl Iterator<Item = SyntaxNode> {
+        std::iter::successors(self.parent(), SyntaxNode::parent)
+    }
+
+    /// Returns the nodes and tokens immediately within this node, in order
+    pub fn children_with_tokens(&self) -> impl Iterator<Item = SyntaxElement> + '_ {
+        let mut offset = self.0.offset;
+        self.0
+            .green
+            .children
+            .iter()
+            .enumerate()
+            .map(move |(index, child)| {
+                let start = offset;
+                offset += child.text_len();
+                match child {
+                    GreenElement::Node(green) => {
+                        SyntaxElement::Node(SyntaxNode(Rc::new(NodeData {
+                            green: Arc::clone(green),
+                            offset: start,
+                            parent: Some((self.clone(), index)),
+                        })))
+                    }
+                    GreenElement::Token(green) => SyntaxElement::Token(SyntaxToken {
+                        green: Arc::clone(green),
+                        offset: start,
+                        parent: self.clone(),
+                    }),
+                }
+            })
+    }
+
+    /// Returns the nodes immediately within this node, in order
+    pub fn children(&self) -> impl Iterator<Item = SyntaxNode> + '_ {
+        self.children_with_tokens().filter_map(|child| match child {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        })
+    }
+
+    /// Returns this node and every node within it, in document order
+    pub fn descendants(&self) -> impl Iterator<Item = SyntaxNode> {
+        let mut stack = vec![self.clone()];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            let mut children = node.children().collect::<Vec<_>>();
+            children.reverse();
+            stack.extend(children);
+            Some(node)
+        })
+    }
+
+    /// Returns every token within this node, in document order
+    pub fn descendant_tokens(&self) -> impl Iterator<Item = SyntaxToken> {
+        let mut stack = vec![SyntaxElement::Node(self.clone())];
+        std::iter::from_fn(move || loop {
+            match stack.pop()? {
+                SyntaxElement::Token(token) => return Some(token),
+                SyntaxElement::Node(node) => {
+                    let mut children = node.children_with_tokens().collect::<Vec<_>>();
+                    children.reverse();
+                    stack.extend(children);
+                }
+            }
+        })
+    }
+
+    /// Returns the green root of a tree in which this node is replaced by `replacement`,
+    /// sharing every subtree beside the path to it
+    pub fn replace_with(
+        &self,
+        replacement: GreenNode,
+    ) -> GreenNode {
+        match &self.0.parent {
+            None => replacement,
+            Some((parent, index)) => {
+                let mut children = parent.0.green.children.clone();
+                children[*index] = replacement.into();
+                parent.replace_with(GreenNode::new(parent.kind(), children))
+            }
+        }
+    }
+}
+
+impl PartialEq for SyntaxNode {
+    fn eq(
+        &self,
+        other: &Self,
+    ) -> bool {
+        Arc::ptr_eq(&self.0.green, &other.0.green) && self.0.offset == other.0.offset
+    }
+}
+
+impl fmt::Debug for SyntaxNode {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{:?}@{:?}", self.kind(), self.text_range())
+    }
+}
+
+impl fmt::Display for SyntaxNode {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{}", self.0.green)
+    }
+}
+
+impl SyntaxToken {
+    pub fn kind(&self) -> SyntaxKind {
+        self.green.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.green.text
+    }
+
+    pub fn green(&self) -> &Arc<GreenToken> {
+        &self.green
+    }
+
+    /// The byte range of the token's text
+    pub fn text_range(&self) -> Range<usize> {
+        self.offset..self.offset + self.green.text.len()
+    }
+
+    pub fn parent(&self) -> SyntaxNode {
+        self.parent.clone()
+    }
+}
+
+impl PartialEq for SyntaxToken {
+    fn eq(
+        &self,
+        other: &Self,
+    ) -> bool {
+        Arc::ptr_eq(&self.green, &other.green) && self.offset == other.offset
+    }
+}
+
+impl fmt::Debug for SyntaxToken {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "{:?}@{:?} {:?}",
+            self.kind(),
+            self.text_range(),
+            self.text()
+        )
+    }
+}
+
+impl fmt::Display for SyntaxToken {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.write_str(self.text())
+    }
+}
+
+/// A lossless syntax tree of a stream of documents, along with where the node
+/// of each indexed JSON Pointer path is
+pub struct SyntaxTree {
+    root: Arc<GreenNode>,
+    /// Byte ranges of the nodes of pointers
+    pointers: BTreeMap<String, Range<usize>>,
+}
+
+/// The structure of a node as events describe it, before gaps are filled with tokens
+struct Shape {
+    kind: SyntaxKind,
+    range: Range<usize>,
+    children: Vec<Shape>,
+}
+
+impl Shape {
+    fn leaf(
+        kind: SyntaxKind,
+        range: Range<usize>,
+    ) -> Self {
+        Shape {
+            kind,
+            range,
+            children: Vec::new(),
+        }
+    }
+}
+
+/// A collection whose children are being collected
+struct Frame {
+    kind: SyntaxKind,
+    children: Vec<Shape>,
+    /// The key of the entry whose value is next
+    key: Option<Shape>,
+}
+
+impl Frame {
+    fn new(kind: SyntaxKind) -> Self {
+        Frame {
+            kind,
+            children: Vec::new(),
+            key: None,
+        }
+    }
+}
+
+impl SyntaxTree {
+    /// Parses utf8 text into a lossless syntax tree
+    pub fn parse<S>(text: S) -> Result<Self>
+    where
+        S: AsRef<str>,
+    {
+        let text = text.as_ref();
+        let mut positions = Positions::default();
+        positions.record();
+        let mut positions = crate::load(&Yaml, text, Default::default(), positions)?;
+        let mut pointers = BTreeMap::new();
+        let mut documents = Vec::<Vec<Shape>>::new();
+        let mut frames = Vec::<Frame>::new();
+        for (event, _) in positions.take_stream() {
+            let (pointer, shape) = match event {
+                SpannedEvent::DocumentStart => {
+                    documents.push(Vec::new());
+                    continue;
+                }
+                SpannedEvent::DocumentEnd => continue,
+                SpannedEvent::MappingStart { .. } => {
+                    frames.push(Frame::new(SyntaxKind::Mapping));
+                    continue;
+                }
+                SpannedEvent::SequenceStart { .. } => {
+                    frames.push(Frame::new(SyntaxKind::Sequence));
+                    continue;
+                }
+                SpannedEvent::Key { span, .. } => {
+                    if let Some(frame) = frames.last_mut() {
+                        frame.key = Some(Shape::leaf(SyntaxKind::Key, span.bytes));
+                    }
+                    continue;
+                }
+                SpannedEvent::Scalar { pointer, span, .. } => {
+                    (pointer, Shape::leaf(SyntaxKind::Scalar, span.bytes))
+                }
+                SpannedEvent::Alias { pointer, span } => {
+                    (pointer, Shape::leaf(SyntaxKind::Alias, span.bytes))
+                }
+                SpannedEvent::MappingEnd { pointer, span }
+                | SpannedEvent::SequenceEnd { pointer, span } => match frames.pop() {
+                    Some(frame) => (
+                        pointer,
+                        Shape {
+                            kind: frame.kind,
+                            range: span.bytes,
+                            children: frame.children,
+                        },
+                    ),
+                    None => continue,
+                },
+            };
+            pointers.insert(pointer, shape.range.clone());
+            match frames.last_mut() {
+                Some(frame) => match frame.key.take() {
+                    Some(key) => frame.children.push(Shape {
+                        kind: SyntaxKind::Entry,
+                        range: key.range.start..shape.range.end.max(key.range.end),
+                        children: vec![key, shape],
+                    }),
+                    None => frame.children.push(shape),
+                },
+                None => match documents.last_mut() {
+                    Some(roots) => roots.push(shape),
+                    None => documents.push(vec![shape]),
+                },
+            }
+        }
+        let documents = positions
+            .documents()
+            .iter()
+            .zip(documents)
+            .map(|(document, roots)| {
+                let start = document.start.as_ref().unwrap_or(&document.content);
+                let end = document.end.as_ref().unwrap_or(&document.content);
+                Shape {
+                    kind: SyntaxKind::Document,
+                    range: start.bytes.start..end.bytes.end,
+                    children: roots,
+                }
+            })
+            .collect();
+        let stream = Shape {
+            kind: SyntaxKind::Stream,
+            range: 0..text.len(),
+            children: documents,
+        };
+        Ok(SyntaxTree {
+            root: Arc::new(green(text, stream)),
+            pointers,
+        })
+    }
+
+    /// Returns the root of the tree, a [`SyntaxKind::Stream`](enum.SyntaxKind.html#variant.Stream)
+    pub fn root(&self) -> SyntaxNode {
+        SyntaxNode::new_root(Arc::clone(&self.root))
+    }
+
+    pub fn green(&self) -> &Arc<GreenNode> {
+        &self.root
+    }
+
+    /// Gets the node of the value a JSON Pointer path refers to
+    pub fn node<P>(
+        &self,
+        ptr: P,
+    ) -> Option<SyntaxNode>
+    where
+        P: AsRef<str>,
+    {
+        let range = self.pointers.get(ptr.as_ref())?;
+        find(self.root(), range)
+    }
+}
+
+impl fmt::Display for SyntaxTree {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{}", self.root)
+    }
+}
+
+/// Finds the value node spanning a byte range at or within `node`
+fn find(
+    node: SyntaxNode,
+    range: &Range<usize>,
+) -> Option<SyntaxNode> {
+    let value = matches!(
+        node.kind(),
+        SyntaxKind::Mapping | SyntaxKind::Sequence | SyntaxKind::Scalar | SyntaxKind::Alias
+    );
+    if value && node.text_range() == *range {
+        return Some(node);
+    }
+    node.children()
+        .filter(|child| {
+            let within = child.text_range();
+            within.start <= range.start && range.end <= within.end
+        })
+        .find_map(|child| find(child, range))
+}
+
+/// Builds the green node of a shape, filling the gaps between its children with tokens.
+/// Children overlapping what came before them are left to be tokenized instead
+fn green(
+    text: &str,
+    shape: Shape,
+) -> GreenNode {
+    let mut children = Vec::new();
+    let mut at = shape.range.start;
+    if shape.children.is_empty()
+        && matches!(
+            shape.kind,
+            SyntaxKind::Key | SyntaxKind::Scalar | SyntaxKind::Alias
+        )
+    {
+        if !shape.range.is_empty() {
+            children.push(GreenToken::new(SyntaxKind::Text, &text[shape.range.clone()]).into());
+        }
+        return GreenNode::new(shape.kind, children);
+    }
+    for child in shape.children {
+        if child.range.start < at || child.range.end > shape.range.end {
+            continue;
+        }
+        children.extend(tokens(text, at..child.range.start));
+        at = child.range.end;
+        children.push(green(text, child).into());
+    }
+    children.extend(tokens(text, at..shape.range.end));
+    GreenNode::new(shape.kind, children)
+}
+
+/// Splits text between nodes into tokens
+fn tokens(
+    text: &str,
+    range: Range<usize>,
+) -> Vec<GreenElement> {
+    let mut tokens = Vec::new();
+    let mut at = range.start;
+    while at < range.end {
+        let rest = &text[at..range.end];
+        let line_start = at == 0 || text[..at].ends_with('\n');
+        let marker = |marker: &str| {
+            line_start
+                && rest.starts_with(marker)
+                && !rest[3..].starts_with(|c: char| !c.is_whitespace())
+        };
+        let until = |stop: &dyn Fn(char) -> bool| rest.find(stop).unwrap_or(rest.len());
+        let (kind, len) = match rest.chars().next().unwrap_or_default() {
+            ' ' | '\t' => (SyntaxKind::Whitespace, until(&|c| c != ' ' && c != '\t')),
+            '\n' => (SyntaxKind::Newline, 1),
+            '\r' if rest.starts_with("\r\n") => (SyntaxKind::Newline, 2),
+            '\r' => (SyntaxKind::Newline, 1),
+            '#' => (SyntaxKind::Comment, until(&|c| c == '\r' || c == '\n')),
+            '%' if line_start => (SyntaxKind::Directive, until(&|c| c == '\r' || c == '\n')),
+            '-' if marker("---") => (SyntaxKind::DocumentStart, 3),
+            '.' if marker("...") => (SyntaxKind::DocumentEnd, 3),
+            '\u{feff}' => (SyntaxKind::Bom, '\u{feff}'.len_utf8()),
+            '-' => (SyntaxKind::Dash, 1),
+            '?' => (SyntaxKind::QuestionMark, 1),
+            ':' => (SyntaxKind::Colon, 1),
+            ',' => (SyntaxKind::Comma, 1),
+            '[' => (SyntaxKind::LBracket, 1),
+            ']' => (SyntaxKind::RBracket, 1),
+            '{' => (SyntaxKind::LBrace, 1),
+            '}' => (SyntaxKind::RBrace, 1),
+            c @ '&' | c @ '!' => (
+                if c == '&' {
+                    SyntaxKind::Anchor
+                } else {
+                    SyntaxKind::Tag
+                },
+                until(&|c| c.is_whitespace() || ",[]{}".contains(c)),
+            ),
+            c => (
+                SyntaxKind::Unknown,
+                until(&|c| c.is_whitespace()).max(c.len_utf8()),
+            ),
+        };
+        tokens.push(GreenToken::new(kind, &rest[..len]).into());
+        at += len;
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_text() -> Result<()> {
+        let docs = [
+            include_str!("../tests/data/example.yml"),
+            include_str!("../tests/data/example.json"),
+            "%YAML 1.2\n---\n# c\nkey: &a \"v\" # t\nlist: !t\n  - |\n    lit\n  - {x: [y], e: }\nnul:\n? [c]\n: z\nal: *a\n...\n---\nb: 2\n",
+            "\u{feff}a: 1\r\nb:\r\n  - 2\r\n",
+            "",
+            "# only a comment\n",
+            "plain scalar",
+        ];
+        for doc in &docs {
+            let tree = SyntaxTree::parse(doc)?;
+            assert_eq!(tree.to_string(), *doc);
+            let tokens = tree.root().descendant_tokens().collect::<Vec<_>>();
+            assert!(
+                tokens
+                    .iter()
+                    .all(|token| token.kind() != SyntaxKind::Unknown),
+                "{:?}",
+                tokens
+            );
+            let mut at = 0;
+            for token in tokens {
+                assert_eq!(token.text_range().start, at);
+                at = token.text_range().end;
+            }
+            assert_eq!(at, doc.len());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn nodes_of_pointers() -> Result<()> {
+        let yaml = "a:\n  - &x {b: [1, 2]} # trailing\n  - !t c\n";
+        let tree = SyntaxTree::parse(yaml)?;
+        let text = |ptr: &str| tree.node(ptr).map(|node| node.to_string());
+        assert_eq!(text("/a/0").as_deref(), Some("{b: [1, 2]}"));
+        assert_eq!(text("/a/0/b/1").as_deref(), Some("2"));
+        assert_eq!(text("/a/1").as_deref(), Some("c"));
+        assert_eq!(text("").as_deref(), Some(&yaml[..yaml.len() - 1]));
+        let sequence = tree.node("/a").expect("no sequence");
+        let kinds = sequence
+            .children_with_tokens()
+            .map(|child| match child {
+                SyntaxElement::Node(node) => node.kind(),
+                SyntaxElement::Token(token) => token.kind(),
+            })
+            .filter(|kind| !kind.is_trivia())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                SyntaxKind::Dash,
+                SyntaxKind::Anchor,
+                SyntaxKind::Mapping,
+                SyntaxKind::Dash,
+                SyntaxKind::Tag,
+                SyntaxKind::Scalar
+            ]
+        );
+        let entry = tree.node("/a/0/b").and_then(|node| node.parent());
+        assert_eq!(entry.map(|entry| entry.kind()), Some(SyntaxKind::Entry));
+        Ok(())
+    }
+
+    #[test]
+    fn replacing_nodes_shares_the_rest() -> Result<()> {
+        let tree = SyntaxTree::parse("a: 1 # one\nb: [2]\n")?;
+        let one = tree.node("/a").expect("no scalar");
+        let replaced = one.replace_with(GreenNode::new(
+            SyntaxKind::Scalar,
+            vec![GreenToken::new(SyntaxKind::Text, "10").into()],
+        ));
+        assert_eq!(replaced.to_string(), "a: 10 # one\nb: [2]\n");
+        let b = tree.node("/b").expect("no sequence");
+        let root = SyntaxNode::new_root(Arc::new(replaced));
+        assert!(root
+            .descendants()
+            .any(|node| Arc::ptr_eq(node.green(), b.green())));
+        Ok(())
+    }
+}
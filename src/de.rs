@@ -0,0 +1,718 @@
+use crate::{from_str_with, path, Error, Options, Position, Positions, Result, Span};
+use serde::de::{
+    self,
+    value::{BorrowedStrDeserializer, SeqDeserializer, StrDeserializer},
+    DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor,
+};
+use std::fmt;
+use yaml_rust::{Yaml, YamlLoader};
+
+const SPANNED: &str = "$__lincolns_private_Spanned";
+const SPANNED_FIELDS: &[&str] = &[
+    "$__lincolns_private_position",
+    "$__lincolns_private_span",
+    "$__lincolns_private_value",
+];
+
+/// A deserialized value along with where it was found
+///
+/// ```rust,edition2018
+/// use lincolns::{deserialize, Position, Spanned};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     name: Spanned<String>,
+///     ports: Vec<Spanned<u16>>,
+/// }
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let config: Config = deserialize("name: web\nports:\n  - 80\n  - 443\n")?;
+/// assert_eq!(config.name.get_ref(), "web");
+/// assert_eq!(config.ports[1].span().start, Position { line: 4, col: 4 });
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Spanned<T> {
+    position: Position,
+    span: Span,
+    value: T,
+}
+
+impl<T> Spanned<T> {
+    /// The position of the value, as [`Positions::get`](struct.Positions.html#method.get) reports it,
+    /// which for mapping entries is the position of their key
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// The span of the value's own source text
+    pub fn span(&self) -> &Span {
+        &self.span
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.value
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: PartialEq> PartialEq for Spanned<T> {
+    fn eq(
+        &self,
+        other: &Self,
+    ) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<'de, T> de::Deserialize<'de> for Spanned<T>
+where
+    T: de::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct SpannedVisitor<T>(std::marker::PhantomData<T>);
+
+        impl<'de, T> Visitor<'de> for SpannedVisitor<T>
+        where
+            T: de::Deserialize<'de>,
+        {
+            type Value = Spanned<T>;
+
+            fn expecting(
+                &self,
+                formatter: &mut fmt::Formatter,
+            ) -> fmt::Result {
+                formatter.write_str("a spanned value")
+            }
+
+            fn visit_map<A>(
+                self,
+                mut map: A,
+            ) -> std::result::Result<Spanned<T>, A::Error>
+            where
+                A: MapAccess<'de>,
+            {
+                expect_field(&mut map, SPANNED_FIELDS[0])?;
+                let (line, col) = map.next_value()?;
+                expect_field(&mut map, SPANNED_FIELDS[1])?;
+                let (start_line, start_col, end_line, end_col, start, end) = map.next_value()?;
+                expect_field(&mut map, SPANNED_FIELDS[2])?;
+                Ok(Spanned {
+                    position: Position { line, col },
+                    span: Span {
+                        start: Position {
+                            line: start_line,
+                            col: start_col,
+                        },
+                        end: Position {
+                            line: end_line,
+                            col: end_col,
+                        },
+                        bytes: start..end,
+                    },
+                    value: map.next_value()?,
+                })
+            }
+        }
+
+        deserializer.deserialize_struct(
+            SPANNED,
+            SPANNED_FIELDS,
+            SpannedVisitor(std::marker::PhantomData),
+        )
+    }
+}
+
+fn expect_field<'de, A>(
+    map: &mut A,
+    field: &str,
+) -> std::result::Result<(), A::Error>
+where
+    A: MapAccess<'de>,
+{
+    match map.next_key::<&str>()? {
+        Some(key) if key == field => Ok(()),
+        _ => Err(de::Error::custom(
+            "spanned values require a lincolns deserializer",
+        )),
+    }
+}
+
+/// A [serde](https://serde.rs) deserializer which knows where in its source text each value came from,
+/// filling in [`Spanned`](struct.Spanned.html) values and locating errors
+pub struct Deserializer {
+    value: Yaml,
+    positions: Positions,
+}
+
+impl Deserializer {
+    /// Creates a deserializer for the first document in utf8 text
+    pub fn from_str_with<S>(
+        s: S,
+        options: Options,
+    ) -> Result<Self>
+    where
+        S: AsRef<str>,
+    {
+        let positions = from_str_with(s.as_ref(), options)?;
+        let value = YamlLoader::load_from_str(s.as_ref())?
+            .into_iter()
+            .next()
+            .unwrap_or(Yaml::Null);
+        Ok(Self { value, positions })
+    }
+
+    /// The positions of the content being deserialized
+    pub fn positions(&self) -> &Positions {
+        &self.positions
+    }
+
+    fn node(&self) -> Node<'_> {
+        Node {
+            value: &self.value,
+            pointer: String::new(),
+            positions: &self.positions,
+        }
+    }
+}
+
+/// Deserializes an instance of `T` from utf8 text
+pub fn deserialize<T, S>(s: S) -> Result<T>
+where
+    T: DeserializeOwned,
+    S: AsRef<str>,
+{
+    deserialize_with(s, Options::default())
+}
+
+/// Deserializes an instance of `T` from utf8 text using the provided [`Options`](struct.Options.html)
+pub fn deserialize_with<T, S>(
+    s: S,
+    options: Options,
+) -> Result<T>
+where
+    T: DeserializeOwned,
+    S: AsRef<str>,
+{
+    T::deserialize(&Deserializer::from_str_with(s, options)?)
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Deserialize {
+            message: msg.to_string(),
+            pointer: None,
+            position: None,
+        }
+    }
+}
+
+impl Error {
+    /// Locates a deserialization error at a pointer, unless a nested value already has
+    fn at(
+        self,
+        at: &str,
+        positions: &Positions,
+    ) -> Self {
+        match self {
+            Error::Deserialize {
+                message,
+                pointer: None,
+                ..
+            } => Error::Deserialize {
+                message,
+                position: positions.locate_pointer(at).map(|(position, _)| position),
+                pointer: Some(at.to_owned()),
+            },
+            other => other,
+        }
+    }
+}
+
+/// A value and the pointer it was found at
+struct Node<'a> {
+    value: &'a Yaml,
+    pointer: String,
+    positions: &'a Positions,
+}
+
+impl<'a> Node<'a> {
+    fn child(
+        &self,
+        value: &'a Yaml,
+        token: &str,
+    ) -> Self {
+        Node {
+            value,
+            pointer: format!("{}/{}", self.pointer, path::escape(token)),
+            positions: self.positions,
+        }
+    }
+
+    fn error(
+        &self,
+        err: Error,
+    ) -> Error {
+        err.at(&self.pointer, self.positions)
+    }
+}
+
+/// The pointer token for a mapping key
+fn token(key: &Yaml) -> Option<String> {
+    match key {
+        Yaml::String(s) | Yaml::Real(s) => Some(s.clone()),
+        Yaml::Integer(i) => Some(i.to_string()),
+        Yaml::Boolean(b) => Some(b.to_string()),
+        Yaml::Null => Some("~".into()),
+        _ => None,
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &Deserializer {
+    type Error = Error;
+
+    fn deserialize_any<V>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.node().deserialize_any(visitor)
+    }
+
+    fn deserialize_option<V>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.node().deserialize_option(visitor)
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.node().deserialize_struct(name, fields, visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.node().deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        self.node().deserialize_newtype_struct(name, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map identifier ignored_any
+    }
+}
+
+impl<'de, 'a> de::Deserializer<'de> for Node<'a> {
+    type Error = Error;
+
+    fn deserialize_any<V>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let result = match self.value {
+            Yaml::Null => visitor.visit_unit(),
+            Yaml::Boolean(b) => visitor.visit_bool(*b),
+            Yaml::Integer(i) => visitor.visit_i64(*i),
+            Yaml::Real(_) => match self.value.as_f64() {
+                Some(f) => visitor.visit_f64(f),
+                None => Err(de::Error::custom("invalid float")),
+            },
+            Yaml::String(s) => visitor.visit_str(s),
+            Yaml::Array(items) => visitor.visit_seq(Items {
+                node: &self,
+                items: items.iter().enumerate(),
+            }),
+            Yaml::Hash(entries) => visitor.visit_map(Entries {
+                node: &self,
+                entries: entries.iter(),
+                value: None,
+            }),
+            Yaml::Alias(_) | Yaml::BadValue => Err(de::Error::custom("unsupported value")),
+        };
+        result.map_err(|err| self.error(err))
+    }
+
+    fn deserialize_option<V>(
+        self,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Yaml::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if name != SPANNED || fields != SPANNED_FIELDS {
+            return self.deserialize_any(visitor);
+        }
+        let (position, span) = match self.positions.locate_pointer(&self.pointer) {
+            Some(location) => location,
+            None => {
+                return Err(self.error(de::Error::custom(format!(
+                    "no position for {}",
+                    self.pointer
+                ))))
+            }
+        };
+        visitor.visit_map(SpannedAccess {
+            position: Some(vec![position.line, position.col]),
+            span: Some(vec![
+                span.start.line,
+                span.start.col,
+                span.end.line,
+                span.end.col,
+                span.bytes.start,
+                span.bytes.end,
+            ]),
+            node: Some(self),
+        })
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        let result = match self.value {
+            Yaml::String(variant) => visitor.visit_enum(variant.as_str().into_deserializer()),
+            Yaml::Hash(entries) if entries.len() == 1 => {
+                let (key, value) = entries.front().expect("one entry");
+                match token(key) {
+                    Some(variant) => visitor.visit_enum(Variant {
+                        node: self.child(value, &variant),
+                        variant,
+                    }),
+                    None => Err(de::Error::custom("expected a variant name")),
+                }
+            }
+            _ => Err(de::Error::custom(
+                "expected a variant name or a mapping of one",
+            )),
+        };
+        result.map_err(|err| self.error(err))
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map identifier ignored_any
+    }
+}
+
+struct Items<'n, 'a, I> {
+    node: &'n Node<'a>,
+    items: I,
+}
+
+impl<'de, 'n, 'a, I> SeqAccess<'de> for Items<'n, 'a, I>
+where
+    I: Iterator<Item = (usize, &'a Yaml)>,
+{
+    type Error = Error;
+
+    fn next_element_seed<T>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.items.next() {
+            Some((index, item)) => seed
+                .deserialize(self.node.child(item, &index.to_string()))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+struct Entries<'n, 'a, I> {
+    node: &'n Node<'a>,
+    entries: I,
+    value: Option<Node<'a>>,
+}
+
+impl<'de, 'n, 'a, I> MapAccess<'de> for Entries<'n, 'a, I>
+where
+    I: Iterator<Item = (&'a Yaml, &'a Yaml)>,
+{
+    type Error = Error;
+
+    fn next_key_seed<K>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let (key, value) = match self.entries.next() {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+        let child = self.node.child(value, &token(key).unwrap_or_default());
+        let key = seed.deserialize(Node {
+            value: key,
+            pointer: child.pointer.clone(),
+            positions: self.node.positions,
+        })?;
+        self.value = Some(child);
+        Ok(Some(key))
+    }
+
+    fn next_value_seed<V>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        match self.value.take() {
+            Some(value) => seed.deserialize(value),
+            None => Err(de::Error::custom("value requested before key")),
+        }
+    }
+}
+
+/// Supplies a node's location ahead of the node itself
+struct SpannedAccess<'a> {
+    position: Option<Vec<usize>>,
+    span: Option<Vec<usize>>,
+    node: Option<Node<'a>>,
+}
+
+impl<'de, 'a> MapAccess<'de> for SpannedAccess<'a> {
+    type Error = Error;
+
+    fn next_key_seed<K>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let field = if self.position.is_some() {
+            SPANNED_FIELDS[0]
+        } else if self.span.is_some() {
+            SPANNED_FIELDS[1]
+        } else if self.node.is_some() {
+            SPANNED_FIELDS[2]
+        } else {
+            return Ok(None);
+        };
+        seed.deserialize(BorrowedStrDeserializer::new(field))
+            .map(Some)
+    }
+
+    fn next_value_seed<V>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        if let Some(numbers) = self.position.take().or_else(|| self.span.take()) {
+            seed.deserialize(SeqDeserializer::new(numbers.into_iter()))
+        } else {
+            match self.node.take() {
+                Some(node) => seed.deserialize(node),
+                None => Err(de::Error::custom("no more values")),
+            }
+        }
+    }
+}
+
+struct Variant<'a> {
+    node: Node<'a>,
+    variant: String,
+}
+
+impl<'de, 'a> de::EnumAccess<'de> for Variant<'a> {
+    type Error = Error;
+    type Variant = Node<'a>;
+
+    fn variant_seed<V>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Node<'a>)>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant: StrDeserializer<'_, Error> = self.variant.as_str().into_deserializer();
+        let variant = seed.deserialize(variant)?;
+        Ok((variant, self.node))
+    }
+}
+
+impl<'de, 'a> de::VariantAccess<'de> for Node<'a> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T>(
+        self,
+        seed: T,
+    ) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_any(self, visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        de::Deserializer::deserialize_any(self, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    struct Config {
+        name: Spanned<String>,
+        replicas: Option<u8>,
+        tags: Vec<Spanned<String>>,
+        mode: Mode,
+        nested: Spanned<Nested>,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Mode {
+        Fast,
+        Safe,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Nested {
+        flag: bool,
+    }
+
+    #[test]
+    fn captures_spans() -> Result<()> {
+        let config: Config =
+            deserialize("name: 'web'\ntags: [a, b]\nmode: safe\nnested:\n  flag: true\n")?;
+        assert_eq!(config.name.get_ref(), "web");
+        assert_eq!(config.name.position(), Position { line: 1, col: 0 });
+        assert_eq!(config.name.span().bytes, 6..11);
+        assert_eq!(config.replicas, None);
+        assert_eq!(config.tags[1].span().start, Position { line: 2, col: 10 });
+        assert_eq!(config.mode, Mode::Safe);
+        assert!(config.nested.get_ref().flag);
+        assert_eq!(config.nested.span().start, Position { line: 5, col: 2 });
+        Ok(())
+    }
+
+    #[test]
+    fn locates_errors() {
+        match deserialize::<Config, _>("name: web\ntags: [a, [b]]\n") {
+            Err(Error::Deserialize {
+                pointer, position, ..
+            }) => {
+                assert_eq!(pointer.as_deref(), Some("/tags/1"));
+                assert_eq!(position, Some(Position { line: 2, col: 10 }));
+            }
+            other => panic!("unexpected {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn spans_the_root() -> Result<()> {
+        let root: Spanned<Vec<u8>> = deserialize("\n[1, 2]\n")?;
+        assert_eq!(root.get_ref(), &vec![1, 2]);
+        assert_eq!(root.span().start, Position { line: 2, col: 0 });
+        Ok(())
+    }
+}
@@ -0,0 +1,205 @@
+use crate::{Position, Positions};
+use std::fmt;
+
+/// How serious a [`Diagnostic`](struct.Diagnostic.html) is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl Severity {
+    /// The lowercase name diagnostics are prefixed with, such as `error`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A message about a node, formatted on one line as
+/// `error: message at config.yml:12:4 (/spec/image)`
+///
+/// The file, position, and pointer are each left out when they aren't known
+///
+/// ```rust,edition2018
+/// use lincolns::Diagnostic;
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let positions = lincolns::from_str("spec:\n  image: nginx\n")?;
+/// let diagnostic = Diagnostic::error("image is not pinned")
+///     .pointer("/spec/image")
+///     .file("config.yml")
+///     .locate(&positions);
+/// assert_eq!(
+///     diagnostic.to_string(),
+///     "error: image is not pinned at config.yml:2:2 (/spec/image)"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// The JSON Pointer path of the node the diagnostic is about
+    pub pointer: Option<String>,
+    /// The name of the file the node is in, as it's displayed
+    pub file: Option<String>,
+    pub position: Option<Position>,
+}
+
+impl Diagnostic {
+    /// Creates a diagnostic with a severity and message
+    pub fn new<M>(
+        severity: Severity,
+        message: M,
+    ) -> Self
+    where
+        M: Into<String>,
+    {
+        Diagnostic {
+            severity,
+            message: message.into(),
+            pointer: None,
+            file: None,
+            position: None,
+        }
+    }
+
+    /// Creates an error diagnostic
+    pub fn error<M>(message: M) -> Self
+    where
+        M: Into<String>,
+    {
+        Diagnostic::new(Severity::Error, message)
+    }
+
+    /// Creates a warning diagnostic
+    pub fn warning<M>(message: M) -> Self
+    where
+        M: Into<String>,
+    {
+        Diagnostic::new(Severity::Warning, message)
+    }
+
+    /// Creates a note diagnostic
+    pub fn note<M>(message: M) -> Self
+    where
+        M: Into<String>,
+    {
+        Diagnostic::new(Severity::Note, message)
+    }
+
+    /// Sets the JSON Pointer path of the node the diagnostic is about
+    pub fn pointer<P>(
+        mut self,
+        ptr: P,
+    ) -> Self
+    where
+        P: Into<String>,
+    {
+        self.pointer = Some(ptr.into());
+        self
+    }
+
+    /// Sets the name of the file the node is in, as it should be displayed
+    pub fn file<F>(
+        mut self,
+        file: F,
+    ) -> Self
+    where
+        F: Into<String>,
+    {
+        self.file = Some(file.into());
+        self
+    }
+
+    /// Sets the position of the node
+    pub fn position(
+        mut self,
+        position: Position,
+    ) -> Self {
+        self.position = Some(position);
+        self
+    }
+
+    /// Sets the position of the node from its pointer's position in a lookup table,
+    /// or that of its closest existing ancestor when the node is missing
+    pub fn locate(
+        mut self,
+        positions: &Positions,
+    ) -> Self {
+        if let Some(ptr) = &self.pointer {
+            self.position = positions
+                .get_closest(ptr)
+                .map(|(_, position)| *position)
+                .or(self.position);
+        }
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{}: {}", self.severity, self.message)?;
+        match (&self.file, self.position) {
+            (Some(file), Some(position)) => write!(f, " at {}:{}", file, position)?,
+            (None, Some(position)) => write!(f, " at {}", position)?,
+            (Some(file), None) => write!(f, " in {}", file)?,
+            (None, None) => (),
+        }
+        if let Some(ptr) = &self.pointer {
+            write!(f, " ({})", ptr)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_str, Diagnostic, Position, Result};
+
+    #[test]
+    fn formats_what_is_known() -> Result<()> {
+        let positions = from_str("spec:\n  image: nginx\n")?;
+        assert_eq!(Diagnostic::note("done").to_string(), "note: done");
+        assert_eq!(
+            Diagnostic::warning("unused")
+                .pointer("/spec/replicas/max")
+                .locate(&positions)
+                .to_string(),
+            "warning: unused at 1:0 (/spec/replicas/max)"
+        );
+        assert_eq!(
+            Diagnostic::error("unreadable")
+                .file("config.yml")
+                .locate(&positions)
+                .to_string(),
+            "error: unreadable in config.yml"
+        );
+        assert_eq!(
+            Diagnostic::error("bad")
+                .position(Position { line: 3, col: 1 })
+                .file("a.yml")
+                .to_string(),
+            "error: bad at a.yml:3:1"
+        );
+        Ok(())
+    }
+}
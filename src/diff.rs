@@ -0,0 +1,275 @@
+use crate::{kind::article, NodeKind, Position, Positions, Result};
+use std::fmt;
+
+/// Differences between two [`Positions`](struct.Positions.html) tables
+///
+/// Each list is ordered by pointer
+#[derive(Debug, Default, PartialEq)]
+pub struct Diff<'a> {
+    /// Pointers only present in the newer table
+    pub added: Vec<(&'a str, &'a Position)>,
+    /// Pointers only present in the older table
+    pub removed: Vec<(&'a str, &'a Position)>,
+    /// Pointers present in both tables but at different positions
+    pub moved: Vec<Moved<'a>>,
+}
+
+/// A pointer whose position changed between two tables
+#[derive(Debug, PartialEq)]
+pub struct Moved<'a> {
+    pub pointer: &'a str,
+    pub old: &'a Position,
+    pub new: &'a Position,
+}
+
+impl<'a> Diff<'a> {
+    /// Returns true when both tables contained the same pointers at the same positions
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.moved.is_empty()
+    }
+}
+
+impl Positions {
+    /// Compares this table with a newer revision, `other`, returning
+    /// the pointers that were added, removed, or moved
+    pub fn diff<'a>(
+        &'a self,
+        other: &'a Positions,
+    ) -> Diff<'a> {
+        let mut diff = Diff::default();
+        for (pointer, old) in self.iter() {
+            match other.get(pointer) {
+                Some(new) if new != old => diff.moved.push(Moved { pointer, old, new }),
+                Some(_) => (),
+                None => diff.removed.push((pointer, old)),
+            }
+        }
+        for (pointer, new) in other.iter() {
+            if self.get(pointer).is_none() {
+                diff.added.push((pointer, new));
+            }
+        }
+        diff
+    }
+}
+
+/// A node as it was in one revision of a document
+#[derive(Debug, Clone, PartialEq)]
+pub struct Revision {
+    pub position: Position,
+    pub kind: NodeKind,
+    /// The parsed value of a scalar, `None` for collections and aliases
+    pub value: Option<String>,
+}
+
+impl fmt::Display for Revision {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match &self.value {
+            Some(value) => write!(f, "{}", value),
+            None => write!(f, "{} {}", article(self.kind), self.kind),
+        }
+    }
+}
+
+/// A pointer whose value was added, removed, or changed between two documents,
+/// returned by [`diff`](fn.diff.html)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    pub pointer: String,
+    /// The node in the old document, `None` when it was added
+    pub old: Option<Revision>,
+    /// The node in the new document, `None` when it was removed
+    pub new: Option<Revision>,
+}
+
+impl Change {
+    /// Returns true when the pointer is only present in the new document
+    pub fn is_added(&self) -> bool {
+        self.old.is_none()
+    }
+
+    /// Returns true when the pointer is only present in the old document
+    pub fn is_removed(&self) -> bool {
+        self.new.is_none()
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match (&self.old, &self.new) {
+            (Some(old), Some(new)) => write!(
+                f,
+                "{} changed from {} (old line {}) to {} (new line {})",
+                self.pointer, old, old.position.line, new, new.position.line
+            ),
+            (Some(old), None) => write!(
+                f,
+                "{} removed {} (old line {})",
+                self.pointer, old, old.position.line
+            ),
+            (None, Some(new)) => write!(
+                f,
+                "{} added {} (new line {})",
+                self.pointer, new, new.position.line
+            ),
+            (None, None) => write!(f, "{} unchanged", self.pointer),
+        }
+    }
+}
+
+/// Compares two revisions of a document, returning the pointers whose values were added,
+/// removed, or changed, ordered by pointer
+///
+/// Scalars change when their parsed value does, and any node changes when its kind does.
+/// Nodes within an added, removed, or changed collection aren't listed themselves.
+/// Unlike [`Positions::diff`](struct.Positions.html#method.diff), nodes that only moved
+/// aren't changes
+///
+/// ```rust,edition2018
+/// # fn main() -> lincolns::Result<()>  {
+/// let changes = lincolns::diff(
+///     "name: web\nreplicas: 2\n",
+///     "# scaled up\nname: web\nreplicas: 5\nports: [80]\n",
+/// )?;
+/// assert_eq!(
+///     changes.iter().map(ToString::to_string).collect::<Vec<_>>(),
+///     vec![
+///         "/ports added a sequence (new line 4)",
+///         "/replicas changed from 2 (old line 2) to 5 (new line 3)",
+///     ]
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn diff<O, N>(
+    old: O,
+    new: N,
+) -> Result<Vec<Change>>
+where
+    O: AsRef<str>,
+    N: AsRef<str>,
+{
+    Ok(changes(&crate::from_str(old)?, &crate::from_str(new)?))
+}
+
+/// Lists the changes between two tables, as [`diff`](fn.diff.html) does
+pub(crate) fn changes(
+    old: &Positions,
+    new: &Positions,
+) -> Vec<Change> {
+    let revision = |positions: &Positions, pointer: &str| {
+        Some(Revision {
+            position: *positions.get(pointer)?,
+            kind: positions.kind(pointer)?,
+            value: positions
+                .get_value(pointer)
+                .map(|(value, _)| value.to_owned()),
+        })
+    };
+    let mut pointers = old.keys().chain(new.keys()).collect::<Vec<_>>();
+    pointers.sort_unstable();
+    pointers.dedup();
+    let mut changes = Vec::<Change>::new();
+    // changed pointers whose descendants may yet follow. Sorting places descendants after their
+    // ancestor, with only siblings such as `/a!` sorting between `/a` and `/a/b`
+    let mut open = Vec::<&str>::new();
+    for pointer in pointers {
+        while let Some(prefix) = open.last() {
+            match pointer.strip_prefix(prefix) {
+                Some(rest) if rest.as_bytes().first() <= Some(&b'/') => break,
+                _ => {
+                    open.pop();
+                }
+            }
+        }
+        // nodes within a collection already listed changed along with it
+        if open
+            .last()
+            .is_some_and(|prefix| pointer[prefix.len()..].starts_with('/'))
+        {
+            continue;
+        }
+        let (before, after) = (revision(old, pointer), revision(new, pointer));
+        let changed = match (&before, &after) {
+            (Some(before), Some(after)) => before.kind != after.kind || before.value != after.value,
+            _ => true,
+        };
+        if changed {
+            open.push(pointer);
+            changes.push(Change {
+                pointer: pointer.to_owned(),
+                old: before,
+                new: after,
+            });
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_str, Moved, Position, Result};
+
+    #[test]
+    fn diff_reports_added_removed_and_moved() -> Result<()> {
+        let old = from_str("foo: 1\nbar: 2\n")?;
+        let new = from_str("baz: 0\nfoo: 1\n")?;
+        let diff = old.diff(&new);
+        assert_eq!(diff.added, vec![("/baz", &Position { line: 1, col: 0 })]);
+        assert_eq!(diff.removed, vec![("/bar", &Position { line: 2, col: 0 })]);
+        assert_eq!(
+            diff.moved,
+            vec![Moved {
+                pointer: "/foo",
+                old: &Position { line: 1, col: 0 },
+                new: &Position { line: 2, col: 0 },
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn diff_of_identical_tables_is_empty() -> Result<()> {
+        let positions = from_str(include_str!("../tests/data/example.yml"))?;
+        assert!(positions.diff(&positions).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn diff_reports_changed_values_at_both_positions() -> Result<()> {
+        let changes = crate::diff(
+            "spec:\n  replicas: 2\n  debug: true\n  ports: [80, 443]\nlabels: {app: web}\n",
+            "spec:\n  ports: [80]\n  replicas: 2\n  debug: false\nlabels: none\n",
+        )?;
+        assert_eq!(
+            changes.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "/labels changed from a mapping (old line 5) to none (new line 5)",
+                "/spec/debug changed from true (old line 3) to false (new line 4)",
+                "/spec/ports/1 removed 443 (old line 4)",
+            ]
+        );
+        assert!(changes[2].is_removed());
+        assert!(!changes[2].is_added());
+        Ok(())
+    }
+
+    #[test]
+    fn diff_skips_nodes_within_changed_collections() -> Result<()> {
+        let changes = crate::diff("a: {b: 1}\na!: 1\nc: [1]\n", "a: 2\na!: {d: [1]}\nc: [2]\n")?;
+        assert_eq!(
+            changes
+                .iter()
+                .map(|change| change.pointer.as_str())
+                .collect::<Vec<_>>(),
+            vec!["/a", "/a!", "/c/0"]
+        );
+        Ok(())
+    }
+}
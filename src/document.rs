@@ -0,0 +1,155 @@
+use crate::{source::Source, Span};
+use std::ops::Range;
+
+/// The structural boundaries of a document within a stream
+///
+/// ```rust,edition2018
+/// # fn main() -> lincolns::Result<()>  {
+/// let positions = lincolns::from_str("---\nname: app\n...\n")?;
+/// let document = &positions.documents()[0];
+/// assert_eq!(document.start.as_ref().map(|span| span.start.line), Some(1));
+/// assert_eq!(document.end.as_ref().map(|span| span.start.line), Some(3));
+/// // new entries may be appended after the last of the document's content
+/// assert_eq!((document.content.end.line, document.content.end.col), (2, 9));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct Document {
+    /// The `---` marker starting the document, if it was explicitly started
+    pub start: Option<Span>,
+    /// The document's content, from the start of its root node to the end of its last child
+    pub content: Span,
+    /// The `...` marker ending the document, if it was explicitly ended
+    pub end: Option<Span>,
+}
+
+impl Document {
+    /// Builds a document given the offsets of its start and end events and the byte range of its content
+    pub(crate) fn new(
+        start: usize,
+        content: Range<usize>,
+        end: usize,
+        source: &Source,
+    ) -> Self {
+        let marker = |offset: usize, marker: &str| {
+            source.text()[offset..]
+                .starts_with(marker)
+                .then(|| source.span(offset..offset + marker.len()))
+        };
+        Document {
+            start: marker(start, "---"),
+            content: source.span(content),
+            end: marker(end, "..."),
+        }
+    }
+}
+
+/// A `%` directive preceding a document, such as `%YAML 1.2` or `%TAG ! tag:example.com,2000:`
+///
+/// ```rust,edition2018
+/// # fn main() -> lincolns::Result<()>  {
+/// let positions = lincolns::from_str("%YAML 1.2\n---\nname: app\n")?;
+/// let directive = &positions.directives()[0];
+/// assert_eq!(directive.name, "YAML");
+/// assert_eq!(directive.parameters, vec!["1.2"]);
+/// assert_eq!(directive.span.start.line, 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct Directive {
+    /// Name of the directive, excluding the `%`
+    pub name: String,
+    /// Parameters following the name
+    pub parameters: Vec<String>,
+    /// Region of the directive, from its `%` to the end of its last parameter
+    pub span: Span,
+}
+
+/// Finds the directives in a source given the byte ranges of its scalars,
+/// within which lines starting with `%` are content
+pub(crate) fn directives(
+    source: &Source,
+    scalars: &[Range<usize>],
+) -> Vec<Directive> {
+    let text = source.text();
+    let mut directives = Vec::new();
+    let mut line_start = 0;
+    for line in text.split_inclusive('\n') {
+        let start = line_start;
+        line_start += line.len();
+        if !line.starts_with('%') || scalars.iter().any(|range| range.contains(&start)) {
+            continue;
+        }
+        // directives may be followed by a comment
+        let body = line.find(" #").map_or(line, |idx| &line[..idx]).trim_end();
+        let mut tokens = body[1..].split_whitespace();
+        let name = tokens.next().unwrap_or_default().to_owned();
+        directives.push(Directive {
+            name,
+            parameters: tokens.map(str::to_owned).collect(),
+            span: source.span(start..start + body.len()),
+        });
+    }
+    directives
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_str, Position, Result};
+
+    #[test]
+    fn indexes_directives() -> Result<()> {
+        let positions = from_str(
+            "%YAML 1.1 # version\n%TAG !e! tag:example.com,2000:\n---\na: |\n  %not\n...\n%YAML 1.1\n---\nb: 1\n",
+        )?;
+        let directives = positions
+            .directives()
+            .iter()
+            .map(|directive| {
+                (
+                    directive.name.as_str(),
+                    directive.parameters.join(" "),
+                    directive.span.start,
+                    directive.span.end,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            directives,
+            vec![
+                (
+                    "YAML",
+                    "1.1".into(),
+                    Position { line: 1, col: 0 },
+                    Position { line: 1, col: 9 }
+                ),
+                (
+                    "TAG",
+                    "!e! tag:example.com,2000:".into(),
+                    Position { line: 2, col: 0 },
+                    Position { line: 2, col: 30 }
+                ),
+                (
+                    "YAML",
+                    "1.1".into(),
+                    Position { line: 7, col: 0 },
+                    Position { line: 7, col: 9 }
+                )
+            ]
+        );
+        let markers = positions
+            .documents()
+            .iter()
+            .map(|document| {
+                (
+                    document.start.as_ref().map(|span| span.start.line),
+                    document.end.as_ref().map(|span| span.start.line),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(markers, vec![(Some(3), Some(6)), (Some(8), None)]);
+        Ok(())
+    }
+}
@@ -0,0 +1,215 @@
+use crate::{path, Pointer, Position, Positions};
+
+/// A part of a dotted path
+enum Segment<'a> {
+    /// Keys joined by separators, split according to the keys the document has
+    Keys(&'a str),
+    /// A bracketed index or quoted key, taken as is
+    Token(&'a str),
+}
+
+/// Splits a dotted path into runs of separated keys and bracketed tokens,
+/// or `None` when a bracket isn't closed
+fn segments(
+    path: &str,
+    separator: char,
+) -> Option<Vec<Segment<'_>>> {
+    let mut segments = Vec::new();
+    let mut rest = path.strip_prefix(separator).unwrap_or(path);
+    while !rest.is_empty() {
+        rest = match rest.strip_prefix('[') {
+            Some(bracketed) => {
+                let (token, after) = match bracketed.chars().next() {
+                    Some(quote @ '"') | Some(quote @ '\'') => {
+                        let end = bracketed[1..].find(quote)? + 1;
+                        (&bracketed[1..end], bracketed[end + 1..].strip_prefix(']')?)
+                    }
+                    _ => {
+                        let end = bracketed.find(']')?;
+                        (&bracketed[..end], &bracketed[end + 1..])
+                    }
+                };
+                segments.push(Segment::Token(token));
+                after.strip_prefix(separator).unwrap_or(after)
+            }
+            None => {
+                let end = rest.find('[').unwrap_or(rest.len());
+                let keys = &rest[..end];
+                segments.push(Segment::Keys(keys.strip_suffix(separator).unwrap_or(keys)));
+                &rest[end..]
+            }
+        };
+    }
+    Some(segments)
+}
+
+impl Positions {
+    /// Gets a node's position given a dotted path, such as `spec.containers[0].image`,
+    /// as jq and yq write them
+    ///
+    /// See [`dotted_pointer`](#method.dotted_pointer) for how paths resolve
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::Position;
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("spec:\n  containers:\n    - image: nginx\n")?;
+    /// assert_eq!(
+    ///     positions.get_dotted("spec.containers[0].image"),
+    ///     Some(&Position { line: 3, col: 6 })
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_dotted<P>(
+        &self,
+        path: P,
+    ) -> Option<&Position>
+    where
+        P: AsRef<str>,
+    {
+        self.get_dotted_with(path, '.')
+    }
+
+    /// Gets a node's position given a path of keys joined by `separator`
+    pub fn get_dotted_with<P>(
+        &self,
+        path: P,
+        separator: char,
+    ) -> Option<&Position>
+    where
+        P: AsRef<str>,
+    {
+        self.dotted(path.as_ref(), separator)
+            .and_then(|(ptr, _)| self.get(ptr))
+    }
+
+    /// Translates a path of keys joined by `separator` into the JSON Pointer path
+    /// of the node it refers to, or `None` when there is no such node
+    ///
+    /// A leading separator is optional and an empty path refers to the root. Sequence items
+    /// are indexed in brackets, `items[0]`, or as keys, `items.0`. Since keys may themselves
+    /// contain the separator, keys are split according to the keys the document has, preferring
+    /// the longest. Keys can also be quoted in brackets, `metadata.labels["app.kubernetes.io/name"]`,
+    /// to be taken as they are
+    ///
+    /// ```rust,edition2018
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("labels:\n  app.kubernetes.io/name: web\n")?;
+    /// let pointer = positions.dotted_pointer(".labels.app.kubernetes.io/name", '.');
+    /// assert_eq!(
+    ///     pointer.as_ref().map(|pointer| pointer.as_str()),
+    ///     Some("/labels/app.kubernetes.io~1name")
+    /// );
+    /// assert_eq!(
+    ///     positions.dotted_pointer("labels['app.kubernetes.io/name']", '.'),
+    ///     pointer
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn dotted_pointer<P>(
+        &self,
+        path: P,
+        separator: char,
+    ) -> Option<Pointer>
+    where
+        P: AsRef<str>,
+    {
+        self.dotted(path.as_ref(), separator)
+            .map(|(_, tokens)| Pointer::from_tokens(tokens))
+    }
+
+    /// Resolves a dotted path into the pointer the node is indexed under, and its unescaped tokens
+    fn dotted(
+        &self,
+        path: &str,
+        separator: char,
+    ) -> Option<(String, Vec<String>)> {
+        let mut tokens = Vec::<String>::new();
+        for segment in segments(path, separator)? {
+            let ptr = tokens
+                .iter()
+                .map(|token| format!("/{}", path::escape(token)))
+                .collect::<String>();
+            match segment {
+                Segment::Keys(keys) => {
+                    if !self.split_keys(&ptr, keys, separator, &mut tokens) {
+                        return None;
+                    }
+                }
+                Segment::Token(token) => tokens.push(token.to_owned()),
+            }
+        }
+        let ptr = tokens
+            .iter()
+            .map(|token| format!("/{}", path::escape(token)))
+            .collect::<String>();
+        Some(ptr)
+            .filter(|ptr| self.contains(ptr))
+            .map(|ptr| (ptr, tokens))
+    }
+
+    /// Splits keys joined by separators into the tokens of nodes nested within `ptr`,
+    /// trying longer keys before shorter ones
+    fn split_keys(
+        &self,
+        ptr: &str,
+        keys: &str,
+        separator: char,
+        tokens: &mut Vec<String>,
+    ) -> bool {
+        if keys.is_empty() {
+            return true;
+        }
+        let ends = keys
+            .match_indices(separator)
+            .map(|(idx, _)| idx)
+            .chain(std::iter::once(keys.len()))
+            .collect::<Vec<_>>();
+        for end in ends.into_iter().rev() {
+            let (token, rest) = keys.split_at(end);
+            let child = format!("{}/{}", ptr, path::escape(token));
+            if !self.contains(&child) {
+                continue;
+            }
+            tokens.push(token.to_owned());
+            let rest = rest.strip_prefix(separator).unwrap_or(rest);
+            if self.split_keys(&child, rest, separator, tokens) {
+                return true;
+            }
+            tokens.pop();
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_str, Position, Result};
+
+    #[test]
+    fn resolves_dotted_paths() -> Result<()> {
+        let positions =
+            from_str("a:\n  b.c:\n    - d: 1\n  b:\n    c: 2\nx/y: {'~': 3}\nitems: [[q]]\n")?;
+        let pointer = |path: &str| {
+            positions
+                .dotted_pointer(path, '.')
+                .map(|pointer| pointer.as_str().to_owned())
+        };
+        assert_eq!(pointer("a.b.c[0].d"), Some("/a/b.c/0/d".into()));
+        assert_eq!(pointer("a.b.c"), Some("/a/b.c".into()));
+        assert_eq!(pointer("a.b[\"c\"]"), Some("/a/b/c".into()));
+        assert_eq!(pointer("x/y.~"), Some("/x~1y/~0".into()));
+        assert_eq!(pointer(".items[0][0]"), Some("/items/0/0".into()));
+        assert_eq!(pointer("items.0.0"), Some("/items/0/0".into()));
+        assert_eq!(pointer(""), Some("".into()));
+        assert_eq!(pointer("a.missing"), None);
+        assert_eq!(pointer("items[0"), None);
+        assert_eq!(
+            positions.get_dotted_with("a:b.c:0", ':'),
+            Some(&Position { line: 3, col: 6 })
+        );
+        Ok(())
+    }
+}
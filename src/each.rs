@@ -0,0 +1,202 @@
+use crate::{lookup::complex_key, path, source::Source, Error, Options, Position, Result};
+use yaml_rust::parser::{Event, Parser};
+
+/// A collection whose nodes are being visited
+struct Frame {
+    sequence: bool,
+    /// Index of the next item of a sequence
+    next: usize,
+    /// Key of a mapping entry whose value is next, and its position. Keys that
+    /// can't be rendered are `None`
+    key: Option<(Option<String>, Position)>,
+    /// Length of the path before the collection's token was pushed onto it, if it was
+    parent: Option<usize>,
+}
+
+/// Calls `visit` with the JSON Pointer path, position, and scalar value of each node of utf8
+/// text as it's parsed, without building an index
+///
+/// Nodes are visited in document order, each once per occurrence, so the nodes of repeated keys
+/// are visited for each of them. Positions and values are those
+/// [`Positions::get_value`](struct.Positions.html#method.get_value) reports, collections and
+/// aliases having no value. As with the index, tokens are escaped and the document root
+/// has none. Entries with keys which can't be rendered, such as keys holding aliases, are
+/// skipped along with their contents
+///
+/// ```rust,edition2018
+/// use lincolns::{for_each, Position};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let mut visited = Vec::new();
+/// for_each("name: web\nports: [80]\n", |ptr, position, value| {
+///     visited.push((ptr.to_owned(), position.line, value.map(str::to_owned)));
+/// })?;
+/// assert_eq!(
+///     visited,
+///     vec![
+///         ("/name".into(), 1, Some("web".into())),
+///         ("/ports".into(), 2, None),
+///         ("/ports/0".into(), 2, Some("80".into())),
+///     ]
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn for_each<S, F>(
+    s: S,
+    mut visit: F,
+) -> Result<()>
+where
+    S: AsRef<str>,
+    F: FnMut(&str, Position, Option<&str>),
+{
+    let text = s.as_ref();
+    let options = Options::default();
+    let source = Source::new(text, &options);
+    let mut parser = Parser::new(text.chars());
+    let mut frames = Vec::<Frame>::new();
+    let mut path = String::new();
+    // nodes skipped along with their keys, by nesting depth
+    let mut skipped = 0;
+    // a collection item whose position depends on its first child, as the index positions them
+    let mut pending: Option<Position> = None;
+    loop {
+        let (event, marker) = parser.next().map_err(|error| Error::Parse {
+            position: source.marker(error.marker().line(), error.marker().col()),
+            error,
+        })?;
+        let position = source.marker(marker.line(), marker.col());
+        let starts = matches!(event, Event::SequenceStart(_) | Event::MappingStart(_));
+        let ends = matches!(event, Event::SequenceEnd | Event::MappingEnd);
+        if let Some(start) = pending.take() {
+            let node = starts || matches!(event, Event::Scalar(..) | Event::Alias(_));
+            visit(&path, if node { start.min(position) } else { start }, None);
+        }
+        if skipped > 0 {
+            if starts {
+                skipped += 1;
+            } else if ends {
+                skipped -= 1;
+            }
+            continue;
+        }
+        match &event {
+            Event::StreamEnd => return Ok(()),
+            Event::DocumentStart => {
+                frames.clear();
+                path.clear();
+                continue;
+            }
+            _ if ends => {
+                if let Some(parent) = frames.pop().and_then(|frame| frame.parent) {
+                    path.truncate(parent);
+                }
+                continue;
+            }
+            Event::Scalar(..)
+            | Event::Alias(_)
+            | Event::SequenceStart(_)
+            | Event::MappingStart(_) => {}
+            _ => continue,
+        }
+        let (token, at, item) = match frames.last_mut() {
+            None => {
+                if starts {
+                    frames.push(Frame {
+                        sequence: matches!(event, Event::SequenceStart(_)),
+                        next: 0,
+                        key: None,
+                        parent: None,
+                    });
+                }
+                continue;
+            }
+            Some(frame) if frame.sequence => {
+                frame.next += 1;
+                (Some((frame.next - 1).to_string()), position, true)
+            }
+            Some(frame) => match frame.key.take() {
+                Some((key, at)) => (key, at, false),
+                None => {
+                    let key = match &event {
+                        Event::Scalar(value, ..) => Some(value.clone()),
+                        _ if starts => complex_key(&mut parser, &source, &event)?,
+                        _ => None,
+                    };
+                    frame.key = Some((key, position));
+                    continue;
+                }
+            },
+        };
+        let token = match token {
+            Some(token) => token,
+            None => {
+                if starts {
+                    skipped = 1;
+                }
+                continue;
+            }
+        };
+        let parent = path.len();
+        path.push('/');
+        path.push_str(&path::escape(&token));
+        match &event {
+            Event::Scalar(value, ..) => {
+                visit(&path, at, Some(value));
+                path.truncate(parent);
+            }
+            Event::Alias(_) => {
+                visit(&path, at, None);
+                path.truncate(parent);
+            }
+            _ => {
+                frames.push(Frame {
+                    sequence: matches!(event, Event::SequenceStart(_)),
+                    next: 0,
+                    key: None,
+                    parent: Some(parent),
+                });
+                if item {
+                    pending = Some(at);
+                } else {
+                    visit(&path, at, None);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::from_str;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn visits_what_the_index_holds() -> Result<()> {
+        let docs = [
+            include_str!("../tests/data/example.yml"),
+            include_str!("../tests/data/example.json"),
+            "a:\n  - b: 1\n    'c~d': [x, {y: z}]\n  -\n    - deep\n? [complex, key]\n: value\nf: &anchor {g: 1}\nh: *anchor\n",
+            "- - 1\n  - 2\n- {}\n",
+            "? [a, {b: c}]\n: 1\n? {x: [y]}\n: {z: 2}\n&k k: 3\n? *k\n: {skipped: 4}\nafter: 5\n",
+        ];
+        for doc in &docs {
+            let positions = from_str(doc)?;
+            let mut visited = BTreeMap::new();
+            for_each(doc, |ptr, position, value| {
+                visited.insert(ptr.to_owned(), (position, value.map(str::to_owned)));
+            })?;
+            let indexed = positions
+                .iter()
+                .map(|(ptr, position)| {
+                    let value = positions.get_value(ptr).map(|(value, _)| value.to_owned());
+                    (ptr.clone(), (*position, value))
+                })
+                .collect::<BTreeMap<_, _>>();
+            assert_eq!(visited, indexed, "{}", doc);
+        }
+        assert!(for_each("a: [1", |_, _, _| ()).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,747 @@
+//! Format-preserving edits of YAML and JSON text, guided by the spans of its indexed nodes
+use crate::{from_str, path, Error, NodeKind, Positions, Result, ScalarStyle};
+use std::ops::Range;
+
+/// Replaces the value of the scalar a JSON Pointer path refers to, returning the edited text
+///
+/// Only the scalar's own text is rewritten, leaving comments, indentation, tags, and
+/// anchors around it as they were. The new value is written in the scalar's style:
+/// plain scalars stay plain unless the value would then read differently, in which case it
+/// is double quoted, as are values which single quoted and block scalars can't hold.
+/// Plain values are written as given, so `"3"` replaces a plain scalar with an integer,
+/// though within JSON text only numbers, booleans, and null are written unquoted
+///
+/// ```rust,edition2018
+/// # fn main() -> lincolns::Result<()>  {
+/// let yaml = "spec:\n  image: 'nginx:1.19' # pinned\n  replicas: 1\n";
+/// let yaml = lincolns::edit::set(yaml, "/spec/image", "nginx:1.21")?;
+/// let yaml = lincolns::edit::set(yaml, "/spec/replicas", "3")?;
+/// assert_eq!(yaml, "spec:\n  image: 'nginx:1.21' # pinned\n  replicas: 3\n");
+/// # Ok(())
+/// # }
+/// ```
+pub fn set<S, P, V>(
+    text: S,
+    ptr: P,
+    value: V,
+) -> Result<String>
+where
+    S: AsRef<str>,
+    P: AsRef<str>,
+    V: AsRef<str>,
+{
+    let mut editor = Editor::new(text.as_ref())?;
+    editor.set(ptr, value)?;
+    Ok(editor.text)
+}
+
+/// Adds a scalar to the mapping or sequence a JSON Pointer path refers to, returning
+/// the edited text
+///
+/// See [`Editor::insert`](struct.Editor.html#method.insert)
+pub fn insert<S, P, K, V>(
+    text: S,
+    ptr: P,
+    key: K,
+    value: V,
+) -> Result<String>
+where
+    S: AsRef<str>,
+    P: AsRef<str>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let mut editor = Editor::new(text.as_ref())?;
+    editor.insert(ptr, key, value)?;
+    Ok(editor.text)
+}
+
+/// Removes the mapping entry or sequence item a JSON Pointer path refers to, returning
+/// the edited text
+///
+/// See [`Editor::remove`](struct.Editor.html#method.remove)
+pub fn remove<S, P>(
+    text: S,
+    ptr: P,
+) -> Result<String>
+where
+    S: AsRef<str>,
+    P: AsRef<str>,
+{
+    let mut editor = Editor::new(text.as_ref())?;
+    editor.remove(ptr)?;
+    Ok(editor.text)
+}
+
+/// Text being edited along with the positions of its nodes, which are remapped as each
+/// edit is made so that edits can follow one another without the text being loaded again
+///
+/// Only node positions are remapped. Comments, anchors, and other tables of the
+/// [`Positions`](../struct.Positions.html) an editor starts with are left out after an edit
+///
+/// ```rust,edition2018
+/// use lincolns::{edit::Editor, Position};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let mut editor = Editor::new("spec:\n  ports: [80]\n  debug: true\n")?;
+/// editor.remove("/spec/debug")?;
+/// editor.insert("/spec/ports", "-", "443")?;
+/// editor.insert("/spec", "image", "nginx")?;
+/// assert_eq!(editor.text(), "spec:\n  ports: [80, 443]\n  image: nginx\n");
+/// assert_eq!(
+///     editor.positions().get("/spec/image"),
+///     Some(&Position { line: 3, col: 2 })
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Editor {
+    text: String,
+    positions: Positions,
+}
+
+/// Where a mapping entry or sequence item begins and ends within the text
+struct Item {
+    pointer: String,
+    /// From its key, its value, or for block sequences, its `-`
+    start: usize,
+    end: usize,
+}
+
+impl Editor {
+    /// Loads text to edit
+    pub fn new<S>(text: S) -> Result<Self>
+    where
+        S: Into<String>,
+    {
+        let text = text.into();
+        let positions = from_str(&text)?;
+        Ok(Editor { text, positions })
+    }
+
+    /// The text as edited so far
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The positions of nodes within the text as edited so far
+    pub fn positions(&self) -> &Positions {
+        &self.positions
+    }
+
+    /// Returns the edited text and the positions of its nodes
+    pub fn into_parts(self) -> (String, Positions) {
+        (self.text, self.positions)
+    }
+
+    /// Replaces the value of the scalar a JSON Pointer path refers to, as
+    /// [`set`](fn.set.html) does
+    pub fn set<P, V>(
+        &mut self,
+        ptr: P,
+        value: V,
+    ) -> Result<()>
+    where
+        P: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let (ptr, value) = (ptr.as_ref(), value.as_ref());
+        let (text, positions) = (&self.text, &self.positions);
+        let (ptr, mut bytes, style) = match (
+            positions.entry_at(ptr),
+            positions.span(ptr),
+            positions.style(ptr),
+        ) {
+            (Some(entry), Some(span), Some(style)) => {
+                (entry.pointer.to_owned(), span.bytes.clone(), style)
+            }
+            _ => return Err(Error::InvalidEdit(format!("{} is not a scalar", ptr))),
+        };
+        // JSON has no plain strings, only numbers, booleans, and null
+        let json = text.trim_start().starts_with(['{', '[']);
+        let rendered = match style {
+            ScalarStyle::Plain if json && !json_literal(value) => double_quoted(value),
+            ScalarStyle::Plain if plain(value) => value.to_owned(),
+            ScalarStyle::SingleQuoted if !value.contains('\n') => {
+                format!("'{}'", value.replace('\'', "''"))
+            }
+            ScalarStyle::Literal | ScalarStyle::Folded => {
+                block(text, bytes.clone(), style, value).unwrap_or_else(|| double_quoted(value))
+            }
+            _ => double_quoted(value),
+        };
+        let mut with = rendered.clone();
+        // empty scalars have no text of their own, and are placed where their key ends
+        if bytes.is_empty() {
+            if !text[bytes.start..].starts_with(':') {
+                return Err(Error::InvalidEdit(format!(
+                    "the empty value of {} can't be replaced",
+                    ptr
+                )));
+            }
+            bytes = bytes.start + 1..bytes.start + 1;
+            with.insert(0, ' ');
+        }
+        let at = bytes.start + with.len() - rendered.len();
+        self.splice(
+            bytes,
+            &with,
+            // the scalar's key is kept as its value is grafted over it
+            |moved| Some(moved.to_owned()),
+            &rendered,
+            at,
+            |grafted| Some(format!("{}{}", ptr, grafted)),
+        )
+    }
+
+    /// Adds a scalar to the mapping or sequence a JSON Pointer path refers to
+    ///
+    /// Mappings gain an entry with `key` after their last, failing if they already have one.
+    /// Sequences gain an item before the one at index `key`, or after their last given `-`.
+    /// Values are written plain when they can be, and double quoted otherwise, as are
+    /// strings and keys within JSON text. Entries and items of block collections are
+    /// written on their own line, indented as their siblings are
+    pub fn insert<P, K, V>(
+        &mut self,
+        ptr: P,
+        key: K,
+        value: V,
+    ) -> Result<()>
+    where
+        P: AsRef<str>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let (ptr, key, value) = (ptr.as_ref(), key.as_ref(), value.as_ref());
+        let positions = &self.positions;
+        let text = &self.text;
+        let invalid = |reason: &str| Err(Error::InvalidEdit(format!("{} {}", ptr, reason)));
+        let (parent, span) = match (positions.entry_at(ptr), positions.span(ptr)) {
+            (Some(entry), Some(span)) => (entry.pointer.to_owned(), span.bytes.clone()),
+            (None, Some(span)) if ptr.is_empty() => (String::new(), span.bytes.clone()),
+            _ => return invalid("is missing"),
+        };
+        let items = self.items(&parent);
+        let flow = text[span.start..].starts_with(['{', '[']);
+        let json = flow && text.trim_start().starts_with(['{', '[']);
+        let value = if json && !json_literal(value) {
+            double_quoted(value)
+        } else {
+            scalar(value)
+        };
+        let line_ending = positions.line_ending().as_str();
+        let (entry, index) = match positions.kind(&parent) {
+            Some(NodeKind::Mapping) => {
+                if positions.contains(format!("{}/{}", parent, path::escape(key))) {
+                    return invalid(&format!("already has the key {:?}", key));
+                }
+                let key = if json {
+                    double_quoted(key)
+                } else {
+                    scalar(key)
+                };
+                (format!("{}: {}", key, value), None)
+            }
+            Some(NodeKind::Sequence) => {
+                let index = match key {
+                    "-" => items.len(),
+                    _ => match key.parse::<usize>() {
+                        Ok(index) if index <= items.len() => index,
+                        _ => return invalid(&format!("has no index {}", key)),
+                    },
+                };
+                (value, Some(index))
+            }
+            _ => return invalid("is not a mapping or sequence"),
+        };
+        let before = index.and_then(|index| items.get(index));
+        let (at, with, start) = match (flow, before, items.last()) {
+            (true, Some(item), _) => (item.start, format!("{}, ", entry), 0),
+            (true, None, Some(last)) => (last.end, format!(", {}", entry), 2),
+            (true, None, None) => (span.start + 1, entry.clone(), 0),
+            (false, Some(item), _) => {
+                let indent = " ".repeat(item.start - line_start(text, item.start));
+                // after the item's `-`, so the sequence still starts there
+                (
+                    item.start + 1,
+                    format!(" {}{}{}-", entry, line_ending, indent),
+                    1,
+                )
+            }
+            (false, None, Some(last)) => {
+                let first = &items[0];
+                let indent = " ".repeat(first.start - line_start(text, first.start));
+                let dash = if index.is_some() { "- " } else { "" };
+                let with = format!("{}{}{}{}", line_ending, indent, dash, entry);
+                let start = with.len() - entry.len();
+                (line_end(text, last.end), with, start)
+            }
+            (false, None, None) => return invalid("has no entries to follow"),
+        };
+        // the entry is indexed on its own as the sole entry of a flow collection
+        let (snippet, graft) = match index {
+            Some(index) => (format!("[{}]", entry), format!("{}/{}", parent, index)),
+            None => (format!("{{{}}}", entry), parent.clone()),
+        };
+        self.splice(
+            at..at,
+            &with,
+            |moved| match index {
+                Some(index) => renumber(&parent, moved, index, true),
+                None => Some(moved.to_owned()),
+            },
+            &snippet,
+            at + start - 1,
+            |grafted| match index {
+                Some(_) if !grafted.is_empty() => Some(graft.clone()),
+                None if !grafted.is_empty() => Some(format!("{}{}", graft, grafted)),
+                _ => None,
+            },
+        )
+    }
+
+    /// Removes the mapping entry or sequence item a JSON Pointer path refers to, along
+    /// with everything nested within it
+    ///
+    /// Entries and items of block collections are removed along with their line, while
+    /// removing the last of them leaves an empty flow collection, `{}` or `[]`
+    pub fn remove<P>(
+        &mut self,
+        ptr: P,
+    ) -> Result<()>
+    where
+        P: AsRef<str>,
+    {
+        let ptr = ptr.as_ref();
+        let text = &self.text;
+        let ptr = match self.positions.entry_at(ptr) {
+            Some(entry) => entry.pointer.to_owned(),
+            None => return Err(Error::InvalidEdit(format!("{} is missing", ptr))),
+        };
+        let parent = ptr.rsplit_once('/').map_or("", |(parent, _)| parent);
+        let items = self.items(parent);
+        let idx = match items.iter().position(|item| item.pointer == ptr) {
+            Some(idx) => idx,
+            None => return Err(Error::InvalidEdit(format!("{} can't be removed", ptr))),
+        };
+        let sequence = self.positions.kind(parent) == Some(NodeKind::Sequence);
+        let span = self
+            .positions
+            .span(parent)
+            .map(|span| span.bytes.clone())
+            .unwrap_or_default();
+        let flow = text[span.start..].starts_with(['{', '[']);
+        let item = &items[idx];
+        let prev = idx.checked_sub(1).map(|prev| &items[prev]);
+        let (bytes, with) = match (items.get(idx + 1), prev) {
+            (Some(next), _) => (item.start..next.start, ""),
+            (None, Some(prev)) if flow => (prev.end..item.end, ""),
+            (None, Some(prev)) => (line_end(text, prev.end)..line_end(text, item.end), ""),
+            (None, None) if flow => (item.start..item.end, ""),
+            (None, None) => (item.start..item.end, if sequence { "[]" } else { "{}" }),
+        };
+        let nested = format!("{}/", ptr);
+        self.splice(
+            bytes,
+            with,
+            |moved| {
+                if sequence {
+                    renumber(parent, moved, idx, false)
+                } else if moved == ptr || moved.starts_with(&nested) {
+                    None
+                } else {
+                    Some(moved.to_owned())
+                }
+            },
+            "",
+            0,
+            |_| None,
+        )
+    }
+
+    /// The entries or items of the collection at `parent`, in document order
+    fn items(
+        &self,
+        parent: &str,
+    ) -> Vec<Item> {
+        let block = self
+            .positions
+            .span(parent)
+            .is_some_and(|span| !self.text[span.bytes.start..].starts_with(['{', '[']));
+        let sequence = self.positions.kind(parent) == Some(NodeKind::Sequence);
+        self.positions
+            .children(parent)
+            .into_iter()
+            .filter_map(|(ptr, _)| {
+                let span = self.positions.span(ptr)?;
+                let start = match self.positions.key_span(ptr) {
+                    Some(key) => key.bytes.start,
+                    None if block && sequence => self.text[..span.bytes.start].rfind('-')?,
+                    None => span.bytes.start,
+                };
+                Some(Item {
+                    pointer: ptr.to_owned(),
+                    start,
+                    end: span.bytes.end,
+                })
+            })
+            .collect()
+    }
+
+    /// Replaces `bytes` of the text, moving pointers and offsets of the nodes indexed as
+    /// `pointer` and the edit direct, then indexes `snippet`, text loaded on its own
+    /// found at `at` within the edited text, with pointers as `graft` directs
+    fn splice<P, G>(
+        &mut self,
+        bytes: Range<usize>,
+        with: &str,
+        pointer: P,
+        snippet: &str,
+        at: usize,
+        graft: G,
+    ) -> Result<()>
+    where
+        P: Fn(&str) -> Option<String>,
+        G: Fn(&str) -> Option<String>,
+    {
+        let grafted = from_str(snippet)?;
+        let mut text = String::with_capacity(self.text.len() + with.len());
+        text.push_str(&self.text[..bytes.start]);
+        text.push_str(with);
+        text.push_str(&self.text[bytes.end..]);
+        // spans ending where text is inserted end before it
+        let moved = |offset: usize, end: bool| {
+            if offset < bytes.start || (end && offset == bytes.start) {
+                offset
+            } else if offset >= bytes.end {
+                offset + with.len() - bytes.len()
+            } else {
+                bytes.start
+            }
+        };
+        let mut positions = self.positions.remap(&self.text, &text, pointer, moved);
+        positions.graft(grafted.remap(snippet, &text, graft, |offset, _| offset + at));
+        self.text = text;
+        self.positions = positions;
+        Ok(())
+    }
+}
+
+/// Moves a pointer within the items of the sequence at `parent` past an item inserted at
+/// `index`, or back over one removed from there, dropping the pointers of a removed item
+fn renumber(
+    parent: &str,
+    ptr: &str,
+    index: usize,
+    inserted: bool,
+) -> Option<String> {
+    let (item, nested) = match ptr
+        .strip_prefix(parent)
+        .and_then(|rest| rest.strip_prefix('/'))
+    {
+        Some(rest) => rest
+            .split_once('/')
+            .map_or((rest, None), |(item, nested)| (item, Some(nested))),
+        None => return Some(ptr.to_owned()),
+    };
+    let item = match item.parse::<usize>() {
+        Ok(item) if item >= index => item,
+        _ => return Some(ptr.to_owned()),
+    };
+    let item = match (inserted, item == index) {
+        (true, _) => item + 1,
+        (false, true) => return None,
+        (false, false) => item - 1,
+    };
+    Some(match nested {
+        Some(nested) => format!("{}/{}/{}", parent, item, nested),
+        None => format!("{}/{}", parent, item),
+    })
+}
+
+/// Byte offset of the start of the line containing an offset
+fn line_start(
+    text: &str,
+    offset: usize,
+) -> usize {
+    text[..offset].rfind('\n').map_or(0, |idx| idx + 1)
+}
+
+/// Byte offset of the end of the line containing an offset, before its line ending
+fn line_end(
+    text: &str,
+    offset: usize,
+) -> usize {
+    let end = text[offset..]
+        .find('\n')
+        .map_or(text.len(), |idx| offset + idx);
+    if text[..end].ends_with('\r') {
+        end - 1
+    } else {
+        end
+    }
+}
+
+/// Writes a value plain when it reads as itself, and double quoted otherwise
+fn scalar(value: &str) -> String {
+    if plain(value) {
+        value.to_owned()
+    } else {
+        double_quoted(value)
+    }
+}
+
+/// Returns true for values JSON reads as numbers, booleans, or null
+fn json_literal(value: &str) -> bool {
+    matches!(value, "true" | "false" | "null")
+        || (value.starts_with(|c: char| c == '-' || c.is_ascii_digit())
+            && value.ends_with(|c: char| c.is_ascii_digit())
+            && value.parse::<f64>().is_ok())
+}
+
+/// Returns true if a value reads as itself written as a plain scalar, in block or flow context
+fn plain(value: &str) -> bool {
+    let mut chars = value.chars();
+    let leading = match chars.next() {
+        // indicators may only start a plain scalar when followed by a non-space
+        Some('-') | Some('?') | Some(':') => chars.next().is_some_and(|c| !c.is_whitespace()),
+        Some(c) => !c.is_whitespace() && !"#&*!|>'\"%@`".contains(c),
+        None => false,
+    };
+    leading
+        && !value.ends_with(char::is_whitespace)
+        && !value.ends_with(':')
+        && !value.contains(": ")
+        && !value.contains(" #")
+        && !value.contains(|c: char| c.is_control() || ",[]{}".contains(c))
+}
+
+/// Writes a value in double quotes, escaping it as needed
+pub(crate) fn double_quoted(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Writes a value as a block scalar replacing the one at `bytes`, keeping its indentation
+/// and any comment following its header, or `None` if the style can't hold the value
+fn block(
+    text: &str,
+    bytes: Range<usize>,
+    style: ScalarStyle,
+    value: &str,
+) -> Option<String> {
+    let content = value.trim_end_matches('\n');
+    let folded = style == ScalarStyle::Folded;
+    // leading spaces would need an indentation indicator, and aren't folded
+    let indented = |line: &str| line.starts_with([' ', '\t']);
+    if content.is_empty()
+        || content.starts_with('\n')
+        || indented(content)
+        || (folded && content.split('\n').any(indented))
+    {
+        return None;
+    }
+    let raw = &text[bytes.clone()];
+    let (header, body) = raw.split_once('\n').unwrap_or((raw, ""));
+    let indent = body
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start_matches(' ').len())
+        .unwrap_or_else(|| {
+            let line = &text[text[..bytes.start].rfind('\n').map_or(0, |idx| idx + 1)..];
+            line.len() - line.trim_start_matches(' ').len() + 2
+        });
+    let chomp = match value.len() - content.len() {
+        0 => "-",
+        1 => "",
+        _ => "+",
+    };
+    let comment = header.trim_start_matches(|c: char| "|>+-".contains(c) || c.is_ascii_digit());
+    let mut rendered = format!("{}{}{}", if folded { '>' } else { '|' }, chomp, comment);
+    let mut prev = "";
+    for line in content.split('\n') {
+        rendered.push('\n');
+        // folding joins lines broken once, so each break within text is written twice
+        if folded && !prev.is_empty() {
+            rendered.push('\n');
+        }
+        if !line.is_empty() {
+            rendered.push_str(&" ".repeat(indent));
+            rendered.push_str(line);
+        }
+        prev = line;
+    }
+    // kept trailing line breaks beyond the one ending the text follow as empty lines
+    rendered.push_str(&"\n".repeat((value.len() - content.len()).saturating_sub(1)));
+    Some(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(
+        text: &str,
+        ptr: &str,
+    ) -> Option<String> {
+        let positions = from_str(text).ok()?;
+        positions.get_value(ptr).map(|(value, _)| value.to_owned())
+    }
+
+    #[test]
+    fn values_round_trip_in_each_style() -> Result<()> {
+        let yaml = "a: plain # note\nb: 'single'\nc: \"double\"\nd: |\n  lit\ne: >-\n    fold\n    ed\nf:\ng: [x, y]\n";
+        let values = [
+            "text",
+            "with: colon",
+            "it's",
+            "two\nlines",
+            "two\n\nbreaks\n",
+            "kept\n\n",
+            "-1",
+            "# hash",
+            " spaced",
+            "quote\" and \\",
+            "",
+        ];
+        for ptr in &["/a", "/b", "/c", "/d", "/e", "/f", "/g/1"] {
+            for new in &values {
+                let edited = set(yaml, ptr, new)?;
+                assert_eq!(
+                    value(&edited, ptr).as_deref(),
+                    Some(*new),
+                    "{} = {:?} in\n{}",
+                    ptr,
+                    new,
+                    edited
+                );
+                assert_eq!(value(&edited, "/g/0").as_deref(), Some("x"));
+            }
+        }
+        assert_eq!(
+            set(yaml, "/a", "other")?,
+            yaml.replace("a: plain", "a: other")
+        );
+        assert_eq!(
+            set(yaml, "/d", "a\nb\n")?,
+            yaml.replace("  lit", "  a\n  b")
+        );
+        assert!(set(yaml, "/g", "x").is_err());
+        assert!(set(yaml, "/missing", "x").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn values_stay_json_within_json() -> Result<()> {
+        let json = "{\"a\": 1, \"b\": [true]}";
+        assert_eq!(
+            set(json, "/a", "hello")?,
+            "{\"a\": \"hello\", \"b\": [true]}"
+        );
+        assert_eq!(set(json, "/a", "2.5")?, "{\"a\": 2.5, \"b\": [true]}");
+        assert_eq!(set(json, "/b/0", "null")?, "{\"a\": 1, \"b\": [null]}");
+        assert_eq!(set(json, "/b/0", "yes")?, "{\"a\": 1, \"b\": [\"yes\"]}");
+        Ok(())
+    }
+
+    /// Asserts an editor's positions are those of its text loaded anew
+    fn assert_remapped(editor: &Editor) {
+        let loaded = from_str(editor.text()).unwrap();
+        let positions = editor.positions();
+        assert_eq!(
+            positions.keys().collect::<Vec<_>>(),
+            loaded.keys().collect::<Vec<_>>(),
+            "in\n{}",
+            editor.text()
+        );
+        for (ptr, position) in loaded.iter() {
+            assert_eq!(
+                positions.get(ptr),
+                Some(position),
+                "{} in\n{}",
+                ptr,
+                editor.text()
+            );
+            assert_eq!(
+                positions.span(ptr),
+                loaded.span(ptr),
+                "{} in\n{}",
+                ptr,
+                editor.text()
+            );
+            assert_eq!(positions.key_span(ptr), loaded.key_span(ptr), "{}", ptr);
+            assert_eq!(positions.get_value(ptr), loaded.get_value(ptr), "{}", ptr);
+        }
+    }
+
+    #[test]
+    fn edits_remap_positions() -> Result<()> {
+        let mut editor = Editor::new(
+            "name: web # the name\nports:\n  - 80\n  - 443\nenv: {A: 1, B: 2}\nlist: [x, y]\n",
+        )?;
+        let edits: Vec<fn(&mut Editor) -> Result<()>> = vec![
+            |editor| editor.set("/name", "a longer name"),
+            |editor| editor.insert("/ports", "0", "22"),
+            |editor| editor.insert("/ports", "-", "8080"),
+            |editor| editor.remove("/ports/1"),
+            |editor| editor.insert("/env", "C", "three: 3"),
+            |editor| editor.remove("/env/A"),
+            |editor| editor.insert("/list", "1", "between"),
+            |editor| editor.remove("/list/2"),
+            |editor| editor.insert("/list", "0", "first"),
+            |editor| editor.insert("", "image", "nginx"),
+            |editor| editor.remove("/name"),
+            |editor| editor.set("/ports/0", "2\n2"),
+        ];
+        for edit in &edits {
+            edit(&mut editor)?;
+            assert_remapped(&editor);
+        }
+        assert_eq!(
+            editor.text(),
+            "ports:\n  - \"2\\n2\"\n  - 443\n  - 8080\nenv: {B: 2, C: \"three: 3\"}\nlist: [first, x, between]\nimage: nginx\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn removes_entries_and_items() -> Result<()> {
+        let yaml = "a:\n  - b: 1\n    c: 2 # two\n  - d\ne: [1]\n";
+        assert_eq!(
+            remove(yaml, "/a/0/b")?,
+            "a:\n  - c: 2 # two\n  - d\ne: [1]\n"
+        );
+        assert_eq!(remove(yaml, "/a/0/c")?, "a:\n  - b: 1\n  - d\ne: [1]\n");
+        assert_eq!(
+            remove(yaml, "/a/1")?,
+            "a:\n  - b: 1\n    c: 2 # two\ne: [1]\n"
+        );
+        assert_eq!(
+            remove(yaml, "/e/0")?,
+            "a:\n  - b: 1\n    c: 2 # two\n  - d\ne: []\n"
+        );
+        assert_eq!(remove("a:\n  b: 1\n", "/a/b")?, "a:\n  {}\n");
+        assert_eq!(
+            insert("{\"a\": [1]}", "", "b", "two")?,
+            "{\"a\": [1], \"b\": \"two\"}"
+        );
+        assert!(insert(yaml, "/e", "x", "1").is_err());
+        assert!(insert(yaml, "/e", "5", "1").is_err());
+        assert!(remove(yaml, "/missing").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{Error, Result};
+use std::{borrow::Cow, char::REPLACEMENT_CHARACTER};
+
+/// Character encodings a YAML stream may use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Utf8,
+    Utf16Be,
+    Utf16Le,
+    Utf32Be,
+    Utf32Le,
+}
+
+impl Encoding {
+    /// Detects the encoding of a stream from its byte order mark or, lacking one,
+    /// the pattern of null bytes its first character makes, as YAML 1.2 section 5.2 describes
+    fn detect(bytes: &[u8]) -> Encoding {
+        match bytes {
+            [0, 0, 0xFE, 0xFF, ..] | [0, 0, 0, _, ..] => Encoding::Utf32Be,
+            [0xFF, 0xFE, 0, 0, ..] | [_, 0, 0, 0, ..] => Encoding::Utf32Le,
+            [0xFE, 0xFF, ..] | [0, _, ..] => Encoding::Utf16Be,
+            [0xFF, 0xFE, ..] | [_, 0, ..] => Encoding::Utf16Le,
+            _ => Encoding::Utf8,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Utf16Be => "UTF-16BE",
+            Encoding::Utf16Le => "UTF-16LE",
+            Encoding::Utf32Be => "UTF-32BE",
+            Encoding::Utf32Le => "UTF-32LE",
+        }
+    }
+}
+
+/// Decodes a YAML stream into text, replacing invalid sequences when `lossy`
+///
+/// Byte order marks are decoded along with the rest of the text
+pub(crate) fn decode(
+    bytes: &[u8],
+    lossy: bool,
+) -> Result<Cow<'_, str>> {
+    let encoding = Encoding::detect(bytes);
+    let invalid =
+        |offset: usize| Error::Encoding(format!("invalid {} at byte {}", encoding.name(), offset));
+    match encoding {
+        Encoding::Utf8 if lossy => Ok(String::from_utf8_lossy(bytes)),
+        Encoding::Utf8 => Ok(Cow::Borrowed(std::str::from_utf8(bytes)?)),
+        Encoding::Utf16Be | Encoding::Utf16Le => {
+            let units = bytes.chunks(2).map(|pair| match (encoding, pair) {
+                (Encoding::Utf16Be, [hi, lo]) => Some(u16::from_be_bytes([*hi, *lo])),
+                (_, [lo, hi]) => Some(u16::from_le_bytes([*lo, *hi])),
+                _ => None,
+            });
+            let mut text = String::with_capacity(bytes.len() / 2);
+            let mut offset = 0;
+            // a trailing odd byte decodes as an unpaired surrogate would
+            for decoded in char::decode_utf16(units.map(|unit| unit.unwrap_or(0xDC00))) {
+                let c = match decoded {
+                    Ok(c) => c,
+                    Err(_) if lossy => REPLACEMENT_CHARACTER,
+                    Err(_) => return Err(invalid(offset)),
+                };
+                offset += c.len_utf16() * 2;
+                text.push(c);
+            }
+            Ok(Cow::Owned(text))
+        }
+        Encoding::Utf32Be | Encoding::Utf32Le => {
+            let mut text = String::with_capacity(bytes.len() / 4);
+            for (idx, quad) in bytes.chunks(4).enumerate() {
+                let c = match (encoding, quad) {
+                    (Encoding::Utf32Be, [a, b, c, d]) => u32::from_be_bytes([*a, *b, *c, *d]),
+                    (_, [a, b, c, d]) => u32::from_le_bytes([*a, *b, *c, *d]),
+                    _ => u32::MAX,
+                };
+                match char::from_u32(c) {
+                    Some(c) => text.push(c),
+                    None if lossy => text.push(REPLACEMENT_CHARACTER),
+                    None => return Err(invalid(idx * 4)),
+                }
+            }
+            Ok(Cow::Owned(text))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utf16le(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+    }
+
+    #[test]
+    fn decodes_by_byte_order_mark_or_null_pattern() -> Result<()> {
+        let text = "a: é\n";
+        let mut bom = vec![0xFF, 0xFE];
+        bom.extend(utf16le(text));
+        assert_eq!(decode(&bom, false)?, format!("\u{feff}{}", text));
+        assert_eq!(decode(&utf16le(text), false)?, text);
+        let be = text
+            .chars()
+            .flat_map(|c| (c as u32).to_be_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(decode(&be, false)?, text);
+        assert_eq!(decode(text.as_bytes(), false)?, text);
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_unpaired_surrogates_unless_lossy() -> Result<()> {
+        let mut bytes = utf16le("a: ");
+        bytes.extend(&[0x00, 0xD8]);
+        assert!(matches!(decode(&bytes, false), Err(Error::Encoding(_))));
+        assert_eq!(decode(&bytes, true)?, "a: \u{fffd}");
+        Ok(())
+    }
+}
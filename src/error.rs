@@ -1,15 +1,52 @@
+use crate::{Limit, PointerError, Position, Warning};
 use std::{error::Error as StdError, fmt, io, str::Utf8Error};
 use yaml_rust::ScanError;
 
 /// Possible errors that may occur while loading content
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
-    /// Failure to parse content
-    Parse(ScanError),
+    /// Failure to parse content, at the position the parser stopped
+    Parse {
+        error: ScanError,
+        position: Position,
+    },
     /// Failure to load data
     Io(io::Error),
     /// Failure to read data as utf8 text
     Utf8(Utf8Error),
+    /// Failure to decode data detected as UTF-16 or UTF-32
+    Encoding(String),
+    /// A mapping contained the same key more than once while loading with
+    /// [`DuplicateKeys::Error`](enum.DuplicateKeys.html#variant.Error)
+    DuplicateKey {
+        pointer: String,
+        first: Position,
+        second: Position,
+    },
+    /// Failure to deserialize a value, located at the pointer of the value when known
+    Deserialize {
+        message: String,
+        pointer: Option<String>,
+        position: Option<Position>,
+    },
+    /// A JSON Patch document which wasn't a sequence of operations
+    InvalidPatch(String),
+    /// An edit which couldn't be made to the node it targets
+    InvalidEdit(String),
+    /// A schema which couldn't be compiled for validation
+    InvalidSchema(String),
+    /// A file which couldn't be included, located at the node including it
+    InvalidInclude(String),
+    /// An invalid JSON Pointer
+    InvalidPointer(PointerError),
+    /// Content crossed a resource limit set by [`Options`](struct.Options.html)
+    LimitExceeded(Limit),
+    /// Content skipped while indexing which an
+    /// [`Options::on_unhandled`](struct.Options.html#method.on_unhandled) handler rejected
+    Unhandled(Warning),
+    /// Failure reported by a custom [`Backend`](trait.Backend.html)
+    Backend(Box<dyn StdError + Send + Sync>),
 }
 
 impl fmt::Display for Error {
@@ -18,18 +55,72 @@ impl fmt::Display for Error {
         f: &mut fmt::Formatter<'_>,
     ) -> std::result::Result<(), fmt::Error> {
         match self {
-            Error::Parse(ref err) => writeln!(f, "{}", err),
+            Error::Parse { ref error, .. } => writeln!(f, "{}", error),
             Error::Io(ref err) => writeln!(f, "{}", err),
             Error::Utf8(ref err) => writeln!(f, "{}", err),
+            Error::Encoding(ref reason) => writeln!(f, "{}", reason),
+            Error::Deserialize {
+                ref message,
+                pointer: Some(ref pointer),
+                position: Some(position),
+            } => writeln!(
+                f,
+                "{} at {} line {} column {}",
+                message, pointer, position.line, position.col
+            ),
+            Error::Deserialize { ref message, .. } => writeln!(f, "{}", message),
+            Error::InvalidPatch(ref reason) => writeln!(f, "invalid patch: {}", reason),
+            Error::InvalidEdit(ref reason) => writeln!(f, "invalid edit: {}", reason),
+            Error::InvalidSchema(ref reason) => writeln!(f, "invalid schema: {}", reason),
+            Error::InvalidInclude(ref reason) => writeln!(f, "invalid include: {}", reason),
+            Error::InvalidPointer(ref err) => writeln!(f, "{}", err),
+            Error::Backend(ref err) => writeln!(f, "{}", err),
+            Error::LimitExceeded(Limit::Depth(max)) => {
+                writeln!(f, "content nested more than {} deep", max)
+            }
+            Error::LimitExceeded(Limit::Nodes(max)) => {
+                writeln!(f, "content has more than {} nodes", max)
+            }
+            Error::LimitExceeded(Limit::InputSize(max)) => {
+                writeln!(f, "content is larger than {} bytes", max)
+            }
+            Error::Unhandled(ref warning) => writeln!(
+                f,
+                "skipped content within {:?} at {}",
+                warning.pointer(),
+                warning.position()
+            ),
+            Error::DuplicateKey {
+                ref pointer,
+                first,
+                second,
+            } => writeln!(
+                f,
+                "duplicate key {} at line {} column {}, first defined at line {} column {}",
+                pointer, second.line, second.col, first.line, first.col
+            ),
         }
     }
 }
 
-impl StdError for Error {}
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Parse { ref error, .. } => Some(error),
+            Error::Io(ref err) => Some(err),
+            Error::Utf8(ref err) => Some(err),
+            Error::InvalidPointer(ref err) => Some(err),
+            Error::Backend(ref err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
 
+/// Positions parse errors as the parser reports them, with columns numbered from 0 in chars
 impl From<ScanError> for Error {
-    fn from(err: ScanError) -> Error {
-        Error::Parse(err)
+    fn from(error: ScanError) -> Error {
+        let position = Position::from(*error.marker());
+        Error::Parse { error, position }
     }
 }
 
@@ -39,6 +130,12 @@ impl From<io::Error> for Error {
     }
 }
 
+impl From<PointerError> for Error {
+    fn from(err: PointerError) -> Error {
+        Error::InvalidPointer(err)
+    }
+}
+
 impl From<Utf8Error> for Error {
     fn from(err: Utf8Error) -> Error {
         Error::Utf8(err)
@@ -0,0 +1,94 @@
+//! A C ABI for looking up positions, declared by `include/lincolns.h`
+//!
+//! Build a shared library with `cargo rustc --release --features ffi --crate-type cdylib`
+use crate::Positions;
+use std::{ffi::CStr, os::raw::c_char, ptr};
+
+/// Loads a lookup table from NUL-terminated UTF-8 text, returning null when the text
+/// can't be loaded. Tables must be released with [`lincolns_free`](fn.lincolns_free.html)
+///
+/// # Safety
+///
+/// `text` must be null or point to a NUL-terminated string
+#[no_mangle]
+pub unsafe extern "C" fn lincolns_index_new(text: *const c_char) -> *mut Positions {
+    if text.is_null() {
+        return ptr::null_mut();
+    }
+    match CStr::from_ptr(text).to_str().map(crate::from_str) {
+        Ok(Ok(positions)) => Box::into_raw(Box::new(positions)),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// Looks up the position of a NUL-terminated JSON Pointer path, writing its line and column
+/// and returning true when found
+///
+/// # Safety
+///
+/// `index` must be null or a table returned by [`lincolns_index_new`](fn.lincolns_index_new.html)
+/// which has not been freed, `pointer` must be null or point to a NUL-terminated string,
+/// and `out_line` and `out_col` must each be null or valid for writes
+#[no_mangle]
+pub unsafe extern "C" fn lincolns_get(
+    index: *const Positions,
+    pointer: *const c_char,
+    out_line: *mut usize,
+    out_col: *mut usize,
+) -> bool {
+    if index.is_null() || pointer.is_null() {
+        return false;
+    }
+    let position = match CStr::from_ptr(pointer).to_str() {
+        Ok(pointer) => (*index).get(pointer),
+        Err(_) => None,
+    };
+    match position {
+        Some(position) => {
+            if !out_line.is_null() {
+                *out_line = position.line;
+            }
+            if !out_col.is_null() {
+                *out_col = position.col;
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+/// Releases a table returned by [`lincolns_index_new`](fn.lincolns_index_new.html)
+///
+/// # Safety
+///
+/// `index` must be null or a table returned by `lincolns_index_new` which has not been freed
+#[no_mangle]
+pub unsafe extern "C" fn lincolns_free(index: *mut Positions) {
+    if !index.is_null() {
+        drop(Box::from_raw(index));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CString;
+
+    #[test]
+    fn looks_up_positions() {
+        let text = CString::new("a:\n  b: 1\n").unwrap();
+        let pointer = CString::new("/a/b").unwrap();
+        let missing = CString::new("/c").unwrap();
+        let (mut line, mut col) = (0, 0);
+        unsafe {
+            let index = lincolns_index_new(text.as_ptr());
+            assert!(!index.is_null());
+            assert!(lincolns_get(index, pointer.as_ptr(), &mut line, &mut col));
+            assert_eq!((line, col), (2, 2));
+            assert!(!lincolns_get(index, missing.as_ptr(), &mut line, &mut col));
+            lincolns_free(index);
+            let invalid = CString::new("a: [").unwrap();
+            assert!(lincolns_index_new(invalid.as_ptr()).is_null());
+        }
+    }
+}
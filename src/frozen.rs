@@ -0,0 +1,50 @@
+use crate::Positions;
+use std::{ops::Deref, sync::Arc};
+
+/// An immutable [`Positions`](struct.Positions.html) table, without the buffers used
+/// while indexing, for sharing between threads
+///
+/// Derefs to `Positions`, offering all of its lookups but none of its changes
+///
+/// ```rust,edition2018
+/// use lincolns::{from_str, Position};
+/// use std::{sync::Arc, thread};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let frozen = from_str("a: 1\nb: 2\n")?.freeze();
+/// let shared = Arc::clone(&frozen);
+/// let found = thread::spawn(move || shared.get("/b").copied()).join().unwrap();
+/// assert_eq!(found, Some(Position { line: 2, col: 0 }));
+/// assert_eq!(frozen.len(), 2);
+/// # Ok(())
+/// # }
+/// ```
+pub struct FrozenPositions(Positions);
+
+impl Deref for FrozenPositions {
+    type Target = Positions;
+
+    fn deref(&self) -> &Positions {
+        &self.0
+    }
+}
+
+impl Positions {
+    /// Freezes this table into a [`FrozenPositions`](struct.FrozenPositions.html),
+    /// releasing the events it was indexed from
+    pub fn freeze(mut self) -> Arc<FrozenPositions> {
+        self.shrink_to_fit();
+        Arc::new(FrozenPositions(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frozen_positions_are_shareable() {
+        fn shareable<T: Send + Sync>() {}
+        shareable::<FrozenPositions>();
+    }
+}
@@ -0,0 +1,377 @@
+use crate::{backend::Event, Backend, Error, Result, ScalarStyle};
+use hcl_edit::{
+    expr::{Expression, ObjectKey},
+    parser::parse_body,
+    structure::{Block, BlockLabel, Body, Structure},
+    Span,
+};
+use std::ops::Range;
+
+/// A [`Backend`](trait.Backend.html) for [HCL](https://github.com/hashicorp/hcl) content,
+/// such as Terraform configuration, built on [hcl-edit](https://docs.rs/hcl-edit)
+///
+/// Blocks are indexed under their identifier followed by each of their labels, so blocks
+/// sharing an identifier share a mapping, and blocks sharing every label are collected into
+/// a sequence. Expressions other than literals, arrays, and objects are indexed as the scalar
+/// of their source text. Requires the `hcl` feature
+///
+/// ```rust,edition2018
+/// use lincolns::{from_str_using, Hcl, Options, Position};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let terraform = r#"resource "aws_instance" "web" {
+///   ami           = "ami-123"
+///   instance_type = var.size
+/// }
+/// "#;
+/// let positions = from_str_using(&Hcl, terraform, Options::default())?;
+/// assert_eq!(
+///     positions.get_value("/resource/aws_instance/web/ami"),
+///     Some(("ami-123", &Position { line: 2, col: 2 }))
+/// );
+/// assert_eq!(
+///     positions.get_value("/resource/aws_instance/web/instance_type").map(|(value, _)| value),
+///     Some("var.size")
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Hcl;
+
+impl Backend for Hcl {
+    fn parse(
+        &self,
+        text: &str,
+        sink: &mut dyn FnMut(Event, usize),
+    ) -> Result<()> {
+        let body = parse_body(text).map_err(|err| Error::Backend(Box::new(err)))?;
+        let mut emitter = Emitter { text, sink };
+        let start = body.span().map_or(0, |span| span.start);
+        emitter.sink(Event::DocumentStart, start);
+        emitter.body(&body, start, text.len());
+        emitter.sink(Event::DocumentEnd, text.len());
+        Ok(())
+    }
+}
+
+/// The entries of a body, keyed in the order keys first appear
+#[derive(Default)]
+struct Entries<'a> {
+    entries: Vec<(&'a str, Entry<'a>)>,
+}
+
+enum Entry<'a> {
+    Attribute {
+        key: Range<usize>,
+        value: &'a Expression,
+    },
+    /// Blocks sharing a label, keyed by their next label
+    Labeled {
+        key: Range<usize>,
+        style: ScalarStyle,
+        entries: Entries<'a>,
+    },
+    /// Blocks with no further labels
+    Blocks {
+        key: Range<usize>,
+        style: ScalarStyle,
+        blocks: Vec<&'a Block>,
+    },
+}
+
+impl<'a> Entry<'a> {
+    fn key(&self) -> (Range<usize>, ScalarStyle) {
+        match self {
+            Entry::Attribute { key, .. } => (key.clone(), ScalarStyle::Plain),
+            Entry::Labeled { key, style, .. } | Entry::Blocks { key, style, .. } => {
+                (key.clone(), *style)
+            }
+        }
+    }
+}
+
+impl<'a> Entries<'a> {
+    /// Files the attributes and blocks of a body
+    fn of(body: &'a Body) -> Self {
+        let mut entries = Entries::default();
+        for structure in body.iter() {
+            match structure {
+                Structure::Attribute(attribute) => entries.entries.push((
+                    attribute.key.as_str(),
+                    Entry::Attribute {
+                        key: attribute.key.span().unwrap_or_default(),
+                        value: &attribute.value,
+                    },
+                )),
+                Structure::Block(block) => {
+                    let ident = (
+                        block.ident.as_str(),
+                        block.ident.span().unwrap_or_default(),
+                        ScalarStyle::Plain,
+                    );
+                    let labels = block.labels.iter().map(|label| {
+                        let style = match label {
+                            BlockLabel::Ident(_) => ScalarStyle::Plain,
+                            BlockLabel::String(_) => ScalarStyle::DoubleQuoted,
+                        };
+                        (label.as_str(), label.span().unwrap_or_default(), style)
+                    });
+                    let path = std::iter::once(ident).chain(labels).collect::<Vec<_>>();
+                    entries.insert(&path, block);
+                }
+            }
+        }
+        entries
+    }
+
+    /// Byte offset just past the last block filed within these entries
+    fn end(&self) -> Option<usize> {
+        self.entries
+            .iter()
+            .filter_map(|(_, entry)| match entry {
+                Entry::Attribute { .. } => None,
+                Entry::Labeled { entries, .. } => entries.end(),
+                Entry::Blocks { blocks, .. } => blocks
+                    .iter()
+                    .filter_map(|block| block.span())
+                    .map(|span| span.end)
+                    .max(),
+            })
+            .max()
+    }
+
+    /// Files a block under the path of its identifier and labels
+    fn insert(
+        &mut self,
+        path: &[(&'a str, Range<usize>, ScalarStyle)],
+        block: &'a Block,
+    ) {
+        let ((name, key, style), path) = match path.split_first() {
+            Some(((name, key, style), path)) => ((*name, key.clone(), *style), path),
+            None => return,
+        };
+        let last = path.is_empty();
+        let existing = self
+            .entries
+            .iter_mut()
+            .find(|(existing, _)| *existing == name);
+        match (existing, last) {
+            (Some((_, Entry::Blocks { blocks, .. })), true) => blocks.push(block),
+            (Some((_, Entry::Labeled { entries, .. })), false) => entries.insert(path, block),
+            // names of both attributes and blocks are repeated, as duplicate keys
+            (_, true) => self.entries.push((
+                name,
+                Entry::Blocks {
+                    key,
+                    style,
+                    blocks: vec![block],
+                },
+            )),
+            (_, false) => {
+                let mut entries = Entries::default();
+                entries.insert(path, block);
+                self.entries.push((
+                    name,
+                    Entry::Labeled {
+                        key,
+                        style,
+                        entries,
+                    },
+                ));
+            }
+        }
+    }
+}
+
+struct Emitter<'t, 's> {
+    text: &'t str,
+    sink: &'s mut dyn FnMut(Event, usize),
+}
+
+impl<'t, 's> Emitter<'t, 's> {
+    fn sink(
+        &mut self,
+        event: Event,
+        offset: usize,
+    ) {
+        (self.sink)(event, offset)
+    }
+
+    fn scalar(
+        &mut self,
+        value: &str,
+        style: ScalarStyle,
+        offset: usize,
+    ) {
+        self.sink(
+            Event::Scalar {
+                value: value.into(),
+                style,
+                tag: None,
+            },
+            offset,
+        );
+    }
+
+    /// Emits the source text of a span as a plain scalar
+    fn raw(
+        &mut self,
+        span: Range<usize>,
+    ) {
+        let text = self.text;
+        self.scalar(
+            text[span.clone()].trim_end(),
+            ScalarStyle::Plain,
+            span.start,
+        );
+    }
+
+    fn body(
+        &mut self,
+        body: &Body,
+        start: usize,
+        end: usize,
+    ) {
+        self.sink(Event::MappingStart { anchor: 0 }, start);
+        self.entries(Entries::of(body));
+        self.sink(Event::MappingEnd, end);
+    }
+
+    fn entries(
+        &mut self,
+        entries: Entries,
+    ) {
+        for (name, entry) in entries.entries {
+            let (key, style) = entry.key();
+            self.scalar(name, style, key.start);
+            match entry {
+                Entry::Attribute { value, .. } => self.expression(value),
+                Entry::Labeled { entries, .. } => {
+                    self.sink(Event::MappingStart { anchor: 0 }, key.start);
+                    let end = entries.end().unwrap_or(key.end);
+                    self.entries(entries);
+                    self.sink(Event::MappingEnd, end);
+                }
+                Entry::Blocks { blocks, .. } => match blocks.as_slice() {
+                    [block] => self.block(block),
+                    blocks => {
+                        let start = blocks[0].span().map_or(key.start, |span| span.start);
+                        self.sink(Event::SequenceStart { anchor: 0 }, start);
+                        for block in blocks {
+                            self.block(block);
+                        }
+                        let end = blocks[blocks.len() - 1]
+                            .span()
+                            .map_or(key.end, |span| span.end);
+                        self.sink(Event::SequenceEnd, end);
+                    }
+                },
+            }
+        }
+    }
+
+    fn block(
+        &mut self,
+        block: &Block,
+    ) {
+        let span = block.span().unwrap_or_default();
+        self.body(&block.body, span.start, span.end);
+    }
+
+    fn expression(
+        &mut self,
+        expression: &Expression,
+    ) {
+        let span = expression.span().unwrap_or_default();
+        match expression {
+            Expression::String(value) => {
+                self.scalar(value.as_str(), ScalarStyle::DoubleQuoted, span.start)
+            }
+            Expression::Array(array) => {
+                self.sink(Event::SequenceStart { anchor: 0 }, span.start);
+                for item in array.iter() {
+                    self.expression(item);
+                }
+                self.sink(Event::SequenceEnd, span.end - 1);
+            }
+            Expression::Object(object) => {
+                self.sink(Event::MappingStart { anchor: 0 }, span.start);
+                for (key, value) in object.iter() {
+                    let key_span = key.span().unwrap_or_default();
+                    match key {
+                        ObjectKey::Ident(ident) => {
+                            self.scalar(ident.as_str(), ScalarStyle::Plain, key_span.start)
+                        }
+                        ObjectKey::Expression(Expression::String(value)) => {
+                            self.scalar(value.as_str(), ScalarStyle::DoubleQuoted, key_span.start)
+                        }
+                        ObjectKey::Expression(_) => self.raw(key_span),
+                    }
+                    self.expression(value.expr());
+                }
+                self.sink(Event::MappingEnd, span.end - 1);
+            }
+            _ => self.raw(span),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_str_using, NodeKind, Options, Position};
+
+    #[test]
+    fn indexes_blocks_and_attributes() -> Result<()> {
+        let hcl = r#"terraform {
+  required_version = ">= 1.0"
+}
+
+resource "aws_instance" "web" {
+  count = 2
+  tags  = { Name = "web", "team" = "ops" }
+  ingress {
+    port = 80
+  }
+  ingress {
+    port = 443
+  }
+}
+
+resource aws_instance "db" {
+  zones = ["a", "b"]
+}
+"#;
+        let positions = from_str_using(&Hcl, hcl, Options::default())?;
+        assert_eq!(
+            positions.get("/terraform/required_version"),
+            Some(&Position { line: 2, col: 2 })
+        );
+        assert_eq!(
+            positions.get("/resource/aws_instance/web"),
+            Some(&Position { line: 5, col: 24 })
+        );
+        assert_eq!(
+            positions.kind("/resource/aws_instance/web/count"),
+            Some(NodeKind::Integer)
+        );
+        assert_eq!(
+            positions.get_value("/resource/aws_instance/web/tags/team"),
+            Some(("ops", &Position { line: 7, col: 26 }))
+        );
+        assert_eq!(
+            positions.get("/resource/aws_instance/web/ingress/1/port"),
+            Some(&Position { line: 12, col: 4 })
+        );
+        assert_eq!(
+            positions.get("/resource/aws_instance/db/zones/1"),
+            Some(&Position { line: 17, col: 16 })
+        );
+        assert!(matches!(
+            from_str_using(&Hcl, "a = ", Options::default()),
+            Err(Error::Backend(_))
+        ));
+        Ok(())
+    }
+}
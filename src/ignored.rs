@@ -0,0 +1,117 @@
+use crate::{path, Position, Positions, Unknown};
+use serde_ignored::Path;
+
+/// Converts a [serde_ignored](https://docs.rs/serde_ignored) path, such as `foo.bar.2.baz`,
+/// into a JSON Pointer, such as `/foo/bar/2/baz`
+pub fn ignored_pointer(path: &Path) -> String {
+    match path {
+        Path::Root => String::new(),
+        Path::Seq { parent, index } => format!("{}/{}", ignored_pointer(parent), index),
+        Path::Map { parent, key } => {
+            format!("{}/{}", ignored_pointer(parent), path::escape(key))
+        }
+        Path::Some { parent }
+        | Path::NewtypeStruct { parent }
+        | Path::NewtypeVariant { parent } => ignored_pointer(parent),
+    }
+}
+
+impl Positions {
+    /// Returns the position of the value at a [serde_ignored](https://docs.rs/serde_ignored) path
+    pub fn get_ignored(
+        &self,
+        path: &Path,
+    ) -> Option<&Position> {
+        self.get(ignored_pointer(path))
+    }
+
+    /// Deserializes a value from `deserializer`, as [`serde_ignored::deserialize`](https://docs.rs/serde_ignored/latest/serde_ignored/fn.deserialize.html)
+    /// does, along with the fields it ignored located within this table, in the order they
+    /// were ignored
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{from_str, Position};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Service {
+    ///     image: String,
+    /// }
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>>  {
+    /// let json = "{\n  \"image\": \"nginx\",\n  \"enviroment\": {}\n}";
+    /// let positions = from_str(json)?;
+    /// let (service, ignored) = positions
+    ///     .deserialize_ignored::<_, Service>(&mut serde_json::Deserializer::from_str(json))?;
+    /// assert_eq!(service.image, "nginx");
+    /// assert_eq!(ignored[0].pointer, "/enviroment");
+    /// assert_eq!(ignored[0].position, Position { line: 3, col: 2 });
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn deserialize_ignored<'de, D, T>(
+        &self,
+        deserializer: D,
+    ) -> std::result::Result<(T, Vec<Unknown>), D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: serde::Deserialize<'de>,
+    {
+        let mut ignored = Vec::new();
+        let value = serde_ignored::deserialize(deserializer, |path| {
+            let pointer = ignored_pointer(&path);
+            if let Some(position) = self.get(&pointer) {
+                ignored.push(Unknown {
+                    pointer,
+                    position: *position,
+                    suggestion: None,
+                });
+            }
+        })?;
+        Ok((value, ignored))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_str, Result};
+    use serde::Deserialize;
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Config {
+        services: BTreeMap<String, Option<Service>>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Service {
+        replicas: u8,
+    }
+
+    #[test]
+    fn locates_ignored_fields() -> Result<()> {
+        let json =
+            "{\"services\": {\n  \"web\": {\"replicas\": 1, \"replica\": 2}\n},\n\"version\": 3}";
+        let positions = from_str(json)?;
+        let (_, ignored) = positions
+            .deserialize_ignored::<_, Config>(&mut serde_json::Deserializer::from_str(json))
+            .expect("invalid config");
+        assert_eq!(
+            ignored
+                .into_iter()
+                .map(|unknown| (unknown.pointer, unknown.position))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "/services/web/replica".to_owned(),
+                    Position { line: 2, col: 25 }
+                ),
+                ("/version".to_owned(), Position { line: 4, col: 0 }),
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,366 @@
+use crate::{Error, Pointer, Position, Positions, Result};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, File},
+    path::{Path, PathBuf},
+};
+
+/// Identifies one of the files an [`Includes`](struct.Includes.html) loaded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SourceId(usize);
+
+impl SourceId {
+    /// The order the file was loaded in, the root file being 0
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// A node of one file which includes a node of another
+#[derive(Debug, Clone, PartialEq)]
+pub struct Include<'a> {
+    /// The including file
+    pub source: SourceId,
+    /// The JSON Pointer path of the including node
+    pub pointer: &'a str,
+    /// The included file
+    pub target: SourceId,
+    /// The JSON Pointer path of the included node, the root for whole files
+    pub target_pointer: &'a str,
+}
+
+/// Loads a file along with every file it includes, through `!include path.yml` tags
+/// and `$ref` references, such as `{"$ref": "definitions.json#/port"}`
+///
+/// Included paths are relative to the file including them. References without a file,
+/// `#/local`, or to a URL are left as they are
+#[derive(Debug, Clone)]
+pub struct Resolver {
+    tags: Vec<String>,
+    refs: bool,
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Resolver {
+            tags: vec!["!include".into()],
+            refs: true,
+        }
+    }
+}
+
+impl Resolver {
+    /// Creates a resolver following `!include` tags and `$ref` references
+    pub fn new() -> Self {
+        Resolver::default()
+    }
+
+    /// Follows scalars with another tag, such as `!import`, as includes
+    pub fn tag<T>(
+        mut self,
+        tag: T,
+    ) -> Self
+    where
+        T: Into<String>,
+    {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Sets whether `$ref` references are followed, true by default
+    pub fn refs(
+        mut self,
+        refs: bool,
+    ) -> Self {
+        self.refs = refs;
+        self
+    }
+
+    /// Loads a file and, recursively, every file it includes
+    ///
+    /// Files are loaded once however many times they're included, so files including
+    /// each other load without looping. Files which fail to load, and references to
+    /// pointers the referenced file doesn't have, fail with an
+    /// [`Error::InvalidInclude`](enum.Error.html#variant.InvalidInclude)
+    /// locating the include
+    pub fn resolve<P>(
+        &self,
+        path: P,
+    ) -> Result<Includes>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let mut includes = Includes {
+            sources: vec![(path.to_owned(), read(path)?)],
+            links: BTreeMap::new(),
+        };
+        let mut loaded = HashMap::new();
+        loaded.insert(fs::canonicalize(path)?, SourceId(0));
+        let mut pending = vec![SourceId(0)];
+        while let Some(source) = pending.pop() {
+            let (including, positions) = &includes.sources[source.0];
+            let dir = including
+                .parent()
+                .unwrap_or_else(|| Path::new(""))
+                .to_owned();
+            let sites = self.sites(positions);
+            let including = including.clone();
+            for (pointer, position, file, fragment) in sites {
+                let failed = |reason: String| {
+                    Error::InvalidInclude(format!(
+                        "{} at {} line {} column {}: {}",
+                        including.display(),
+                        pointer,
+                        position.line,
+                        position.col,
+                        reason
+                    ))
+                };
+                let path = dir.join(&file);
+                let canonical = fs::canonicalize(&path)
+                    .map_err(|err| failed(format!("{}: {}", path.display(), err)))?;
+                let target = match loaded.get(&canonical) {
+                    Some(target) => *target,
+                    None => {
+                        let positions = read(&path).map_err(|err| {
+                            failed(format!(
+                                "{}: {}",
+                                path.display(),
+                                err.to_string().trim_end()
+                            ))
+                        })?;
+                        let target = SourceId(includes.sources.len());
+                        includes.sources.push((path.clone(), positions));
+                        loaded.insert(canonical, target);
+                        pending.push(target);
+                        target
+                    }
+                };
+                // fragments are percent-encoded, `#/a%20b`, while the index holds `/a b`
+                let fragment = Pointer::normalize(&fragment)
+                    .map(String::from)
+                    .map_err(|err| failed(err.to_string()))?;
+                if !includes.sources[target.0].1.contains(&fragment) {
+                    return Err(failed(format!("{} has no {}", path.display(), fragment)));
+                }
+                includes.links.insert((source, pointer), (target, fragment));
+            }
+        }
+        Ok(includes)
+    }
+
+    /// Lists the pointers, positions, files, and fragments a file includes
+    fn sites(
+        &self,
+        positions: &Positions,
+    ) -> Vec<(String, Position, String, String)> {
+        positions
+            .iter()
+            .filter_map(|(ptr, position)| {
+                if let Some(tag) = positions.tag(ptr) {
+                    if self.tags.iter().any(|include| include == tag) {
+                        let (file, _) = positions.get_value(ptr)?;
+                        return Some((ptr.clone(), *position, file.to_owned(), String::new()));
+                    }
+                }
+                let parent = ptr.strip_suffix("/$ref").filter(|_| self.refs)?;
+                let (reference, _) = positions.get_value(ptr)?;
+                let (file, fragment) = match reference.find('#') {
+                    Some(idx) => (&reference[..idx], &reference[idx..]),
+                    None => (reference, ""),
+                };
+                if file.is_empty() || file.contains("://") {
+                    return None;
+                }
+                let position = *positions.get(parent)?;
+                Some((
+                    parent.to_owned(),
+                    position,
+                    file.to_owned(),
+                    fragment.to_owned(),
+                ))
+            })
+            .collect()
+    }
+}
+
+fn read(path: &Path) -> Result<Positions> {
+    crate::from_reader(File::open(path)?)
+}
+
+/// Lookup tables of a file and the files it includes, resolving JSON Pointer paths
+/// through includes as though they'd been inlined
+///
+/// ```rust,no_run
+/// use lincolns::{Position, Resolver};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// // app.yml has `database: !include db.yml` and db.yml has `host: localhost`
+/// let includes = Resolver::new().resolve("app.yml")?;
+/// let (source, position) = includes.get("/database/host").expect("no host");
+/// assert_eq!(includes.path(source), std::path::Path::new("db.yml"));
+/// assert_eq!(position, &Position { line: 1, col: 0 });
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Includes {
+    sources: Vec<(PathBuf, Positions)>,
+    /// Included nodes, keyed by the source and pointer of the node including them
+    links: BTreeMap<(SourceId, String), (SourceId, String)>,
+}
+
+impl Includes {
+    /// The file includes were resolved from
+    pub fn root(&self) -> SourceId {
+        SourceId(0)
+    }
+
+    /// The path of a file, relative to the current directory if the root's was
+    pub fn path(
+        &self,
+        source: SourceId,
+    ) -> &Path {
+        &self.sources[source.0].0
+    }
+
+    /// The lookup table of a file
+    pub fn positions(
+        &self,
+        source: SourceId,
+    ) -> &Positions {
+        &self.sources[source.0].1
+    }
+
+    /// Returns the files loaded, the root first
+    pub fn sources(&self) -> impl Iterator<Item = (SourceId, &Path)> {
+        self.sources
+            .iter()
+            .enumerate()
+            .map(|(index, (path, _))| (SourceId(index), path.as_path()))
+    }
+
+    /// Returns every include, ordered by the including file and pointer
+    pub fn includes(&self) -> impl Iterator<Item = Include<'_>> {
+        self.links
+            .iter()
+            .map(|((source, pointer), (target, target_pointer))| Include {
+                source: *source,
+                pointer,
+                target: *target,
+                target_pointer,
+            })
+    }
+
+    /// Resolves a JSON Pointer path of the root file, with includes inlined, into the
+    /// file and the JSON Pointer path within it of the node it refers to
+    pub fn locate<P>(
+        &self,
+        ptr: P,
+    ) -> Option<(SourceId, String)>
+    where
+        P: AsRef<str>,
+    {
+        self.follow(ptr.as_ref())
+    }
+
+    /// Gets a node's position, and the file it's in, given its JSON Pointer path
+    /// with includes inlined
+    pub fn get<P>(
+        &self,
+        ptr: P,
+    ) -> Option<(SourceId, &Position)>
+    where
+        P: AsRef<str>,
+    {
+        let (source, ptr) = self.locate(ptr)?;
+        Some((source, self.positions(source).get(ptr)?))
+    }
+
+    fn follow(
+        &self,
+        ptr: &str,
+    ) -> Option<(SourceId, String)> {
+        let mut at = self.hop((self.root(), String::new()));
+        for token in ptr.split('/').skip(1) {
+            at.1.push('/');
+            at.1.push_str(token);
+            at = self.hop(at);
+        }
+        Some(at).filter(|(source, ptr)| self.positions(*source).contains(ptr))
+    }
+
+    /// Follows includes from a node until reaching one which isn't an include,
+    /// giving up on includes which loop back on themselves
+    fn hop(
+        &self,
+        mut at: (SourceId, String),
+    ) -> (SourceId, String) {
+        for _ in 0..=self.links.len() {
+            match self.links.get(&at) {
+                Some(target) => at = target.clone(),
+                None => break,
+            }
+        }
+        at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Position, Resolver, Result};
+    use std::path::Path;
+
+    #[test]
+    fn resolves_includes_across_files() -> Result<()> {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/data/include");
+        let includes = Resolver::new().resolve(dir.join("app.yml"))?;
+        let file = |ptr: &str| {
+            includes.get(ptr).map(|(source, position)| {
+                let name = includes.path(source).file_name().expect("no name");
+                (name.to_string_lossy().into_owned(), *position)
+            })
+        };
+        assert_eq!(
+            file("/name"),
+            Some(("app.yml".into(), Position { line: 1, col: 0 }))
+        );
+        assert_eq!(
+            file("/database/host"),
+            Some(("db.yml".into(), Position { line: 1, col: 0 }))
+        );
+        assert_eq!(
+            file("/database/replica/name"),
+            Some(("app.yml".into(), Position { line: 1, col: 0 }))
+        );
+        assert_eq!(
+            file("/ports/http"),
+            Some(("ports.json".into(), Position { line: 4, col: 6 }))
+        );
+        assert_eq!(
+            file("/admin/https"),
+            Some(("ports.json".into(), Position { line: 7, col: 6 }))
+        );
+        assert_eq!(
+            file("/legacy/ftp"),
+            Some(("ports.json".into(), Position { line: 10, col: 6 }))
+        );
+        assert_eq!(file("/database/missing"), None);
+        assert_eq!(
+            includes
+                .locate("/database/replica")
+                .map(|(source, ptr)| (source.index(), ptr)),
+            Some((0, "".into()))
+        );
+        assert_eq!(includes.sources().count(), 3);
+        assert_eq!(includes.includes().count(), 5);
+        let err = Resolver::new()
+            .resolve(dir.join("broken.yml"))
+            .expect_err("resolved a missing pointer");
+        assert!(err.to_string().contains("has no /missing"), "{}", err);
+        let unfollowed = Resolver::new().refs(false).resolve(dir.join("app.yml"))?;
+        assert_eq!(unfollowed.includes().count(), 2);
+        Ok(())
+    }
+}
@@ -0,0 +1,273 @@
+use crate::{backend::Event, Backend, Error, Result, ScalarStyle};
+
+/// A [`Backend`](trait.Backend.html) for INI content, indexing `key = value` entries
+/// under `/section/key` pointers, or `/key` before the first section
+///
+/// Entries may be separated by `=` or `:`, and lines starting with `;` or `#` are comments.
+/// Values wrapped in double quotes are indexed without them
+///
+/// ```rust,edition2018
+/// use lincolns::{from_str_using, Ini, Options, Position};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let ini = "; settings\nname = app\n\n[server]\nport = 8080\n";
+/// let positions = from_str_using(&Ini, ini, Options::default())?;
+/// assert_eq!(positions.get("/name"), Some(&Position { line: 2, col: 0 }));
+/// assert_eq!(
+///     positions.get_value("/server/port"),
+///     Some(("8080", &Position { line: 5, col: 0 }))
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Ini;
+
+/// A [`Backend`](trait.Backend.html) for Java `.properties` content, indexing each entry
+/// under a `/key` pointer
+///
+/// Keys are separated from values by `=`, `:`, or whitespace, lines starting with `#` or `!`
+/// are comments, lines ending with `\` continue onto the next, and escapes such as `\t` and `\u00e9`
+/// are decoded
+///
+/// ```rust,edition2018
+/// use lincolns::{from_str_using, Options, Position, Properties};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let properties = "# settings\nserver.port=8080\ngreeting = hello, \\\n    world\n";
+/// let positions = from_str_using(&Properties, properties, Options::default())?;
+/// assert_eq!(positions.get("/server.port"), Some(&Position { line: 2, col: 0 }));
+/// assert_eq!(positions.get_value("/greeting").map(|(value, _)| value), Some("hello, world"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Properties;
+
+fn scalar(
+    value: &str,
+    style: ScalarStyle,
+) -> Event {
+    Event::Scalar {
+        value: value.into(),
+        style,
+        tag: None,
+    }
+}
+
+/// Each line of text along with the byte offset it starts at, excluding its line ending
+fn lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split_inclusive('\n').scan(0, |offset, line| {
+        let start = *offset;
+        *offset += line.len();
+        Some((start, line.trim_end_matches(['\n', '\r'])))
+    })
+}
+
+/// Byte offset of a trimmed slice of `line`, which starts at `offset`
+fn offset_of(
+    line: &str,
+    offset: usize,
+    slice: &str,
+) -> usize {
+    offset + (slice.as_ptr() as usize - line.as_ptr() as usize)
+}
+
+impl Backend for Ini {
+    fn parse(
+        &self,
+        text: &str,
+        sink: &mut dyn FnMut(Event, usize),
+    ) -> Result<()> {
+        sink(Event::DocumentStart, 0);
+        sink(Event::MappingStart { anchor: 0 }, 0);
+        let mut section_end = None;
+        for (number, (offset, line)) in lines(text).enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with([';', '#']) {
+                continue;
+            }
+            let start = offset_of(line, offset, trimmed);
+            if let Some(name) = trimmed
+                .strip_prefix('[')
+                .and_then(|rest| rest.strip_suffix(']'))
+            {
+                if let Some(end) = section_end.take() {
+                    sink(Event::MappingEnd, end);
+                }
+                let name = name.trim();
+                sink(
+                    scalar(name, ScalarStyle::Plain),
+                    offset_of(line, offset, name),
+                );
+                sink(Event::MappingStart { anchor: 0 }, start);
+                section_end = Some(start + trimmed.len());
+                continue;
+            }
+            let (key, value) = trimmed.split_once(['=', ':']).ok_or_else(|| {
+                Error::Backend(format!("invalid entry on line {}", number + 1).into())
+            })?;
+            let key = key.trim_end();
+            let value = value.trim_start();
+            sink(scalar(key, ScalarStyle::Plain), start);
+            let value_start = offset_of(line, offset, value);
+            match value
+                .strip_prefix('"')
+                .and_then(|quoted| quoted.strip_suffix('"'))
+            {
+                Some(quoted) => sink(scalar(quoted, ScalarStyle::DoubleQuoted), value_start),
+                None => sink(scalar(value, ScalarStyle::Plain), value_start),
+            }
+            if section_end.is_some() {
+                section_end = Some(start + trimmed.len());
+            }
+        }
+        if let Some(end) = section_end {
+            sink(Event::MappingEnd, end);
+        }
+        sink(Event::MappingEnd, text.len());
+        sink(Event::DocumentEnd, text.len());
+        Ok(())
+    }
+}
+
+impl Backend for Properties {
+    fn parse(
+        &self,
+        text: &str,
+        sink: &mut dyn FnMut(Event, usize),
+    ) -> Result<()> {
+        sink(Event::DocumentStart, 0);
+        sink(Event::MappingStart { anchor: 0 }, 0);
+        let mut lines = lines(text);
+        while let Some((offset, line)) = lines.next() {
+            let trimmed = line.trim_start();
+            if trimmed.is_empty() || trimmed.starts_with(['#', '!']) {
+                continue;
+            }
+            let start = offset_of(line, offset, trimmed);
+            // the key ends at the first unescaped separator
+            let mut escaped = false;
+            let key_len = trimmed
+                .char_indices()
+                .find(|(_, c)| {
+                    let separator = !escaped && (*c == '=' || *c == ':' || c.is_whitespace());
+                    escaped = !escaped && *c == '\\';
+                    separator
+                })
+                .map_or(trimmed.len(), |(idx, _)| idx);
+            let rest = trimmed[key_len..].trim_start();
+            let rest = rest.strip_prefix(['=', ':']).map_or(rest, str::trim_start);
+            let mut value = String::new();
+            let mut raw = rest;
+            while continues(raw) {
+                value.push_str(&raw[..raw.len() - 1]);
+                match lines.next() {
+                    Some((_, next)) => raw = next.trim_start(),
+                    None => {
+                        raw = "";
+                        break;
+                    }
+                }
+            }
+            value.push_str(raw);
+            sink(
+                scalar(&unescape(&trimmed[..key_len]), ScalarStyle::Plain),
+                start,
+            );
+            let value_start = offset_of(line, offset, rest);
+            sink(scalar(&unescape(&value), ScalarStyle::Plain), value_start);
+        }
+        sink(Event::MappingEnd, text.len());
+        sink(Event::DocumentEnd, text.len());
+        Ok(())
+    }
+}
+
+/// Whether a line ends with an odd number of backslashes, continuing it onto the next
+fn continues(line: &str) -> bool {
+    line.chars().rev().take_while(|c| *c == '\\').count() % 2 == 1
+}
+
+/// Decodes the escapes of a properties key or value
+fn unescape(raw: &str) -> String {
+    let mut value = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => value.push('\t'),
+            Some('n') => value.push('\n'),
+            Some('r') => value.push('\r'),
+            Some('f') => value.push('\u{c}'),
+            Some('u') => {
+                let hex = chars.by_ref().take(4).collect::<String>();
+                match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    Some(decoded) => value.push(decoded),
+                    None => value.push_str(&hex),
+                }
+            }
+            Some(other) => value.push(other),
+            None => (),
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_str_using, Options, Position};
+
+    #[test]
+    fn indexes_ini_sections() -> Result<()> {
+        let ini = "top: 1\r\n[a]\r\n  x = \"quoted\"\r\n# comment\r\n[ b ]\r\ny=\r\n";
+        let positions = from_str_using(&Ini, ini, Options::default())?;
+        assert_eq!(
+            positions.keys().collect::<Vec<_>>(),
+            vec!["/a", "/a/x", "/b", "/b/y", "/top"]
+        );
+        assert_eq!(positions.get("/a"), Some(&Position { line: 2, col: 1 }));
+        assert_eq!(
+            positions.get_value("/a/x"),
+            Some(("quoted", &Position { line: 3, col: 2 }))
+        );
+        assert_eq!(positions.get("/b"), Some(&Position { line: 5, col: 2 }));
+        assert_eq!(
+            positions.get_value("/b/y").map(|(value, _)| value),
+            Some("")
+        );
+        assert!(matches!(
+            from_str_using(&Ini, "[a]\nnot an entry\n", Options::default()),
+            Err(Error::Backend(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn indexes_properties() -> Result<()> {
+        let properties =
+            "! comment\na\\=b = c\nkey value\nempty\nlong = one \\\n  two\nu=caf\\u00e9\n";
+        let positions = from_str_using(&Properties, properties, Options::default())?;
+        let values = positions
+            .keys()
+            .map(|key| (key, positions.get_value(key).map(|(value, _)| value)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            vec![
+                ("/a=b", Some("c")),
+                ("/empty", Some("")),
+                ("/key", Some("value")),
+                ("/long", Some("one two")),
+                ("/u", Some("café"))
+            ]
+        );
+        assert_eq!(positions.get("/long"), Some(&Position { line: 5, col: 0 }));
+        assert_eq!(positions.get("/u"), Some(&Position { line: 7, col: 0 }));
+        Ok(())
+    }
+}
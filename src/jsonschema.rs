@@ -0,0 +1,134 @@
+use crate::{path, Position, Positions, Span};
+use jsonschema::{paths::LocationSegment, ValidationError};
+use std::{error::Error as StdError, fmt};
+
+/// A [jsonschema](https://docs.rs/jsonschema) validation error located within its source
+#[derive(Debug, Clone)]
+pub struct SchemaError {
+    /// The JSON Pointer path of the invalid value
+    pub pointer: String,
+    /// The JSON Pointer path of the schema keyword the value failed
+    pub schema_path: String,
+    /// A description of the problem
+    pub message: String,
+    /// The position of the invalid value, when it could be found
+    pub position: Option<Position>,
+    /// The span of the invalid value, when it could be found
+    pub span: Option<Span>,
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self.position {
+            Some(position) => write!(
+                f,
+                "{} at {} line {} column {}",
+                self.message, self.pointer, position.line, position.col
+            ),
+            None => write!(f, "{} at {}", self.message, self.pointer),
+        }
+    }
+}
+
+impl StdError for SchemaError {}
+
+impl Positions {
+    /// Locates a [jsonschema](https://docs.rs/jsonschema) validation error within this table's source
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{from_str, Position};
+    /// use serde_json::json;
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let yaml = "name: web\nreplicas: many\n";
+    /// let schema = json!({"properties": {"replicas": {"type": "integer"}}});
+    /// let instance = json!({"name": "web", "replicas": "many"});
+    /// let validator = jsonschema::validator_for(&schema).unwrap();
+    /// let positions = from_str(yaml)?;
+    /// let errors = positions.schema_errors(validator.iter_errors(&instance));
+    /// assert_eq!(errors[0].pointer, "/replicas");
+    /// assert_eq!(errors[0].position, Some(Position { line: 2, col: 0 }));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn schema_error(
+        &self,
+        err: &ValidationError<'_>,
+    ) -> SchemaError {
+        let pointer = err
+            .instance_path()
+            .into_iter()
+            .map(|segment| match segment {
+                LocationSegment::Property(property) => format!("/{}", path::escape(&property)),
+                LocationSegment::Index(index) => format!("/{}", index),
+            })
+            .collect::<String>();
+        let location = self.locate_pointer(&pointer);
+        SchemaError {
+            schema_path: err.schema_path().to_string(),
+            message: err.to_string(),
+            position: location.as_ref().map(|(position, _)| *position),
+            span: location.map(|(_, span)| span),
+            pointer,
+        }
+    }
+
+    /// Locates each of a sequence of [jsonschema](https://docs.rs/jsonschema) validation errors
+    /// within this table's source
+    pub fn schema_errors<'a, I>(
+        &self,
+        errors: I,
+    ) -> Vec<SchemaError>
+    where
+        I: IntoIterator<Item = ValidationError<'a>>,
+    {
+        errors
+            .into_iter()
+            .map(|err| self.schema_error(&err))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_str, Position, Result};
+    use serde_json::json;
+
+    #[test]
+    fn locates_schema_errors() -> Result<()> {
+        let yaml = "services:\n  - name: web\n    ports: [80, http]\n";
+        let schema = json!({
+            "required": ["version"],
+            "properties": {
+                "services": {
+                    "items": {
+                        "properties": {"ports": {"items": {"type": "integer"}}}
+                    }
+                }
+            }
+        });
+        let instance = json!({"services": [{"name": "web", "ports": [80, "http"]}]});
+        let validator = jsonschema::validator_for(&schema).expect("valid schema");
+        let positions = from_str(yaml)?;
+        let errors = positions.schema_errors(validator.iter_errors(&instance));
+        let located = errors
+            .iter()
+            .map(|err| (err.pointer.as_str(), err.position))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            located,
+            vec![
+                ("", Some(Position { line: 1, col: 0 })),
+                ("/services/0/ports/1", Some(Position { line: 3, col: 16 })),
+            ]
+        );
+        assert_eq!(
+            errors[1].to_string(),
+            "\"http\" is not of type \"integer\" at /services/0/ports/1 line 3 column 16"
+        );
+        Ok(())
+    }
+}
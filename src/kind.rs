@@ -0,0 +1,322 @@
+use crate::YamlVersion;
+use std::fmt;
+use yaml_rust::scanner::{TScalarStyle, TokenType};
+
+/// How a scalar was written in its source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScalarStyle {
+    /// Unquoted, `foo`
+    Plain,
+    /// Quoted with single quotes, `'foo'`
+    SingleQuoted,
+    /// Quoted with double quotes, `"foo"`
+    DoubleQuoted,
+    /// A literal block scalar, `|`
+    Literal,
+    /// A folded block scalar, `>`
+    Folded,
+}
+
+impl ScalarStyle {
+    /// Returns true for quoted styles
+    pub fn is_quoted(self) -> bool {
+        matches!(self, ScalarStyle::SingleQuoted | ScalarStyle::DoubleQuoted)
+    }
+
+    /// Returns true for block scalar styles
+    pub fn is_block(self) -> bool {
+        matches!(self, ScalarStyle::Literal | ScalarStyle::Folded)
+    }
+}
+
+impl From<TScalarStyle> for ScalarStyle {
+    fn from(style: TScalarStyle) -> Self {
+        match style {
+            TScalarStyle::SingleQuoted => ScalarStyle::SingleQuoted,
+            TScalarStyle::DoubleQuoted => ScalarStyle::DoubleQuoted,
+            TScalarStyle::Literal => ScalarStyle::Literal,
+            TScalarStyle::Foled => ScalarStyle::Folded,
+            _ => ScalarStyle::Plain,
+        }
+    }
+}
+
+#[cfg(feature = "yaml-rust2")]
+impl From<yaml_rust2::scanner::TScalarStyle> for ScalarStyle {
+    fn from(style: yaml_rust2::scanner::TScalarStyle) -> Self {
+        use yaml_rust2::scanner::TScalarStyle;
+        match style {
+            TScalarStyle::SingleQuoted => ScalarStyle::SingleQuoted,
+            TScalarStyle::DoubleQuoted => ScalarStyle::DoubleQuoted,
+            TScalarStyle::Literal => ScalarStyle::Literal,
+            TScalarStyle::Folded => ScalarStyle::Folded,
+            TScalarStyle::Plain => ScalarStyle::Plain,
+        }
+    }
+}
+
+/// The indefinite article preceding a kind's name
+pub(crate) fn article(kind: NodeKind) -> &'static str {
+    match kind {
+        NodeKind::Integer | NodeKind::Alias => "an",
+        _ => "a",
+    }
+}
+
+/// Formats a scanned tag as it was written, e.g. `!!str` or `!custom`
+pub(crate) fn tag(token: &TokenType) -> Option<String> {
+    match token {
+        TokenType::Tag(handle, suffix) => Some(tag_name(handle, suffix)),
+        _ => None,
+    }
+}
+
+/// Formats a tag's handle and suffix as they were written
+pub(crate) fn tag_name(
+    handle: &str,
+    suffix: &str,
+) -> String {
+    match handle {
+        "" if suffix == "!" => "!".into(),
+        "" => format!("!<{}>", suffix),
+        // parsers which resolve the secondary handle report its expansion
+        "tag:yaml.org,2002:" => format!("!!{}", suffix),
+        _ => format!("{}{}", handle, suffix),
+    }
+}
+
+/// The kind of node a JSON Pointer path refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    String,
+    Integer,
+    Float,
+    Bool,
+    Null,
+    Mapping,
+    Sequence,
+    /// An alias, `*name`, referring to an anchored node
+    Alias,
+}
+
+impl fmt::Display for NodeKind {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.write_str(match self {
+            NodeKind::String => "string",
+            NodeKind::Integer => "integer",
+            NodeKind::Float => "float",
+            NodeKind::Bool => "boolean",
+            NodeKind::Null => "null",
+            NodeKind::Mapping => "mapping",
+            NodeKind::Sequence => "sequence",
+            NodeKind::Alias => "alias",
+        })
+    }
+}
+
+impl NodeKind {
+    /// Resolves the kind of a scalar from its explicit tag or, for plain scalars, its value
+    /// as a version of YAML reads it
+    pub(crate) fn scalar(
+        value: &str,
+        style: ScalarStyle,
+        tag: Option<&str>,
+        version: YamlVersion,
+    ) -> NodeKind {
+        match tag {
+            Some("!!str") | Some("!!binary") => return NodeKind::String,
+            Some("!!int") => return NodeKind::Integer,
+            Some("!!float") => return NodeKind::Float,
+            Some("!!bool") => return NodeKind::Bool,
+            Some("!!null") => return NodeKind::Null,
+            _ => (),
+        }
+        if style != ScalarStyle::Plain {
+            return NodeKind::String;
+        }
+        if matches!(value, "~" | "null" | "Null" | "NULL") {
+            NodeKind::Null
+        } else if boolean(version, value).is_some() {
+            NodeKind::Bool
+        } else if integer(version, value).is_some() {
+            NodeKind::Integer
+        } else if float(version, value).is_some() {
+            NodeKind::Float
+        } else {
+            NodeKind::String
+        }
+    }
+}
+
+/// Splits a leading sign from a number, returning whether it's negative
+fn sign(value: &str) -> (bool, &str) {
+    match value.as_bytes().first() {
+        Some(b'-') => (true, &value[1..]),
+        Some(b'+') => (false, &value[1..]),
+        _ => (false, value),
+    }
+}
+
+/// Returns true for a non-empty run of digits of a radix, and of `_`s when YAML 1.1 allows them
+fn digits(
+    value: &str,
+    radix: u32,
+    underscores: bool,
+) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_digit(radix) || (underscores && c == '_'))
+}
+
+/// Returns true for the base 60 digits following a YAML 1.1 sexagesimal number's first `:`,
+/// such as the `20:30` of `190:20:30`
+fn sexagesimal(parts: &str) -> bool {
+    parts.split(':').all(|part| {
+        let bytes = part.as_bytes();
+        match bytes {
+            [digit] => digit.is_ascii_digit(),
+            [tens, digit] => (b'0'..=b'5').contains(tens) && digit.is_ascii_digit(),
+            _ => false,
+        }
+    })
+}
+
+/// Sums the base 60 parts of a sexagesimal number, whose first part may be written with `_`s
+fn base60(value: &str) -> Option<i64> {
+    value.split(':').try_fold(0i64, |sum, part| {
+        sum.checked_mul(60)?
+            .checked_add(part.replace('_', "").parse().ok()?)
+    })
+}
+
+/// Resolves a plain boolean
+pub(crate) fn boolean(
+    version: YamlVersion,
+    value: &str,
+) -> Option<bool> {
+    match value {
+        "true" | "True" | "TRUE" => Some(true),
+        "false" | "False" | "FALSE" => Some(false),
+        _ if version == YamlVersion::V1_2 => None,
+        "y" | "Y" | "yes" | "Yes" | "YES" | "on" | "On" | "ON" => Some(true),
+        "n" | "N" | "no" | "No" | "NO" | "off" | "Off" | "OFF" => Some(false),
+        _ => None,
+    }
+}
+
+/// Resolves a plain integer, whose value is `None` when it doesn't fit in an `i64`
+pub(crate) fn integer(
+    version: YamlVersion,
+    value: &str,
+) -> Option<Option<i64>> {
+    let (negative, unsigned) = sign(value);
+    let parse = |digits: &str, radix: u32| {
+        let magnitude = i64::from_str_radix(&digits.replace('_', ""), radix).ok()?;
+        Some(if negative { -magnitude } else { magnitude })
+    };
+    match version {
+        YamlVersion::V1_2 => {
+            // octal and hexadecimal integers are unsigned
+            let (radix, digits) = match (value.strip_prefix("0o"), value.strip_prefix("0x")) {
+                (Some(octal), _) => (8, octal),
+                (_, Some(hex)) => (16, hex),
+                _ => (10, unsigned),
+            };
+            Some(parse(digits, radix)).filter(|_| self::digits(digits, radix, false))
+        }
+        YamlVersion::V1_1 => {
+            let (radix, digits) = match (unsigned.strip_prefix("0b"), unsigned.strip_prefix("0x")) {
+                (Some(binary), _) => (2, binary),
+                (_, Some(hex)) => (16, hex),
+                _ if unsigned.len() > 1 && unsigned.starts_with('0') => (8, &unsigned[1..]),
+                _ => (10, unsigned),
+            };
+            if radix == 10 && !unsigned.starts_with(|c: char| ('1'..='9').contains(&c)) {
+                return Some(Some(0)).filter(|_| unsigned == "0");
+            }
+            match digits.split_once(':') {
+                Some((first, rest)) if radix == 10 => {
+                    let valid = self::digits(first, 10, true) && sexagesimal(rest);
+                    let magnitude = base60(digits);
+                    Some(magnitude.map(|magnitude| if negative { -magnitude } else { magnitude }))
+                        .filter(|_| valid)
+                }
+                _ => Some(parse(digits, radix)).filter(|_| self::digits(digits, radix, true)),
+            }
+        }
+    }
+}
+
+/// Resolves a plain float
+pub(crate) fn float(
+    version: YamlVersion,
+    value: &str,
+) -> Option<f64> {
+    if matches!(value, ".nan" | ".NaN" | ".NAN") {
+        return Some(f64::NAN);
+    }
+    let (negative, unsigned) = sign(value);
+    if matches!(unsigned, ".inf" | ".Inf" | ".INF") {
+        return Some(if negative {
+            f64::NEG_INFINITY
+        } else {
+            f64::INFINITY
+        });
+    }
+    let (mantissa, exponent) = match unsigned.find(['e', 'E']) {
+        Some(idx) => (&unsigned[..idx], Some(&unsigned[idx + 1..])),
+        None => (unsigned, None),
+    };
+    match version {
+        YamlVersion::V1_2 => {
+            let (whole, fraction) = match mantissa.split_once('.') {
+                Some((whole, fraction)) => (whole, Some(fraction)),
+                None => (mantissa, None),
+            };
+            let valid = match (whole, fraction) {
+                ("", Some(fraction)) => digits(fraction, 10, false),
+                (whole, fraction) => {
+                    digits(whole, 10, false)
+                        && fraction.is_none_or(|fraction| {
+                            fraction.is_empty() || digits(fraction, 10, false)
+                        })
+                }
+            } && exponent.is_none_or(|exponent| digits(sign(exponent).1, 10, false));
+            value.parse().ok().filter(|_| valid)
+        }
+        YamlVersion::V1_1 => {
+            // YAML 1.1 floats have a point, and signed exponents
+            let (whole, fraction) = mantissa.split_once('.')?;
+            let valid = match whole.split_once(':') {
+                None if whole.is_empty() => fraction.starts_with(|c: char| c.is_ascii_digit()),
+                None => whole.starts_with(|c: char| c.is_ascii_digit()) && digits(whole, 10, true),
+                Some((first, rest)) => {
+                    exponent.is_none()
+                        && first.starts_with(|c: char| c.is_ascii_digit())
+                        && digits(first, 10, true)
+                        && sexagesimal(rest)
+                }
+            } && (fraction.is_empty() || digits(fraction, 10, true))
+                && exponent.is_none_or(|exponent| {
+                    exponent.starts_with(['-', '+']) && digits(&exponent[1..], 10, false)
+                });
+            if !valid {
+                return None;
+            }
+            let magnitude = match whole.split_once(':') {
+                Some(_) => {
+                    base60(whole)? as f64
+                        + format!("0.{}", fraction.replace('_', ""))
+                            .parse::<f64>()
+                            .ok()?
+                }
+                None => unsigned.replace('_', "").parse().ok()?,
+            };
+            Some(if negative { -magnitude } else { magnitude })
+        }
+    }
+}
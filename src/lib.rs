@@ -1,4 +1,4 @@
-#![deny(intra_doc_link_resolution_failure)]
+#![deny(rustdoc::broken_intra_doc_links)]
 //! A [JSON Pointer](https://tools.ietf.org/html/rfc6901) index for line/column information within JSON and YAML content
 //!
 //! # Example
@@ -31,15 +31,193 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! # `no_std`
+//!
+//! lincolns requires `std`. Content is parsed with [yaml-rust](https://docs.rs/yaml-rust),
+//! which has no `no_std` support, so there is no alloc-only core to build on its own
+//!
+//! # Features
+//!
+//! * `ariadne` - builds multi-label [ariadne](https://docs.rs/ariadne) reports for pointers
+//! * `codespan` - builds [codespan-reporting](https://docs.rs/codespan-reporting) diagnostics labeling pointers
+//! * `derive` - `#[derive(Located)]` for populating position and span fields of your own types
+//! * `ffi` - a C ABI for looking up positions, described by the [`ffi`](ffi/index.html) module
+//! * `hash-index` - [`Options::hash_index`](struct.Options.html#method.hash_index), speeding up exact lookups with a hash map
+//! * `hcl` - an [`Hcl`](struct.Hcl.html) backend indexing blocks and attributes of HCL content, such as Terraform configuration, with [hcl-edit](https://docs.rs/hcl-edit)
+//! * `jsonschema` - locates [jsonschema](https://docs.rs/jsonschema) validation errors as [`SchemaError`](struct.SchemaError.html)s
+//! * `lsp` - conversions into [lsp-types](https://docs.rs/lsp-types) positions, ranges, and locations
+//! * `lsp-server` - a language server, described by the [`lsp_server`](lsp_server/index.html) module, showing pointers on hover, outlining documents, and locating pointers
+//! * `miette` - source spans and diagnostics for [miette](https://docs.rs/miette)
+//! * `mmap` - [`from_mmap`](fn.from_mmap.html), indexing files mapped into memory rather than read
+//! * `path-to-error` - resolves [serde_path_to_error](https://docs.rs/serde_path_to_error) paths and errors to positions
+//! * `plist` - a [`Plist`](struct.Plist.html) backend indexing XML and ASCII property lists
+//! * `python` - a [PyO3](https://pyo3.rs) module exposing `from_str` and a mapping of pointers to positions to Python
+//! * `rayon` - [`from_str_parallel`](fn.from_str_parallel.html), indexing the items of large root sequences, such as JSON arrays, in parallel
+//! * `regex` - [`Positions::find`](struct.Positions.html#method.find), matching pointers against regular expressions
+//! * `schemars` - [`Positions::validate_schema`](struct.Positions.html#method.validate_schema), validating documents against [schemars](https://docs.rs/schemars) schemas and locating each problem
+//! * `serde` - deserialize into your own types with [`Spanned`](struct.Spanned.html) values capturing where each came from, and serialize [`Position`](struct.Position.html)s
+//! * `serde-ignored` - locates the fields [serde_ignored](https://docs.rs/serde_ignored) reports were ignored while deserializing, such as misspelled keys
+//! * `serde_json` and `serde_yaml` - find the pointers [serde_json](https://docs.rs/serde_json) and [serde_yaml](https://docs.rs/serde_yaml) errors occurred within
+//! * `simd` - a [`SimdJson`](struct.SimdJson.html) backend for quickly indexing large JSON documents with [simd-json](https://docs.rs/simd-json)
+//! * `test-util` - [arbitrary](https://docs.rs/arbitrary) generators of positions and of small documents paired with the pointers expected of them, described by the [`test_util`](test_util/index.html) module
+//! * `tracing` - [tracing](https://docs.rs/tracing) spans around parsing, indexing, and scanning for comments and anchors, with counts and durations
+//! * `wasm` - [wasm-bindgen](https://docs.rs/wasm-bindgen) exports for looking up positions from JavaScript
+//! * `xml` - an [`Xml`](struct.Xml.html) backend indexing elements and attributes of XML documents with [roxmltree](https://docs.rs/roxmltree)
+//! * `yaml-rust2` - a [`YamlRust2`](struct.YamlRust2.html) backend built on the maintained [yaml-rust2](https://docs.rs/yaml-rust2) parser
+// derived impls name this crate by its path, as they would downstream
+#[cfg(feature = "derive")]
+extern crate self as lincolns;
+
+// macros are defined before the modules using them
+#[macro_use]
+mod trace;
+
+mod anchor;
+#[cfg(feature = "ariadne")]
+mod ariadne;
+mod backend;
+mod binary;
+#[cfg(feature = "codespan")]
+mod codespan;
+mod comment;
+pub mod cst;
+#[cfg(feature = "serde")]
+mod de;
+mod diagnostic;
+mod diff;
+mod document;
+mod dotted;
+mod each;
+pub mod edit;
+mod encoding;
 mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod frozen;
+#[cfg(feature = "hcl")]
+mod hcl;
+#[cfg(feature = "serde-ignored")]
+mod ignored;
+mod include;
+mod ini;
+#[cfg(feature = "jsonschema")]
+mod jsonschema;
+mod kind;
+mod located;
+mod lookup;
+#[cfg(feature = "lsp")]
+mod lsp;
+#[cfg(feature = "lsp-server")]
+pub mod lsp_server;
+#[cfg(feature = "miette")]
+mod miette;
+#[cfg(feature = "mmap")]
+mod mmap;
+mod options;
+mod outline;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod patch;
 mod path;
+#[cfg(feature = "path-to-error")]
+mod path_to_error;
+#[cfg(feature = "plist")]
+mod plist;
+mod pointer;
 mod position;
+mod provenance;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "regex")]
+mod regex;
+#[cfg(feature = "schemars")]
+mod schemars;
+#[cfg(any(feature = "serde_json", feature = "serde_yaml"))]
+mod serde_error;
+#[cfg(feature = "simd")]
+mod simd;
+mod snippet;
+mod source;
+mod stats;
+mod stream;
+mod suggest;
+mod template;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+mod tree;
+mod trie;
+mod validate;
+mod value;
+mod walk;
+mod warning;
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "xml")]
+mod xml;
 
+#[cfg(feature = "jsonschema")]
+pub use crate::jsonschema::SchemaError;
+#[cfg(feature = "miette")]
+pub use crate::miette::PointerDiagnostic;
+pub use anchor::{Alias, Anchor};
+#[cfg(feature = "yaml-rust2")]
+pub use backend::YamlRust2;
+pub use backend::{Backend, Event, Yaml};
+pub use binary::{from_cbor_slice, from_msgpack_slice};
+pub use comment::Comment;
+#[cfg(feature = "serde")]
+pub use de::{deserialize, deserialize_with, Deserializer, Spanned};
+pub use diagnostic::{Diagnostic, Severity};
+pub use diff::{diff, Change, Diff, Moved, Revision};
+pub use document::{Directive, Document};
+pub use each::for_each;
 pub use error::{Error, Result};
+pub use frozen::FrozenPositions;
+#[cfg(feature = "hcl")]
+pub use hcl::Hcl;
+#[cfg(feature = "serde-ignored")]
+pub use ignored::ignored_pointer;
+pub use include::{Include, Includes, Resolver, SourceId};
+pub use ini::{Ini, Properties};
+pub use kind::{NodeKind, ScalarStyle};
+#[cfg(feature = "derive")]
+pub use lincolns_derive::Located;
+pub use located::Located;
+pub use lookup::locate;
+#[cfg(feature = "mmap")]
+pub use mmap::{from_mmap, from_mmap_with};
+pub use options::{ColumnUnit, DuplicateKeys, Limit, MergeKeys, Options, YamlVersion};
+pub use outline::Symbol;
+#[cfg(feature = "rayon")]
+pub use parallel::from_str_parallel;
+pub use patch::{diff_patch, DiffOperation, MergeChange, MergeOp, PatchOperation};
 use path::Path;
-pub use position::{Position, Positions};
+#[cfg(feature = "path-to-error")]
+pub use path_to_error::path_pointer;
+#[cfg(feature = "plist")]
+pub use plist::Plist;
+pub use pointer::{Pointer, PointerError};
+pub use position::{Duplicate, IntoIter, Iter, Position, Positions, Span};
+pub use provenance::{Origin, Provenance};
+#[cfg(feature = "simd")]
+pub use simd::SimdJson;
+use source::Source;
+pub use source::{LineEnding, LineIndex};
+pub use stats::Stats;
+use std::borrow::Cow;
 use std::io::Read;
-use yaml_rust::parser::Parser;
+pub use stream::{Events, SpannedEvent};
+pub use tree::Node;
+pub use trie::PositionTrie;
+pub use validate::{Requirement, Unknown, Violation};
+pub use value::{Annotated, Value};
+pub use walk::{Entry, Walk};
+pub use warning::Warning;
+#[cfg(feature = "wasm")]
+pub use wasm::{wasm_from_str, WasmEntry, WasmPosition, WasmPositions};
+#[cfg(feature = "xml")]
+pub use xml::Xml;
 
 /// Load a lookup table of [`Position`](struct.Position.html) information
 /// from utf8 text
@@ -47,23 +225,288 @@ pub fn from_str<S>(s: S) -> Result<Positions>
 where
     S: AsRef<str>,
 {
-    let mut parser = Parser::new(s.as_ref().chars());
+    from_str_with(s, Options::default())
+}
+
+/// Load a lookup table of [`Position`](struct.Position.html) information
+/// from utf8 text using the provided [`Options`](struct.Options.html)
+pub fn from_str_with<S>(
+    s: S,
+    options: Options,
+) -> Result<Positions>
+where
+    S: AsRef<str>,
+{
+    from_str_using(&Yaml, s, options)
+}
+
+/// Load a lookup table of [`Position`](struct.Position.html) information
+/// from utf8 text parsed by a [`Backend`](trait.Backend.html) using the provided [`Options`](struct.Options.html)
+pub fn from_str_using<B, S>(
+    backend: &B,
+    s: S,
+    options: Options,
+) -> Result<Positions>
+where
+    B: Backend + ?Sized,
+    S: AsRef<str>,
+{
+    load(backend, s.as_ref(), options, Positions::default())
+}
+
+/// Load a stream of [`SpannedEvent`](enum.SpannedEvent.html)s from utf8 text, for building
+/// structures of your own with position information rather than querying an index
+///
+/// ```rust,edition2018
+/// use lincolns::{events, SpannedEvent};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let keys = events("a: 1\nb: 2")?
+///     .filter_map(|(event, position)| match event {
+///         SpannedEvent::Key { pointer, .. } => Some((pointer, position.line)),
+///         _ => None,
+///     })
+///     .collect::<Vec<_>>();
+/// assert_eq!(keys, vec![("/a".to_string(), 1), ("/b".to_string(), 2)]);
+/// # Ok(())
+/// # }
+/// ```
+pub fn events<S>(s: S) -> Result<Events>
+where
+    S: AsRef<str>,
+{
+    events_with(s, Options::default())
+}
+
+/// Load a stream of [`SpannedEvent`](enum.SpannedEvent.html)s from utf8 text
+/// using the provided [`Options`](struct.Options.html)
+pub fn events_with<S>(
+    s: S,
+    options: Options,
+) -> Result<Events>
+where
+    S: AsRef<str>,
+{
     let mut positions = Positions::default();
-    parser.load(&mut positions, true)?;
-    positions.collect(&Path::Root);
-    Ok(positions)
+    positions.record();
+    let mut positions = load(&Yaml, s.as_ref(), options, positions)?;
+    Ok(Events::new(positions.take_stream()))
+}
+
+/// Load a lookup table of [`Position`](struct.Position.html) information from utf8 text,
+/// indexing what it can rather than failing on the first error
+///
+/// Content preceding a syntax error is indexed, and the error returned alongside the table
+/// with any others, such as every repeated key when loading with
+/// [`DuplicateKeys::Error`](enum.DuplicateKeys.html#variant.Error)
+///
+/// ```rust,edition2018
+/// use lincolns::{from_str_recovering, Options, Position};
+///
+/// let (positions, errors) = from_str_recovering("a: 1\nb: [2\n", Options::default());
+/// assert_eq!(positions.get("/a"), Some(&Position { line: 1, col: 0 }));
+/// assert_eq!(errors.len(), 1);
+/// ```
+pub fn from_str_recovering<S>(
+    s: S,
+    options: Options,
+) -> (Positions, Vec<Error>)
+where
+    S: AsRef<str>,
+{
+    load_recovering(&Yaml, s.as_ref(), options, Positions::default())
+}
+
+fn load<B>(
+    backend: &B,
+    text: &str,
+    options: Options,
+    positions: Positions,
+) -> Result<Positions>
+where
+    B: Backend + ?Sized,
+{
+    let (positions, errors) = load_recovering(backend, text, options, positions);
+    match errors.into_iter().next() {
+        Some(err) => Err(err),
+        None => Ok(positions),
+    }
+}
+
+fn load_recovering<B>(
+    backend: &B,
+    text: &str,
+    options: Options,
+    mut positions: Positions,
+) -> (Positions, Vec<Error>)
+where
+    B: Backend + ?Sized,
+{
+    let _load = phase!("load", bytes = text.len());
+    if let Some(limit) = options.exceeded(text.len(), 0, 0) {
+        return (positions, vec![Error::LimitExceeded(limit)]);
+    }
+    let original = text;
+    let masked = if options.templates {
+        template::mask(text)
+    } else {
+        Cow::Borrowed(text)
+    };
+    let text = masked.as_ref();
+    let source = Source::new(text, &options);
+    let mut errors = Vec::new();
+    // byte order marks are stripped before parsing, offsetting what's parsed after them
+    let bom = if text.starts_with(source::BOM) {
+        positions.mark_bom();
+        source::BOM.len_utf8()
+    } else {
+        0
+    };
+    let (mut depth, mut nodes, mut exceeded) = (0, 0, None);
+    let parsing = phase!("parse");
+    let parsed = backend.parse(&text[bom..], &mut |event, offset| {
+        if exceeded.is_some() {
+            return;
+        }
+        match event {
+            Event::SequenceStart { .. } | Event::MappingStart { .. } => {
+                depth += 1;
+                nodes += 1;
+            }
+            Event::SequenceEnd | Event::MappingEnd => depth -= 1,
+            Event::Scalar { .. } | Event::Alias { .. } => nodes += 1,
+            Event::DocumentStart | Event::DocumentEnd => (),
+        }
+        // content past a limit is left unindexed rather than held onto
+        exceeded = options.exceeded(0, depth, nodes);
+        if exceeded.is_none() {
+            positions.push(event, offset + bom)
+        }
+    });
+    parsing.finish(nodes);
+    if let Some(limit) = exceeded {
+        errors.push(Error::LimitExceeded(limit));
+    }
+    if let Err(err) = parsed {
+        errors.push(match err {
+            // number parse errors as the positions of the table would have been
+            Error::Parse { error, .. } => {
+                let marker = error.marker();
+                let position = source.marker(marker.line(), marker.col());
+                Error::Parse { error, position }
+            }
+            err => err,
+        });
+    }
+    positions.collect(&source);
+    finish(positions, options, source, original, errors)
+}
+
+/// Applies the options which act on a table once its nodes are indexed
+fn finish(
+    mut positions: Positions,
+    mut options: Options,
+    source: Source<'_>,
+    original: &str,
+    mut errors: Vec<Error>,
+) -> (Positions, Vec<Error>) {
+    positions.mark_content(original);
+    if let Some(handler) = options.on_unhandled.as_mut() {
+        if let Some(warning) = positions
+            .warnings()
+            .iter()
+            .find(|warning| !handler.handle(warning))
+        {
+            errors.push(Error::Unhandled(warning.clone()));
+        }
+    }
+    if options.duplicate_keys == DuplicateKeys::Error {
+        errors.extend(
+            positions
+                .duplicates()
+                .iter()
+                .map(|duplicate| Error::DuplicateKey {
+                    pointer: duplicate.pointer.clone(),
+                    first: duplicate.first,
+                    second: duplicate.second,
+                }),
+        );
+    }
+    if options.case_insensitive {
+        positions.fold_case();
+    }
+    #[cfg(feature = "hash-index")]
+    if options.hash_index {
+        positions.hash();
+    }
+    if options.keep_source {
+        positions.retain_source(original.to_owned(), source.into_lines());
+    }
+    (positions, errors)
+}
+
+/// Load a lookup table of [`Position`](struct.Position.html) information from bytes, such as
+/// those received over a network or read from an archive
+///
+/// ```rust,edition2018
+/// use lincolns::{from_slice, Position};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let positions = from_slice(b"\xef\xbb\xbfname: web\n")?;
+/// assert!(positions.has_bom());
+/// assert_eq!(positions.get("/name"), Some(&Position { line: 1, col: 0 }));
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_slice(bytes: &[u8]) -> Result<Positions> {
+    from_slice_with(bytes, Options::default())
+}
+
+/// Load a lookup table of [`Position`](struct.Position.html) information from bytes using the
+/// provided [`Options`](struct.Options.html)
+///
+/// UTF-16 and UTF-32 content is decoded as it is by [`from_reader_with`](fn.from_reader_with.html),
+/// and UTF-8 content is indexed without being copied
+pub fn from_slice_with(
+    bytes: &[u8],
+    options: Options,
+) -> Result<Positions> {
+    if let Some(limit) = options.exceeded(bytes.len(), 0, 0) {
+        return Err(Error::LimitExceeded(limit));
+    }
+    let text = encoding::decode(bytes, options.lossy)?;
+    from_str_with(text, options)
 }
 
 /// Load a lookup table of [`Position`](struct.Position.html) information from a type which implements
 /// `Read`
-pub fn from_reader<R>(mut rdr: R) -> Result<Positions>
+pub fn from_reader<R>(rdr: R) -> Result<Positions>
+where
+    R: Read,
+{
+    from_reader_with(rdr, Options::default())
+}
+
+/// Load a lookup table of [`Position`](struct.Position.html) information from a type which implements
+/// `Read` using the provided [`Options`](struct.Options.html)
+///
+/// UTF-16 and UTF-32 content is detected as YAML specifies and decoded, with
+/// byte offsets then referring to the decoded UTF-8 text
+pub fn from_reader_with<R>(
+    mut rdr: R,
+    options: Options,
+) -> Result<Positions>
 where
     R: Read,
 {
     let mut bytes = Vec::new();
-    rdr.read_to_end(&mut bytes)?;
-    let s = std::str::from_utf8(&bytes)?;
-    from_str(s)
+    match options.max_input_size {
+        // reading just past the limit is enough to know it was exceeded
+        Some(max) => rdr.take(max as u64 + 1).read_to_end(&mut bytes)?,
+        None => rdr.read_to_end(&mut bytes)?,
+    };
+    let text = encoding::decode(&bytes, options.lossy)?;
+    from_str_with(text, options)
 }
 
 #[cfg(test)]
@@ -90,10 +533,133 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn parse_errors_are_positioned() {
+        let err = from_str_with("a: 1\nb: [2\n", Options::default().one_based(true)).unwrap_err();
+        match err {
+            Error::Parse { position, .. } => assert_eq!(position, Position { line: 3, col: 1 }),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(std::error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn recovers_content_preceding_errors() {
+        let (positions, errors) = from_str_recovering(
+            "a: 1\nb:\n  c: 2\nb: 3\nd: [4\n",
+            Options::default().duplicate_keys(DuplicateKeys::Error),
+        );
+        assert_eq!(positions.get("/a"), Some(&Position { line: 1, col: 0 }));
+        assert_eq!(positions.get("/b"), Some(&Position { line: 4, col: 0 }));
+        assert!(matches!(
+            errors.as_slice(),
+            [Error::Parse { .. }, Error::DuplicateKey { pointer, .. }] if pointer == "/b"
+        ));
+    }
+
+    #[test]
+    fn unhandled_content_may_be_ignored() -> Result<()> {
+        use std::{cell::RefCell, rc::Rc};
+        let seen = Rc::new(RefCell::new(Vec::new()));
+        let handled = seen.clone();
+        let positions = from_str_with(
+            "a: &a 1\n*a : 2\nb: 3\n",
+            Options::default().on_unhandled(move |warning| {
+                handled.borrow_mut().push(warning.position().line);
+                true
+            }),
+        )?;
+        assert_eq!(*seen.borrow(), vec![2]);
+        assert!(positions.contains("/b"));
+        Ok(())
+    }
+
+    #[test]
+    fn limits_resources() -> Result<()> {
+        let text = "a:\n  b:\n    c: [1, 2]\n";
+        assert!(from_str_with(text, Options::default().max_depth(4)).is_ok());
+        assert!(matches!(
+            from_str_with(text, Options::default().max_depth(3)),
+            Err(Error::LimitExceeded(Limit::Depth(3)))
+        ));
+        assert!(from_str_with(text, Options::default().max_nodes(9)).is_ok());
+        let (positions, errors) = from_str_recovering(text, Options::default().max_nodes(8));
+        assert!(matches!(
+            errors.as_slice(),
+            [Error::LimitExceeded(Limit::Nodes(8))]
+        ));
+        assert!(positions.contains("/a/b/c/0"));
+        assert!(!positions.contains("/a/b/c/1"));
+        assert!(matches!(
+            from_str_with(text, Options::default().max_input_size(4)),
+            Err(Error::LimitExceeded(Limit::InputSize(4)))
+        ));
+        assert!(matches!(
+            from_reader_with(text.as_bytes(), Options::default().max_input_size(4)),
+            Err(Error::LimitExceeded(Limit::InputSize(4)))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn lossy_reads_invalid_utf8() -> Result<()> {
+        let bytes: &[u8] = b"a: \xff\xfe\nb: 2\n";
+        assert!(matches!(from_reader(bytes), Err(Error::Utf8(_))));
+        let positions = from_reader_with(bytes, Options::default().lossy(true).keep_source(true))?;
+        assert_eq!(positions.text_at("/a"), Some("\u{fffd}\u{fffd}"));
+        assert_eq!(positions.get("/b"), Some(&Position { line: 2, col: 0 }));
+        Ok(())
+    }
+
+    #[test]
+    fn strips_byte_order_marks() -> Result<()> {
+        let positions = from_str_with("\u{feff}a: 1\nb: 2", Options::default().keep_source(true))?;
+        assert!(positions.has_bom());
+        assert_eq!(positions.get("/a"), Some(&Position { line: 1, col: 0 }));
+        assert_eq!(positions.text_at("/a"), Some("1"));
+        assert_eq!(positions.line_text(1), Some("a: 1"));
+        assert!(!from_str("a: 1")?.has_bom());
+        Ok(())
+    }
+
+    #[test]
+    fn decodes_byte_order_marks() -> Result<()> {
+        let utf16 = "\u{feff}a: 1"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect::<Vec<_>>();
+        let positions = from_reader(utf16.as_slice())?;
+        assert!(positions.has_bom());
+        assert_eq!(positions.get("/a"), Some(&Position { line: 1, col: 0 }));
+        Ok(())
+    }
+
+    #[test]
+    fn from_slice_decodes_bytes() -> Result<()> {
+        let utf16 = "\u{feff}a: 1\nb: [2]"
+            .encode_utf16()
+            .flat_map(u16::to_be_bytes)
+            .collect::<Vec<_>>();
+        let positions = from_slice(&utf16)?;
+        assert!(positions.has_bom());
+        assert_eq!(positions.get("/b/0"), Some(&Position { line: 2, col: 4 }));
+        assert!(matches!(from_slice(b"a: \xff"), Err(Error::Utf8(_))));
+        assert!(matches!(
+            from_slice_with(b"a: [1, 2]", Options::default().max_input_size(4)),
+            Err(Error::LimitExceeded(Limit::InputSize(4)))
+        ));
+        Ok(())
+    }
+
     #[test]
     fn impl_into_iter() -> Result<()> {
         let positions = from_str(include_str!("../tests/data/example.yml"))?;
-        assert!(positions.iter().into_iter().next().is_some());
+        assert!(positions.iter().next().is_some());
+        let mut borrowed = Vec::new();
+        for (pointer, position) in &positions {
+            borrowed.push((pointer.clone(), *position));
+        }
+        assert_eq!(borrowed, positions.into_iter().collect::<Vec<_>>());
         Ok(())
     }
 }
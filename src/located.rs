@@ -0,0 +1,152 @@
+use crate::{path, Positions};
+use std::collections::{BTreeMap, HashMap};
+
+/// Types which record where their values were found, populated from a
+/// [`Positions`](struct.Positions.html) table after they've been deserialized
+///
+/// With the `derive` feature, this can be derived for structs, marking which fields to populate
+///
+/// ```rust,edition2018
+/// # #[cfg(feature = "derive")]
+/// # fn main() -> lincolns::Result<()>  {
+/// use lincolns::{from_str, Located, Position};
+///
+/// #[derive(Default, Located)]
+/// struct Service {
+///     image: String,
+///     #[located(position = "image")]
+///     image_position: Option<Position>,
+/// }
+///
+/// let positions = from_str("image: nginx\n")?;
+/// let mut service = Service { image: "nginx".into(), ..Service::default() };
+/// positions.locate(&mut service);
+/// assert_eq!(service.image_position, Some(Position { line: 1, col: 0 }));
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "derive"))]
+/// # fn main() {}
+/// ```
+pub trait Located {
+    /// Populates location information given the pointer this value was found at
+    fn locate(
+        &mut self,
+        positions: &Positions,
+        pointer: &str,
+    );
+}
+
+impl<T: Located> Located for Option<T> {
+    fn locate(
+        &mut self,
+        positions: &Positions,
+        pointer: &str,
+    ) {
+        if let Some(value) = self {
+            value.locate(positions, pointer);
+        }
+    }
+}
+
+impl<T: Located> Located for Box<T> {
+    fn locate(
+        &mut self,
+        positions: &Positions,
+        pointer: &str,
+    ) {
+        (**self).locate(positions, pointer);
+    }
+}
+
+impl<T: Located> Located for Vec<T> {
+    fn locate(
+        &mut self,
+        positions: &Positions,
+        pointer: &str,
+    ) {
+        for (index, item) in self.iter_mut().enumerate() {
+            item.locate(positions, &format!("{}/{}", pointer, index));
+        }
+    }
+}
+
+impl<T: Located> Located for BTreeMap<String, T> {
+    fn locate(
+        &mut self,
+        positions: &Positions,
+        pointer: &str,
+    ) {
+        for (key, value) in self.iter_mut() {
+            value.locate(positions, &format!("{}/{}", pointer, path::escape(key)));
+        }
+    }
+}
+
+impl<T: Located, S> Located for HashMap<String, T, S> {
+    fn locate(
+        &mut self,
+        positions: &Positions,
+        pointer: &str,
+    ) {
+        for (key, value) in self.iter_mut() {
+            value.locate(positions, &format!("{}/{}", pointer, path::escape(key)));
+        }
+    }
+}
+
+impl Positions {
+    /// Populates the location information of a value loaded from the root of this table
+    pub fn locate<T>(
+        &self,
+        value: &mut T,
+    ) where
+        T: Located,
+    {
+        value.locate(self, "");
+    }
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use crate::{from_str, Located, Position, Result, Span};
+
+    #[derive(Default, Located)]
+    struct Config {
+        #[located(span = "services")]
+        services_span: Option<Span>,
+        #[located(nested)]
+        services: Vec<Service>,
+    }
+
+    #[derive(Default, Located)]
+    struct Service {
+        #[located(position)]
+        position: Option<Position>,
+        #[located(position = "ports/1")]
+        second_port: Option<Position>,
+    }
+
+    #[test]
+    fn derives_located() -> Result<()> {
+        let positions = from_str("services:\n  - name: a\n  - name: b\n    ports: [80, 443]\n")?;
+        let mut config = Config {
+            services: vec![Service::default(), Service::default()],
+            ..Config::default()
+        };
+        positions.locate(&mut config);
+        assert_eq!(
+            config.services_span.map(|span| span.start),
+            Some(Position { line: 2, col: 2 })
+        );
+        assert_eq!(
+            config.services[1].position,
+            Some(Position { line: 3, col: 4 })
+        );
+        assert_eq!(
+            config.services[1].second_port,
+            Some(Position { line: 4, col: 16 })
+        );
+        assert_eq!(config.services[0].second_port, None);
+        Ok(())
+    }
+}
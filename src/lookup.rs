@@ -0,0 +1,232 @@
+use crate::{source::Source, Error, Options, Position, Result};
+use yaml_rust::parser::{Event, Parser};
+
+/// A collection whose nodes are being visited
+struct Frame {
+    sequence: bool,
+    /// Index of the next item of a sequence
+    next: usize,
+    /// Key of a mapping entry whose value is next, `None` for keys that aren't scalars
+    key: Option<Option<String>>,
+    /// Whether the collection's token was pushed onto the path
+    nested: bool,
+}
+
+/// Gets a node's position given its JSON Pointer path and utf8 text, without indexing
+/// the rest of the text
+///
+/// Parsing stops as soon as the node is found, making this much faster for
+/// a single lookup near the start of a large document. Positions are those
+/// [`Positions::get`](struct.Positions.html#method.get) reports, except that of repeated keys,
+/// which are found at their first occurrence rather than their last
+///
+/// ```rust,edition2018
+/// use lincolns::{locate, Position};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let yaml = "spec:\n  image: nginx\nstatus: {}\n";
+/// assert_eq!(locate(yaml, "/spec/image")?, Some(Position { line: 2, col: 2 }));
+/// assert_eq!(locate(yaml, "/spec/missing")?, None);
+/// # Ok(())
+/// # }
+/// ```
+pub fn locate<S, P>(
+    s: S,
+    ptr: P,
+) -> Result<Option<Position>>
+where
+    S: AsRef<str>,
+    P: AsRef<str>,
+{
+    let text = s.as_ref();
+    let ptr = ptr.as_ref();
+    let target = match ptr {
+        "" => Vec::new(),
+        _ => match ptr.strip_prefix('/') {
+            Some(tokens) => tokens
+                .split('/')
+                .map(|token| token.replace("~1", "/").replace("~0", "~"))
+                .collect::<Vec<_>>(),
+            None => return Ok(None),
+        },
+    };
+    let options = Options::default();
+    let source = Source::new(text, &options);
+    let mut parser = Parser::new(text.chars());
+    let mut frames = Vec::<Frame>::new();
+    let mut path = Vec::<Option<String>>::new();
+    // whether the node with a token completes the target, given the path to its parent
+    let found = |path: &[Option<String>], token: Option<&str>| {
+        path.len() + 1 == target.len()
+            && path
+                .iter()
+                .zip(&target)
+                .all(|(have, want)| have.as_deref() == Some(want.as_str()))
+            && token == target.last().map(String::as_str)
+    };
+    loop {
+        let (event, marker) = parser.next().map_err(|error| Error::Parse {
+            position: source.marker(error.marker().line(), error.marker().col()),
+            error,
+        })?;
+        let position = || source.marker(marker.line(), marker.col());
+        let token = match &event {
+            Event::StreamEnd => return Ok(None),
+            Event::DocumentStart => {
+                frames.clear();
+                path.clear();
+                continue;
+            }
+            Event::SequenceEnd | Event::MappingEnd => {
+                if frames.pop().is_some_and(|frame| frame.nested) {
+                    path.pop();
+                }
+                continue;
+            }
+            Event::Scalar(..)
+            | Event::Alias(_)
+            | Event::SequenceStart(_)
+            | Event::MappingStart(_) => match frames.last_mut() {
+                None if target.is_empty() => {
+                    return first(&mut parser, &source, &event, position()).map(Some)
+                }
+                None => None,
+                Some(frame) if frame.sequence => {
+                    frame.next += 1;
+                    Some((frame.next - 1).to_string())
+                }
+                Some(frame) => match frame.key.take() {
+                    Some(key) => key,
+                    None => {
+                        let key = match &event {
+                            Event::Scalar(value, ..) => Some(value.clone()),
+                            Event::SequenceStart(_) | Event::MappingStart(_) => {
+                                complex_key(&mut parser, &source, &event)?
+                            }
+                            _ => None,
+                        };
+                        if found(&path, key.as_deref()) {
+                            return Ok(Some(position()));
+                        }
+                        frame.key = Some(key);
+                        continue;
+                    }
+                },
+            },
+            _ => continue,
+        };
+        let nested = !frames.is_empty();
+        if nested
+            && frames.last().is_some_and(|frame| frame.sequence)
+            && found(&path, token.as_deref())
+        {
+            return first(&mut parser, &source, &event, position()).map(Some);
+        }
+        if let Event::SequenceStart(_) | Event::MappingStart(_) = event {
+            if nested {
+                path.push(token);
+            }
+            frames.push(Frame {
+                sequence: matches!(event, Event::SequenceStart(_)),
+                next: 0,
+                key: None,
+                nested,
+            });
+        }
+    }
+}
+
+/// Position of a node which just started, being that of its first child when a block collection
+/// is marked after it, as the index positions them
+fn first(
+    parser: &mut Parser<std::str::Chars>,
+    source: &Source,
+    event: &Event,
+    position: Position,
+) -> Result<Position> {
+    if let Event::SequenceStart(_) | Event::MappingStart(_) = event {
+        let (next, marker) = parser.next().map_err(|error| Error::Parse {
+            position: source.marker(error.marker().line(), error.marker().col()),
+            error,
+        })?;
+        if let Event::Scalar(..)
+        | Event::Alias(_)
+        | Event::SequenceStart(_)
+        | Event::MappingStart(_) = next
+        {
+            return Ok(position.min(source.marker(marker.line(), marker.col())));
+        }
+    }
+    Ok(position)
+}
+
+/// Consumes the rest of a collection used as a key, rendering it in flow style
+/// as the index does. Keys containing aliases have no rendering
+pub(crate) fn complex_key(
+    parser: &mut Parser<std::str::Chars>,
+    source: &Source,
+    start: &Event,
+) -> Result<Option<String>> {
+    let mut key = String::new();
+    let mut rendered = true;
+    let mut stack = vec![(matches!(start, Event::SequenceStart(_)), 0)];
+    while let Some((sequence, items)) = stack.pop() {
+        let (open, close) = if sequence { ('[', ']') } else { ('{', '}') };
+        if items == 0 {
+            key.push(open);
+        }
+        let (event, _) = parser.next().map_err(|error| Error::Parse {
+            position: source.marker(error.marker().line(), error.marker().col()),
+            error,
+        })?;
+        match event {
+            Event::SequenceEnd | Event::MappingEnd => {
+                key.push(close);
+                continue;
+            }
+            Event::StreamEnd => break,
+            _ => (),
+        }
+        if items > 0 {
+            key.push_str(if !sequence && items % 2 == 1 {
+                ": "
+            } else {
+                ", "
+            });
+        }
+        stack.push((sequence, items + 1));
+        match event {
+            Event::Scalar(value, ..) => key.push_str(&value),
+            Event::SequenceStart(_) => stack.push((true, 0)),
+            Event::MappingStart(_) => stack.push((false, 0)),
+            _ => rendered = false,
+        }
+    }
+    Ok(Some(key).filter(|_| rendered))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::from_str;
+
+    #[test]
+    fn locates_what_the_index_does() -> Result<()> {
+        let docs = [
+            include_str!("../tests/data/example.yml"),
+            include_str!("../tests/data/example.json"),
+            "a:\n  - b: 1\n    'c~d': [x, {y: z}]\n  -\n    - deep\n? [complex, key]\n: value\nf: &anchor {g: 1}\nh: *anchor\n",
+            "- - 1\n  - 2\n- {}\n",
+            "? [a, {b: c}]\n: 1\n? {x: [y]}\n: {z: 2}\n&k k: 3\n? *k\n: 4\nafter: 5\n",
+        ];
+        for doc in &docs {
+            let positions = from_str(doc)?;
+            for (ptr, position) in positions.iter() {
+                assert_eq!(locate(doc, ptr)?, Some(*position), "{} in {}", ptr, doc);
+            }
+            assert_eq!(locate(doc, "")?, positions.get("").copied());
+            assert_eq!(locate(doc, "/missing/0")?, None);
+        }
+        Ok(())
+    }
+}
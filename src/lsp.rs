@@ -0,0 +1,131 @@
+use crate::{NodeKind, Position, Positions, Span, Symbol};
+use lsp_types::{DocumentSymbol, Location, Range, SymbolKind, Uri};
+
+/// Converts a position loaded with the default numbering, lines starting at 1 and
+/// columns starting at 0, into a zero-based LSP position
+///
+/// LSP clients measure columns in UTF-16 code units by default. Load content with
+/// [`ColumnUnit::Utf16`](enum.ColumnUnit.html#variant.Utf16) to match
+impl From<&Position> for lsp_types::Position {
+    fn from(pos: &Position) -> Self {
+        lsp_types::Position {
+            line: pos.line.saturating_sub(1) as u32,
+            character: pos.col as u32,
+        }
+    }
+}
+
+impl From<&Span> for Range {
+    fn from(span: &Span) -> Self {
+        Range {
+            start: (&span.start).into(),
+            end: (&span.end).into(),
+        }
+    }
+}
+
+impl From<NodeKind> for SymbolKind {
+    fn from(kind: NodeKind) -> Self {
+        match kind {
+            NodeKind::String => SymbolKind::STRING,
+            NodeKind::Integer | NodeKind::Float => SymbolKind::NUMBER,
+            NodeKind::Bool => SymbolKind::BOOLEAN,
+            NodeKind::Null => SymbolKind::NULL,
+            NodeKind::Mapping => SymbolKind::OBJECT,
+            NodeKind::Sequence => SymbolKind::ARRAY,
+            NodeKind::Alias => SymbolKind::VARIABLE,
+        }
+    }
+}
+
+/// A document symbol spanning a symbol's entry, selecting its value
+impl From<&Symbol> for DocumentSymbol {
+    fn from(symbol: &Symbol) -> Self {
+        #[allow(deprecated)]
+        DocumentSymbol {
+            name: symbol.name.clone(),
+            detail: None,
+            kind: symbol.kind.into(),
+            tags: None,
+            deprecated: None,
+            range: Range {
+                start: (&symbol.start).into(),
+                end: (&symbol.span.end).into(),
+            },
+            selection_range: (&symbol.span).into(),
+            children: (!symbol.children.is_empty())
+                .then(|| symbol.children.iter().map(DocumentSymbol::from).collect()),
+        }
+    }
+}
+
+impl Positions {
+    /// Gets the LSP location of the value a JSON Pointer path refers to within the document at `uri`
+    pub fn location<P>(
+        &self,
+        ptr: P,
+        uri: Uri,
+    ) -> Option<Location>
+    where
+        P: AsRef<str>,
+    {
+        self.span(ptr).map(|span| Location {
+            uri,
+            range: span.into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_str, Result};
+    use lsp_types::{Position, Range};
+
+    #[test]
+    fn outline_converts_to_document_symbols() -> Result<()> {
+        let positions = from_str("foo:\n  bar: [1]")?;
+        let symbols = positions
+            .outline()
+            .iter()
+            .map(lsp_types::DocumentSymbol::from)
+            .collect::<Vec<_>>();
+        let bar = &symbols[0].children.as_ref().expect("no children")[0];
+        assert_eq!(bar.kind, lsp_types::SymbolKind::ARRAY);
+        assert_eq!(
+            bar.range,
+            Range {
+                start: Position {
+                    line: 1,
+                    character: 2
+                },
+                end: Position {
+                    line: 1,
+                    character: 10
+                },
+            }
+        );
+        assert_eq!(bar.selection_range.start.character, 7);
+        Ok(())
+    }
+
+    #[test]
+    fn location_is_zero_based() -> Result<()> {
+        let positions = from_str("foo:\n  bar: baz")?;
+        let uri = "file:///config.yml".parse().expect("invalid uri");
+        let location = positions.location("/foo/bar", uri);
+        assert_eq!(
+            location.map(|location| location.range),
+            Some(Range {
+                start: Position {
+                    line: 1,
+                    character: 7
+                },
+                end: Position {
+                    line: 1,
+                    character: 10
+                },
+            })
+        );
+        Ok(())
+    }
+}
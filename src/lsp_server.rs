@@ -0,0 +1,315 @@
+//! A language server answering pointer-aware requests about the YAML and JSON documents
+//! a client has open, built on [lsp-server](https://docs.rs/lsp-server)
+//!
+//! The `lincolns-lsp` binary serves it over stdio. Install it with
+//! `cargo install lincolns --features lsp-server`. It answers
+//!
+//! * `textDocument/hover` with the JSON Pointer path of the node under the cursor
+//! * `textDocument/documentSymbol` with the document's [`outline`](../struct.Positions.html#method.outline)
+//! * [`lincolns/gotoPointer`](enum.GotoPointer.html) with the location of a JSON Pointer path
+use crate::{ColumnUnit, Options, Positions};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+        Notification as LspNotification,
+    },
+    request::{DocumentSymbolRequest, HoverRequest, Request as LspRequest},
+    DocumentSymbol, DocumentSymbolResponse, Hover, HoverContents, HoverProviderCapability,
+    Location, MarkupContent, MarkupKind, OneOf, Range, ServerCapabilities, TextDocumentIdentifier,
+    TextDocumentSyncCapability, TextDocumentSyncKind, Uri,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, error::Error};
+
+/// A custom request for the location of the value a JSON Pointer path refers to,
+/// answered with `null` when the document has no such path
+pub enum GotoPointer {}
+
+impl LspRequest for GotoPointer {
+    type Params = GotoPointerParams;
+    type Result = Option<Location>;
+    const METHOD: &'static str = "lincolns/gotoPointer";
+}
+
+/// Parameters of a [`GotoPointer`](enum.GotoPointer.html) request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GotoPointerParams {
+    pub text_document: TextDocumentIdentifier,
+    /// The JSON Pointer path to locate
+    pub pointer: String,
+}
+
+/// Lookup tables of the documents a client has open
+///
+/// Documents are indexed as they're opened and changed, with columns measured in UTF-16
+/// code units as LSP clients expect. Documents which fail to load answer every request
+/// with `null` until they're changed into something that loads
+#[derive(Default)]
+pub struct Server {
+    documents: HashMap<Uri, Positions>,
+}
+
+impl Server {
+    /// Creates a server with no open documents
+    pub fn new() -> Self {
+        Server::default()
+    }
+
+    /// The capabilities the server advertises when initialized
+    pub fn capabilities() -> ServerCapabilities {
+        ServerCapabilities {
+            text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+            hover_provider: Some(HoverProviderCapability::Simple(true)),
+            document_symbol_provider: Some(OneOf::Left(true)),
+            ..ServerCapabilities::default()
+        }
+    }
+
+    /// Initializes a connection and serves requests until the client shuts it down
+    pub fn run(
+        mut self,
+        connection: &Connection,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        connection.initialize(serde_json::to_value(Server::capabilities())?)?;
+        for message in &connection.receiver {
+            match message {
+                Message::Request(request) => {
+                    if connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    connection
+                        .sender
+                        .send(Message::Response(self.request(request)))?;
+                }
+                Message::Notification(notification) => self.notification(notification),
+                Message::Response(_) => (),
+            }
+        }
+        Ok(())
+    }
+
+    /// Answers a request, with an error for methods the server doesn't support
+    pub fn request(
+        &mut self,
+        request: Request,
+    ) -> Response {
+        let id = request.id.clone();
+        let answer = match request.method.as_str() {
+            HoverRequest::METHOD => params::<HoverRequest>(request).map(|params| {
+                let params = params.text_document_position_params;
+                let hover = self.hover(&params.text_document.uri, params.position);
+                Response::new_ok(id.clone(), hover)
+            }),
+            DocumentSymbolRequest::METHOD => {
+                params::<DocumentSymbolRequest>(request).map(|params| {
+                    let symbols = self
+                        .documents
+                        .get(&params.text_document.uri)
+                        .map(|positions| {
+                            DocumentSymbolResponse::Nested(
+                                positions
+                                    .outline()
+                                    .iter()
+                                    .map(DocumentSymbol::from)
+                                    .collect(),
+                            )
+                        });
+                    Response::new_ok(id.clone(), symbols)
+                })
+            }
+            GotoPointer::METHOD => params::<GotoPointer>(request).map(|params| {
+                let GotoPointerParams {
+                    text_document,
+                    pointer,
+                } = params;
+                let location = self
+                    .documents
+                    .get(&text_document.uri)
+                    .and_then(|positions| positions.location(pointer, text_document.uri.clone()));
+                Response::new_ok(id.clone(), location)
+            }),
+            _ => Ok(Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("unsupported method {}", request.method),
+            )),
+        };
+        answer.unwrap_or_else(|err| Response::new_err(id, ErrorCode::InvalidParams as i32, err))
+    }
+
+    /// Tracks a document being opened, changed, or closed, ignoring other notifications
+    pub fn notification(
+        &mut self,
+        notification: Notification,
+    ) {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                if let Some(params) = notified::<DidOpenTextDocument>(notification) {
+                    self.load(params.text_document.uri, &params.text_document.text);
+                }
+            }
+            DidChangeTextDocument::METHOD => {
+                // documents sync in full, so the last change holds the whole text
+                if let Some(mut params) = notified::<DidChangeTextDocument>(notification) {
+                    if let Some(change) = params.content_changes.pop() {
+                        self.load(params.text_document.uri, &change.text);
+                    }
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                if let Some(params) = notified::<DidCloseTextDocument>(notification) {
+                    self.documents.remove(&params.text_document.uri);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn load(
+        &mut self,
+        uri: Uri,
+        text: &str,
+    ) {
+        let options = Options::default().column_unit(ColumnUnit::Utf16);
+        match crate::from_str_with(text, options) {
+            Ok(positions) => {
+                self.documents.insert(uri, positions);
+            }
+            Err(err) => {
+                log::debug!("failed to load {}: {}", uri.as_str(), err);
+                self.documents.remove(&uri);
+            }
+        }
+    }
+
+    fn hover(
+        &self,
+        uri: &Uri,
+        position: lsp_types::Position,
+    ) -> Option<Hover> {
+        let positions = self.documents.get(uri)?;
+        let (ptr, start) = positions
+            .enclosing(position.line as usize + 1, position.character as usize)
+            .filter(|(ptr, _)| !ptr.is_empty())?;
+        let end = positions.span(ptr)?.end;
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: format!("`{}`", ptr),
+            }),
+            range: Some(Range {
+                start: start.into(),
+                end: (&end).into(),
+            }),
+        })
+    }
+}
+
+/// Extracts a request's params, describing why when they're invalid
+fn params<R>(request: Request) -> Result<R::Params, String>
+where
+    R: LspRequest,
+{
+    request
+        .extract(R::METHOD)
+        .map(|(_, params)| params)
+        .map_err(|err| err.to_string())
+}
+
+/// Extracts a notification's params, logging them when they're invalid
+fn notified<N>(notification: Notification) -> Option<N::Params>
+where
+    N: LspNotification,
+{
+    notification
+        .extract(N::METHOD)
+        .map_err(|err| log::debug!("{}", err))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use std::thread;
+
+    #[test]
+    fn answers_pointer_requests() {
+        let (server, client) = Connection::memory();
+        let serving = thread::spawn(move || Server::new().run(&server).is_ok());
+        let request = |id: i32, method: &str, params: Value| -> Value {
+            client
+                .sender
+                .send(Request::new(id.into(), method.to_owned(), params).into())
+                .expect("server stopped");
+            match client.receiver.recv().expect("server stopped") {
+                Message::Response(response) => response.result.unwrap_or(Value::Null),
+                message => panic!("unexpected {:?}", message),
+            }
+        };
+        let notify = |method: &str, params: Value| {
+            client
+                .sender
+                .send(Notification::new(method.to_owned(), params).into())
+                .expect("server stopped");
+        };
+        let capabilities = request(1, "initialize", json!({ "capabilities": {} }));
+        assert_eq!(capabilities["capabilities"]["hoverProvider"], json!(true));
+        notify("initialized", json!({}));
+        let document = json!({ "uri": "file:///app.yml" });
+        notify(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": {
+                    "uri": "file:///app.yml",
+                    "languageId": "yaml",
+                    "version": 1,
+                    "text": "name: café\nspec:\n  image: nginx\n"
+                }
+            }),
+        );
+        let hover = request(
+            2,
+            "textDocument/hover",
+            json!({ "textDocument": document, "position": { "line": 2, "character": 10 } }),
+        );
+        assert_eq!(hover["contents"]["value"], json!("`/spec/image`"));
+        assert_eq!(
+            hover["range"],
+            json!({ "start": { "line": 2, "character": 2 }, "end": { "line": 2, "character": 14 } })
+        );
+        let symbols = request(
+            3,
+            "textDocument/documentSymbol",
+            json!({ "textDocument": document }),
+        );
+        assert_eq!(symbols[1]["children"][0]["name"], json!("image"));
+        let location = request(
+            4,
+            "lincolns/gotoPointer",
+            json!({ "textDocument": document, "pointer": "/name" }),
+        );
+        assert_eq!(
+            location["range"],
+            json!({ "start": { "line": 0, "character": 6 }, "end": { "line": 0, "character": 10 } })
+        );
+        notify(
+            "textDocument/didChange",
+            json!({
+                "textDocument": { "uri": "file:///app.yml", "version": 2 },
+                "contentChanges": [{ "text": "name: [" }]
+            }),
+        );
+        let location = request(
+            5,
+            "lincolns/gotoPointer",
+            json!({ "textDocument": document, "pointer": "/name" }),
+        );
+        assert_eq!(location, Value::Null);
+        request(6, "shutdown", Value::Null);
+        notify("exit", Value::Null);
+        assert!(serving.join().expect("server panicked"));
+    }
+}
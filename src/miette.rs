@@ -0,0 +1,112 @@
+use crate::{Positions, Span};
+use miette::{Diagnostic, LabeledSpan, NamedSource, SourceCode, SourceSpan};
+use std::{error::Error as StdError, fmt};
+
+impl From<&Span> for SourceSpan {
+    fn from(span: &Span) -> Self {
+        SourceSpan::new(span.bytes.start.into(), span.bytes.len())
+    }
+}
+
+/// A [miette](https://docs.rs/miette) diagnostic reported at the value a JSON Pointer path refers to
+#[derive(Debug)]
+pub struct PointerDiagnostic {
+    /// The JSON Pointer path the diagnostic is reported at
+    pub pointer: String,
+    /// A description of the problem
+    pub message: String,
+    src: NamedSource<String>,
+    span: SourceSpan,
+}
+
+impl fmt::Display for PointerDiagnostic {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl StdError for PointerDiagnostic {}
+
+impl Diagnostic for PointerDiagnostic {
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        Some(&self.src)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        Some(Box::new(std::iter::once(
+            LabeledSpan::new_primary_with_span(Some(self.pointer.clone()), self.span),
+        )))
+    }
+}
+
+impl Positions {
+    /// Returns the retained source as a named miette source
+    ///
+    /// This requires the source to be retained with [`Options::keep_source`](struct.Options.html#method.keep_source)
+    pub fn named_source<N>(
+        &self,
+        name: N,
+    ) -> Option<NamedSource<String>>
+    where
+        N: AsRef<str>,
+    {
+        self.source()
+            .map(|source| NamedSource::new(name, source.to_owned()))
+    }
+
+    /// Builds a [`PointerDiagnostic`](struct.PointerDiagnostic.html) labeling the value
+    /// a JSON Pointer path refers to within the retained source
+    pub fn miette_diagnostic<P, N, M>(
+        &self,
+        ptr: P,
+        name: N,
+        message: M,
+    ) -> Option<PointerDiagnostic>
+    where
+        P: AsRef<str>,
+        N: AsRef<str>,
+        M: Into<String>,
+    {
+        let ptr = ptr.as_ref();
+        let span = self.span(ptr)?.into();
+        Some(PointerDiagnostic {
+            pointer: ptr.to_owned(),
+            message: message.into(),
+            src: self.named_source(name)?,
+            span,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_str, from_str_with, Options, Result};
+    use miette::{Diagnostic, SourceSpan};
+
+    #[test]
+    fn diagnostic_requires_retained_source() -> Result<()> {
+        let positions = from_str("foo: bar")?;
+        assert!(positions
+            .miette_diagnostic("/foo", "config.yml", "oops")
+            .is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn diagnostic_labels_value() -> Result<()> {
+        let positions = from_str_with("foo:\n  bar: baz", Options::default().keep_source(true))?;
+        let diagnostic = positions
+            .miette_diagnostic("/foo/bar", "config.yml", "unexpected value")
+            .expect("missing diagnostic");
+        assert_eq!(diagnostic.to_string(), "unexpected value");
+        let label = diagnostic.labels().and_then(|mut labels| labels.next());
+        assert_eq!(
+            label.map(|label| *label.inner()),
+            Some(SourceSpan::new(12.into(), 3))
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{encoding, from_str_with, Error, Options, Positions, Result};
+use memmap2::Mmap;
+use std::{fs::File, path::Path};
+
+/// Load a lookup table of [`Position`](struct.Position.html) information from a file,
+/// mapping it into memory rather than reading it. Requires the `mmap` feature
+///
+/// UTF-8 files are indexed in place, without copying their content
+///
+/// ```rust,edition2018
+/// use lincolns::{from_mmap, Position};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let positions = from_mmap("tests/data/example.yml")?;
+/// assert_eq!(positions.get("/test/2/nested/foo"), Some(&Position { line: 7, col: 6 }));
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_mmap<P>(path: P) -> Result<Positions>
+where
+    P: AsRef<Path>,
+{
+    from_mmap_with(path, Options::default())
+}
+
+/// Load a lookup table of [`Position`](struct.Position.html) information from a file
+/// mapped into memory using the provided [`Options`](struct.Options.html)
+///
+/// UTF-16 and UTF-32 content is decoded as it is by [`from_reader_with`](fn.from_reader_with.html)
+pub fn from_mmap_with<P>(
+    path: P,
+    options: Options,
+) -> Result<Positions>
+where
+    P: AsRef<Path>,
+{
+    let file = File::open(path)?;
+    // the mapping is only read while indexing, though other processes changing the file
+    // meanwhile may still produce inconsistent positions
+    let map = unsafe { Mmap::map(&file)? };
+    if let Some(limit) = options.exceeded(map.len(), 0, 0) {
+        return Err(Error::LimitExceeded(limit));
+    }
+    let text = encoding::decode(&map, options.lossy)?;
+    from_str_with(text, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Limit;
+
+    #[test]
+    fn maps_files() -> Result<()> {
+        let positions = from_mmap("tests/data/example.json")?;
+        assert_eq!(
+            positions.len(),
+            crate::from_str(include_str!("../tests/data/example.json"))?.len()
+        );
+        assert!(matches!(
+            from_mmap_with(
+                "tests/data/example.json",
+                Options::default().max_input_size(8)
+            ),
+            Err(Error::LimitExceeded(Limit::InputSize(8)))
+        ));
+        assert!(matches!(
+            from_mmap("tests/data/missing.yml"),
+            Err(Error::Io(_))
+        ));
+        Ok(())
+    }
+}
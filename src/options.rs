@@ -0,0 +1,426 @@
+use crate::{from_str_using, from_str_with, Backend, Positions, Result, Warning};
+use std::fmt;
+use std::io::Read;
+
+/// Options controlling how content is loaded into a [`Positions`](struct.Positions.html) table
+///
+/// Options are also a builder, started by [`Positions::builder`](struct.Positions.html#method.builder),
+/// which loads content with [`load`](#method.load)
+///
+/// ```rust,edition2018
+/// use lincolns::{from_str_with, Options};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let positions = from_str_with("foo: 'bar'", Options::default().keep_source(true))?;
+/// assert_eq!(positions.text_at("/foo"), Some("'bar'"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct Options {
+    pub(crate) keep_source: bool,
+    pub(crate) one_based: bool,
+    pub(crate) column_unit: ColumnUnit,
+    pub(crate) duplicate_keys: DuplicateKeys,
+    pub(crate) merge_keys: MergeKeys,
+    pub(crate) yaml_version: YamlVersion,
+    pub(crate) case_insensitive: bool,
+    pub(crate) on_unhandled: Option<Handler>,
+    pub(crate) max_depth: Option<usize>,
+    pub(crate) max_nodes: Option<usize>,
+    pub(crate) max_input_size: Option<usize>,
+    pub(crate) lossy: bool,
+    pub(crate) templates: bool,
+    #[cfg(feature = "hash-index")]
+    pub(crate) hash_index: bool,
+}
+
+/// A resource limit set by [`Options`](struct.Options.html), reported by
+/// [`Error::LimitExceeded`](enum.Error.html#variant.LimitExceeded) once crossed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    /// The maximum nesting depth of collections, set by [`Options::max_depth`](struct.Options.html#method.max_depth)
+    Depth(usize),
+    /// The maximum number of nodes, set by [`Options::max_nodes`](struct.Options.html#method.max_nodes)
+    Nodes(usize),
+    /// The maximum size of input in bytes, set by [`Options::max_input_size`](struct.Options.html#method.max_input_size)
+    InputSize(usize),
+}
+
+/// A callback deciding whether loading continues past a [`Warning`](enum.Warning.html)
+pub(crate) struct Handler(Box<dyn FnMut(&Warning) -> bool>);
+
+impl Handler {
+    pub(crate) fn handle(
+        &mut self,
+        warning: &Warning,
+    ) -> bool {
+        (self.0)(warning)
+    }
+}
+
+impl fmt::Debug for Handler {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.write_str("Handler")
+    }
+}
+
+/// What to do when a mapping contains the same key more than once
+///
+/// Every repeated key is listed by [`Positions::duplicates`](struct.Positions.html#method.duplicates)
+/// regardless of policy
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateKeys {
+    /// Index the first occurrence of a key, ignoring later ones
+    First,
+    /// Index the last occurrence of a key, as most YAML parsers do. This is the default
+    #[default]
+    Last,
+    /// Fail to load with [`Error::DuplicateKey`](enum.Error.html#variant.DuplicateKey)
+    Error,
+}
+
+/// The unit columns are measured in
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnUnit {
+    /// UTF-8 bytes
+    Utf8,
+    /// UTF-16 code units, as used by the Language Server Protocol
+    Utf16,
+    /// Unicode scalar values (`char`s). This is the default
+    #[default]
+    Char,
+}
+
+/// How YAML merge keys, `<<: *anchor`, are indexed
+///
+/// ```rust,edition2018
+/// use lincolns::{from_str_with, MergeKeys, Options, Position};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let yaml = "defaults: &defaults\n  retries: 3\njob:\n  <<: *defaults\n";
+/// let positions = from_str_with(yaml, Options::default().merge_keys(MergeKeys::Anchor))?;
+/// assert_eq!(positions.get("/job/retries"), Some(&Position { line: 2, col: 2 }));
+/// let positions = from_str_with(yaml, Options::default().merge_keys(MergeKeys::Site))?;
+/// assert_eq!(positions.get("/job/retries"), Some(&Position { line: 4, col: 2 }));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MergeKeys {
+    /// Index `<<` as an ordinary key. This is the default
+    #[default]
+    Literal,
+    /// Expand merged entries, positioned where the anchored mapping defines them
+    Anchor,
+    /// Expand merged entries, positioned at the `<<` key that merges them
+    Site,
+}
+
+/// The YAML version whose rules decide which plain scalars are booleans, numbers, or null
+///
+/// ```rust,edition2018
+/// use lincolns::{from_str_with, NodeKind, Options, YamlVersion};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let yaml = "enabled: yes\nmode: 0o17\nport: 22:22\n";
+/// let positions = from_str_with(yaml, Options::default())?;
+/// assert_eq!(positions.kind("/enabled"), Some(NodeKind::String));
+/// assert_eq!(positions.kind("/mode"), Some(NodeKind::Integer));
+/// let positions = from_str_with(yaml, Options::default().yaml_version(YamlVersion::V1_1))?;
+/// assert_eq!(positions.kind("/enabled"), Some(NodeKind::Bool));
+/// assert_eq!(positions.kind("/mode"), Some(NodeKind::String));
+/// assert_eq!(positions.kind("/port"), Some(NodeKind::Integer));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum YamlVersion {
+    /// YAML 1.1's types, as read by PyYAML and other older parsers. `yes`, `no`, `on`, `off`,
+    /// `y`, and `n` are booleans, `017` is octal, `1_000` is an integer, and `22:22` is a
+    /// base 60 integer
+    V1_1,
+    /// YAML 1.2's core schema, where only `true` and `false` are booleans and octal
+    /// integers are written `0o17`. This is the default
+    #[default]
+    V1_2,
+}
+
+impl ColumnUnit {
+    /// Measures the width of a line prefix in this unit
+    pub(crate) fn measure(
+        self,
+        text: &str,
+    ) -> usize {
+        match self {
+            ColumnUnit::Utf8 => text.len(),
+            ColumnUnit::Utf16 => text.chars().map(char::len_utf16).sum(),
+            ColumnUnit::Char => text.chars().count(),
+        }
+    }
+}
+
+impl Options {
+    /// Retain a copy of the source text within the loaded table,
+    /// enabling lookups of the original text of each node. Defaults to `false`
+    pub fn keep_source(
+        mut self,
+        keep: bool,
+    ) -> Self {
+        self.keep_source = keep;
+        self
+    }
+
+    /// Number columns starting at 1, as most editors do, rather than 0.
+    /// Lines are always numbered starting at 1. Defaults to `false`
+    pub fn one_based(
+        mut self,
+        one_based: bool,
+    ) -> Self {
+        self.one_based = one_based;
+        self
+    }
+
+    /// Sets the unit columns are measured in. Defaults to [`ColumnUnit::Char`](enum.ColumnUnit.html#variant.Char)
+    pub fn column_unit(
+        mut self,
+        unit: ColumnUnit,
+    ) -> Self {
+        self.column_unit = unit;
+        self
+    }
+
+    /// Sets how repeated mapping keys are handled. Defaults to [`DuplicateKeys::Last`](enum.DuplicateKeys.html#variant.Last)
+    pub fn duplicate_keys(
+        mut self,
+        policy: DuplicateKeys,
+    ) -> Self {
+        self.duplicate_keys = policy;
+        self
+    }
+
+    /// Sets how merge keys are indexed. Defaults to [`MergeKeys::Literal`](enum.MergeKeys.html#variant.Literal)
+    pub fn merge_keys(
+        mut self,
+        merge_keys: MergeKeys,
+    ) -> Self {
+        self.merge_keys = merge_keys;
+        self
+    }
+
+    /// Sets the YAML version whose rules resolve the kinds and values of plain scalars.
+    /// Defaults to [`YamlVersion::V1_2`](enum.YamlVersion.html#variant.V1_2)
+    pub fn yaml_version(
+        mut self,
+        version: YamlVersion,
+    ) -> Self {
+        self.yaml_version = version;
+        self
+    }
+
+    /// Look up pointers without regard to case, so `/Foo/BAR` finds `/foo/bar`.
+    /// An exact match is always preferred. Defaults to `false`
+    pub fn case_insensitive(
+        mut self,
+        case_insensitive: bool,
+    ) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Fails to load content with collections nested more than `depth` deep,
+    /// counting a document's outermost collection as 1. Unlimited by default
+    pub fn max_depth(
+        mut self,
+        depth: usize,
+    ) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Fails to load content with more than `nodes` nodes, counting every scalar,
+    /// including mapping keys, alias, and collection. Unlimited by default
+    pub fn max_nodes(
+        mut self,
+        nodes: usize,
+    ) -> Self {
+        self.max_nodes = Some(nodes);
+        self
+    }
+
+    /// Fails to load content larger than `bytes`, which readers stop reading once exceeded.
+    /// Unlimited by default
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{from_str_with, Error, Limit, Options};
+    ///
+    /// assert!(matches!(
+    ///     from_str_with("a: [1, 2, 3]", Options::default().max_input_size(8)),
+    ///     Err(Error::LimitExceeded(Limit::InputSize(8)))
+    /// ));
+    /// ```
+    pub fn max_input_size(
+        mut self,
+        bytes: usize,
+    ) -> Self {
+        self.max_input_size = Some(bytes);
+        self
+    }
+
+    /// Replace invalid UTF-8, UTF-16, or UTF-32 read by [`from_reader_with`](fn.from_reader_with.html)
+    /// or [`from_slice_with`](fn.from_slice_with.html)
+    /// with `U+FFFD REPLACEMENT CHARACTER` rather than failing with
+    /// [`Error::Utf8`](enum.Error.html#variant.Utf8) or [`Error::Encoding`](enum.Error.html#variant.Encoding).
+    /// Byte offsets then refer to the decoded text.
+    /// Defaults to `false`
+    pub fn lossy(
+        mut self,
+        lossy: bool,
+    ) -> Self {
+        self.lossy = lossy;
+        self
+    }
+
+    /// Tolerate the `{{ }}`, `{% %}`, and `{# #}` template expressions of Helm charts and
+    /// Jinja templates, which otherwise fail to parse. Lines holding only templates, such as
+    /// `{{- if .Values.enabled }}`, are read as comments and other templates as plain scalars,
+    /// so scalars and keys containing templates have placeholder values of `_`s. Positions and
+    /// spans are those of the original text. Defaults to `false`
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{from_str_with, Options, Position};
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let yaml = "spec:\n  {{- if .Values.tls }}\n  tls: true\n  {{- end }}\n  image: {{ .Values.image }}\n";
+    /// let positions = from_str_with(yaml, Options::default().templates(true))?;
+    /// assert_eq!(positions.get("/spec/image"), Some(&Position { line: 5, col: 2 }));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn templates(
+        mut self,
+        templates: bool,
+    ) -> Self {
+        self.templates = templates;
+        self
+    }
+
+    /// Index pointers in a hash map alongside the ordered index, speeding up exact lookups
+    /// of large tables at the cost of a copy of each pointer. Ordered iteration is unaffected.
+    /// Defaults to `false`. Requires the `hash-index` feature
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{from_str_with, Options, Position};
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = from_str_with("a: 1\nb: 2", Options::default().hash_index(true))?;
+    /// assert_eq!(positions.get("/b"), Some(&Position { line: 2, col: 0 }));
+    /// assert_eq!(positions.keys().collect::<Vec<_>>(), vec!["/a", "/b"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "hash-index")]
+    pub fn hash_index(
+        mut self,
+        hash_index: bool,
+    ) -> Self {
+        self.hash_index = hash_index;
+        self
+    }
+
+    /// Loads utf8 text with these options, as [`from_str_with`](fn.from_str_with.html) does
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{Position, Positions};
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = Positions::builder()
+    ///     .one_based(true)
+    ///     .keep_source(true)
+    ///     .max_depth(8)
+    ///     .load("a:\n  b: 1")?;
+    /// assert_eq!(positions.get("/a/b"), Some(&Position { line: 2, col: 3 }));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn load<S>(
+        self,
+        s: S,
+    ) -> Result<Positions>
+    where
+        S: AsRef<str>,
+    {
+        from_str_with(s, self)
+    }
+
+    /// Loads utf8 text parsed by a [`Backend`](trait.Backend.html) with these options,
+    /// as [`from_str_using`](fn.from_str_using.html) does
+    pub fn load_using<B, S>(
+        self,
+        backend: &B,
+        s: S,
+    ) -> Result<Positions>
+    where
+        B: Backend + ?Sized,
+        S: AsRef<str>,
+    {
+        from_str_using(backend, s, self)
+    }
+
+    /// Loads content from a type which implements `Read` with these options,
+    /// as [`from_reader_with`](fn.from_reader_with.html) does
+    pub fn load_reader<R>(
+        self,
+        rdr: R,
+    ) -> Result<Positions>
+    where
+        R: Read,
+    {
+        crate::from_reader_with(rdr, self)
+    }
+
+    /// The first limit exceeded by content of a given size, depth, and number of nodes
+    pub(crate) fn exceeded(
+        &self,
+        size: usize,
+        depth: usize,
+        nodes: usize,
+    ) -> Option<Limit> {
+        let over = |limit: Option<usize>, value: usize| limit.filter(|limit| value > *limit);
+        over(self.max_input_size, size)
+            .map(Limit::InputSize)
+            .or_else(|| over(self.max_depth, depth).map(Limit::Depth))
+            .or_else(|| over(self.max_nodes, nodes).map(Limit::Nodes))
+    }
+
+    /// Calls `handler` with each [`Warning`](enum.Warning.html) of content skipped while indexing,
+    /// which decides whether loading continues by returning `true`, or fails with
+    /// [`Error::Unhandled`](enum.Error.html#variant.Unhandled) by returning `false`.
+    /// By default warnings are only listed by [`Positions::warnings`](struct.Positions.html#method.warnings)
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{from_str_with, Error, Options};
+    ///
+    /// let strict = Options::default().on_unhandled(|warning| {
+    ///     eprintln!("skipped content at {}", warning.position());
+    ///     false
+    /// });
+    /// assert!(matches!(
+    ///     from_str_with("a: &a 1\n*a : 2\n", strict),
+    ///     Err(Error::Unhandled(_))
+    /// ));
+    /// ```
+    pub fn on_unhandled<F>(
+        mut self,
+        handler: F,
+    ) -> Self
+    where
+        F: FnMut(&Warning) -> bool + 'static,
+    {
+        self.on_unhandled = Some(Handler(Box::new(handler)));
+        self
+    }
+}
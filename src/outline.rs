@@ -0,0 +1,46 @@
+use crate::{tree::Node, NodeKind, Position, Positions, Span};
+
+/// An entry of a document's outline, returned by [`Positions::outline`](struct.Positions.html#method.outline)
+///
+/// With the `lsp` feature, symbols convert into LSP `DocumentSymbol`s
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    /// The mapping key or sequence index naming the symbol
+    pub name: String,
+    pub kind: NodeKind,
+    /// Where the symbol's entry starts, which is at its key for mapping entries
+    pub start: Position,
+    /// The symbol's value
+    pub span: Span,
+    /// Symbols nested within this one, in document order
+    pub children: Vec<Symbol>,
+}
+
+impl From<Node> for Symbol {
+    fn from(node: Node) -> Self {
+        Symbol {
+            name: node.key().into_owned(),
+            kind: node.kind,
+            start: node.position.min(node.span.start),
+            span: node.span,
+            children: node.children.into_iter().map(Symbol::from).collect(),
+        }
+    }
+}
+
+impl Positions {
+    /// Returns a hierarchical outline of the document, naming each entry by its key or index
+    ///
+    /// ```rust,edition2018
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("jobs:\n  build:\n    steps: []\n")?;
+    /// let outline = positions.outline();
+    /// assert_eq!(outline[0].name, "jobs");
+    /// assert_eq!(outline[0].children[0].children[0].name, "steps");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn outline(&self) -> Vec<Symbol> {
+        self.tree().into_iter().map(Symbol::from).collect()
+    }
+}
@@ -0,0 +1,251 @@
+use crate::{
+    backend::{Backend, Event, Yaml},
+    finish, from_str_with,
+    source::{self, Source},
+    Options, Positions, Result,
+};
+use rayon::prelude::*;
+use std::ops::Range;
+
+/// The fewest items of a root sequence worth indexing as a shard of their own
+const MIN_SHARD_ITEMS: usize = 256;
+
+/// Parses a run of a root sequence's items as though they were the whole of it,
+/// reporting the offsets they'd have in the text they were cut from
+struct Shard<'a> {
+    items: &'a [Range<usize>],
+    /// Offsets of the root sequence's brackets
+    open: usize,
+    close: usize,
+}
+
+impl Backend for Shard<'_> {
+    fn parse(
+        &self,
+        text: &str,
+        sink: &mut dyn FnMut(Event, usize),
+    ) -> Result<()> {
+        let start = self.items.first().map_or(self.open + 1, |item| item.start);
+        let end = self.items.last().map_or(start, |item| item.end);
+        let mut wrapped = String::with_capacity(end - start + 2);
+        wrapped.push('[');
+        wrapped.push_str(&text[start..end]);
+        wrapped.push(']');
+        let close = wrapped.len() - 1;
+        Yaml.parse(&wrapped, &mut |event, offset| {
+            let offset = match offset {
+                0 => self.open,
+                offset if offset >= close => self.close + offset - close,
+                offset => start + offset - 1,
+            };
+            sink(event, offset)
+        })
+    }
+}
+
+/// Finds the offsets of the brackets and the byte ranges of the items of a flow sequence
+/// making up the whole of a text, such as a JSON array
+///
+/// Texts with anchors, aliases, comments, or single quoted scalars, which nodes
+/// may share or which may hide brackets, aren't split
+fn split(text: &str) -> Option<(usize, Vec<Range<usize>>, usize)> {
+    let bytes = text.as_bytes();
+    let open = bytes.iter().position(|byte| !byte.is_ascii_whitespace())?;
+    if bytes[open] != b'[' {
+        return None;
+    }
+    let (mut items, mut depth, mut start, mut string) = (Vec::new(), 0, open + 1, false);
+    let mut idx = start;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'\\' if string => idx += 1,
+            b'"' => string = !string,
+            _ if string => (),
+            b'[' | b'{' => depth += 1,
+            b']' | b'}' if depth > 0 => depth -= 1,
+            byte @ b',' | byte @ b']' if depth == 0 => {
+                let item = &text[start..idx];
+                let lead = item.len() - item.trim_start().len();
+                if item.trim().is_empty() {
+                    return None;
+                }
+                items.push(start + lead..start + item.trim_end().len());
+                if byte == b']' {
+                    return Some((open, items, idx)).filter(|_| text[idx + 1..].trim().is_empty());
+                }
+                start = idx + 1;
+            }
+            b'}' | b'&' | b'*' | b'#' | b'\'' => return None,
+            _ => (),
+        }
+        idx += 1;
+    }
+    None
+}
+
+/// Load a lookup table of [`Position`](struct.Position.html) information from utf8 text
+/// using the provided [`Options`](struct.Options.html), indexing the items of a root sequence
+/// in parallel
+///
+/// Texts which are one large flow sequence, such as a JSON array of records, are split between
+/// the items of their root and the runs of items indexed as shards on rayon's thread pool, then
+/// merged into one table, the same as [`from_str_with`](fn.from_str_with.html) would load.
+/// Other texts, those too small to be worth splitting, and those loaded with templates or
+/// limits are loaded as they would be by `from_str_with`
+///
+/// ```rust,edition2018
+/// use lincolns::{from_str_parallel, Options, Position};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let json = format!(
+///     "[\n{}\n]",
+///     (0..1000).map(|id| format!("  {{\"id\": {}}}", id)).collect::<Vec<_>>().join(",\n")
+/// );
+/// let positions = from_str_parallel(&json, Options::default())?;
+/// assert_eq!(positions.get("/999/id"), Some(&Position { line: 1001, col: 3 }));
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_str_parallel<S>(
+    s: S,
+    options: Options,
+) -> Result<Positions>
+where
+    S: AsRef<str>,
+{
+    let text = s.as_ref();
+    let _load = phase!("load_parallel", bytes = text.len());
+    let bom = if text.starts_with(source::BOM) {
+        source::BOM.len_utf8()
+    } else {
+        0
+    };
+    let body = &text[bom..];
+    let limited = options.max_depth.is_some()
+        || options.max_nodes.is_some()
+        || options.max_input_size.is_some();
+    let (open, items, close) = match split(body) {
+        Some(split) if !limited && !options.templates => split,
+        _ => return from_str_with(text, options),
+    };
+    let per_shard = MIN_SHARD_ITEMS.max(items.len() / (rayon::current_num_threads() * 4) + 1);
+    if items.len() <= per_shard {
+        return from_str_with(text, options);
+    }
+    let source = Source::new(text, &options);
+    let shards = items
+        .par_chunks(per_shard)
+        .enumerate()
+        .map(|(idx, items)| {
+            let shard = Shard { items, open, close };
+            let mut positions = Positions::default();
+            positions.first_item(idx * per_shard);
+            let parsing = phase!("parse", shard = idx);
+            let mut nodes = 0;
+            shard
+                .parse(body, &mut |event, offset| {
+                    if let Event::Scalar { .. }
+                    | Event::Alias { .. }
+                    | Event::SequenceStart { .. }
+                    | Event::MappingStart { .. } = event
+                    {
+                        nodes += 1;
+                    }
+                    positions.push(event, offset + bom)
+                })
+                .ok()?;
+            parsing.finish(nodes);
+            positions.collect_nodes(&source);
+            // scalars with quotes of their own could have been split apart
+            Some(positions).filter(|positions| positions.children_count("") == Some(items.len()))
+        })
+        .collect::<Option<Vec<_>>>();
+    let mut positions = match shards {
+        // shards are joined in pairs, in order, pairs of which are joined in parallel
+        Some(shards) => shards
+            .into_par_iter()
+            .reduce_with(|mut joined, shard| {
+                joined.absorb(shard);
+                joined
+            })
+            .unwrap_or_default(),
+        // errors are reported as they would be when loading the whole text
+        None => return from_str_with(text, options),
+    };
+    if bom > 0 {
+        positions.mark_bom();
+    }
+    positions.count_lines(text);
+    let (positions, errors) = finish(positions, options, source, text, Vec::new());
+    match errors.into_iter().next() {
+        Some(err) => Err(err),
+        None => Ok(positions),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::from_str;
+
+    fn records(count: usize) -> String {
+        let records = (0..count)
+            .map(|id| {
+                format!(
+                    "  {{\"id\": {}, \"name\": \"ré[{}], \\\"x\\\"\", \"tags\": [\"a\", {{\"b\": null}}]}}",
+                    id, id
+                )
+            })
+            .collect::<Vec<_>>();
+        format!("\u{feff}\n[\n{}, {{}}, [], \"end\"]\n", records.join(",\n"))
+    }
+
+    #[test]
+    fn splits_root_sequences() {
+        assert_eq!(
+            split(" [1, {\"a\": [2, 3]}, \"]\"]\n"),
+            Some((1, vec![2..3, 5..18, 20..23], 23))
+        );
+        assert_eq!(split("[1, 2,]"), None);
+        assert_eq!(split("[1, *a]"), None);
+        assert_eq!(split("[1] # done"), None);
+        assert_eq!(split("{\"a\": 1}"), None);
+    }
+
+    #[test]
+    fn indexes_shards_as_a_whole() -> Result<()> {
+        let json = records(1500);
+        let parallel = from_str_parallel(&json, Options::default())?;
+        let sequential = from_str(&json)?;
+        assert_eq!(parallel.len(), sequential.len());
+        for (ptr, position) in sequential.iter() {
+            assert_eq!(parallel.get(ptr), Some(position), "{}", ptr);
+            assert_eq!(parallel.span(ptr), sequential.span(ptr), "{}", ptr);
+            assert_eq!(parallel.kind(ptr), sequential.kind(ptr), "{}", ptr);
+            assert_eq!(
+                parallel.get_value(ptr),
+                sequential.get_value(ptr),
+                "{}",
+                ptr
+            );
+        }
+        for ptr in &["", "/-", "/1499/tags/-"] {
+            assert_eq!(parallel.get(ptr), sequential.get(ptr), "{}", ptr);
+            assert_eq!(parallel.span(ptr), sequential.span(ptr), "{}", ptr);
+        }
+        assert_eq!(parallel.documents(), sequential.documents());
+        assert_eq!(parallel.stats(), sequential.stats());
+        assert_eq!(parallel.has_bom(), sequential.has_bom());
+        assert_eq!(parallel.content_hash(), sequential.content_hash());
+        Ok(())
+    }
+
+    #[test]
+    fn reports_errors_as_a_whole() {
+        let json = records(1500).replacen("\"id\": 900,", "\"id\": 900", 1);
+        let parallel =
+            from_str_parallel(&json, Options::default()).expect_err("loaded invalid json");
+        let sequential = from_str(&json).expect_err("loaded invalid json");
+        assert_eq!(parallel.to_string(), sequential.to_string());
+    }
+}
@@ -0,0 +1,504 @@
+use crate::{
+    diff::changes, edit::double_quoted, path, Error, NodeKind, Pointer, Position, Positions,
+    Result, Value,
+};
+use std::{cmp::Reverse, fmt};
+use yaml_rust::{Yaml, YamlLoader};
+
+/// A [JSON Patch](https://tools.ietf.org/html/rfc6902) operation located within the document it patches
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatchOperation {
+    /// The operation, such as `add` or `remove`
+    pub op: String,
+    /// The JSON Pointer path the operation targets
+    pub path: String,
+    /// The JSON Pointer path `move` and `copy` operations read from
+    pub from: Option<String>,
+    /// The position of `path`, or for an `add` of a new member, of its parent
+    pub position: Option<Position>,
+    /// The position of `from`
+    pub from_position: Option<Position>,
+    /// The first pointer the operation requires which the document lacks
+    pub missing: Option<String>,
+}
+
+impl PatchOperation {
+    /// Returns true when the operation can be applied to the document
+    pub fn applies(&self) -> bool {
+        self.missing.is_none()
+    }
+}
+
+/// What applying a [JSON Merge Patch](https://tools.ietf.org/html/rfc7396) would do to a pointer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeOp {
+    Add,
+    Replace,
+    Remove,
+}
+
+/// A change a [JSON Merge Patch](https://tools.ietf.org/html/rfc7396) would make, located within
+/// the document it patches
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeChange {
+    pub op: MergeOp,
+    /// The JSON Pointer path which would be changed
+    pub pointer: String,
+    /// The position of the value which would be replaced or removed, or for additions,
+    /// of the mapping which would gain a member
+    pub position: Option<Position>,
+}
+
+/// A [JSON Patch](https://tools.ietf.org/html/rfc6902) operation turning one revision
+/// of a document into another, returned by [`diff_patch`](fn.diff_patch.html)
+///
+/// Operations display as their JSON objects
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffOperation {
+    /// The operation, one of `add`, `remove`, or `replace`
+    pub op: String,
+    /// The JSON Pointer path the operation targets
+    pub path: String,
+    /// The JSON text of the value `add` and `replace` operations write
+    pub value: Option<String>,
+    /// The position of the value in the old document, for `remove` and `replace` operations
+    pub old: Option<Position>,
+    /// The position of the value in the new document, for `add` and `replace` operations
+    pub new: Option<Position>,
+}
+
+impl fmt::Display for DiffOperation {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(
+            f,
+            "{{\"op\":\"{}\",\"path\":{}",
+            self.op,
+            double_quoted(&self.path)
+        )?;
+        if let Some(value) = &self.value {
+            write!(f, ",\"value\":{}", value)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+/// Compares two revisions of a document, returning the [JSON Patch](https://tools.ietf.org/html/rfc6902)
+/// operations turning the old into the new, each located within the documents it reads and writes
+///
+/// Operations cover the changes [`diff`](fn.diff.html) lists, ordered so they apply in turn:
+/// removals last in the old document first, then replacements, then additions
+///
+/// ```rust,edition2018
+/// use lincolns::{diff_patch, Position};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let operations = diff_patch("replicas: 2\ndebug: true\n", "replicas: 5\nports: [80]\n")?;
+/// assert_eq!(
+///     operations.iter().map(ToString::to_string).collect::<Vec<_>>(),
+///     vec![
+///         r#"{"op":"remove","path":"/debug"}"#,
+///         r#"{"op":"replace","path":"/replicas","value":5}"#,
+///         r#"{"op":"add","path":"/ports","value":[80]}"#,
+///     ]
+/// );
+/// assert_eq!(operations[1].old, Some(Position { line: 1, col: 0 }));
+/// # Ok(())
+/// # }
+/// ```
+pub fn diff_patch<O, N>(
+    old: O,
+    new: N,
+) -> Result<Vec<DiffOperation>>
+where
+    O: AsRef<str>,
+    N: AsRef<str>,
+{
+    let (old, new) = (crate::from_str(old)?, crate::from_str(new)?);
+    let mut operations = changes(&old, &new)
+        .into_iter()
+        .map(|change| {
+            let op = match (&change.old, &change.new) {
+                (Some(_), Some(_)) => "replace",
+                (Some(_), None) => "remove",
+                _ => "add",
+            };
+            DiffOperation {
+                op: op.into(),
+                path: change.pointer.clone(),
+                value: change.new.as_ref().and_then(|_| {
+                    let mut json = String::new();
+                    write_json(&new.annotated(&change.pointer)?.value, &mut json);
+                    Some(json)
+                }),
+                old: change.old.map(|revision| revision.position),
+                new: change.new.map(|revision| revision.position),
+            }
+        })
+        .collect::<Vec<_>>();
+    // sequences only lose and gain items from their ends, so removing later items first
+    // and adding earlier items first keeps each index valid when it's applied
+    operations.sort_by_key(|operation| match operation.op.as_str() {
+        "remove" => (0, operation.old.map(Reverse), None),
+        "replace" => (1, None, None),
+        _ => (2, None, operation.new),
+    });
+    Ok(operations)
+}
+
+/// Writes a value as JSON. Aliases and floats JSON can't represent are written as `null`
+fn write_json(
+    value: &Value,
+    json: &mut String,
+) {
+    match value {
+        Value::Null | Value::Alias => json.push_str("null"),
+        Value::Bool(value) => json.push_str(&value.to_string()),
+        Value::Integer(value) => json.push_str(&value.to_string()),
+        Value::Float(value) if value.is_finite() => json.push_str(&value.to_string()),
+        Value::Float(_) => json.push_str("null"),
+        Value::String(value) => json.push_str(&double_quoted(value)),
+        Value::Sequence(items) => {
+            json.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    json.push(',');
+                }
+                write_json(&item.value, json);
+            }
+            json.push(']');
+        }
+        Value::Mapping(entries) => {
+            json.push('{');
+            for (index, (key, entry)) in entries.iter().enumerate() {
+                if index > 0 {
+                    json.push(',');
+                }
+                json.push_str(&double_quoted(key));
+                json.push(':');
+                write_json(&entry.value, json);
+            }
+            json.push('}');
+        }
+    }
+}
+
+impl Positions {
+    /// Locates each operation of a [JSON Patch](https://tools.ietf.org/html/rfc6902) document,
+    /// given as JSON or YAML text, within this table
+    ///
+    /// Operations are each resolved against the document as it is, rather than as
+    /// earlier operations would leave it
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{from_str, Position};
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = from_str("spec:\n  replicas: 1\n")?;
+    /// let operations = positions.locate_patch(
+    ///     r#"[
+    ///       {"op": "replace", "path": "/spec/replicas", "value": 3},
+    ///       {"op": "remove", "path": "/spec/image"}
+    ///     ]"#,
+    /// )?;
+    /// assert_eq!(operations[0].position, Some(Position { line: 2, col: 2 }));
+    /// assert_eq!(operations[1].missing.as_deref(), Some("/spec/image"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn locate_patch<P>(
+        &self,
+        patch: P,
+    ) -> Result<Vec<PatchOperation>>
+    where
+        P: AsRef<str>,
+    {
+        let docs = YamlLoader::load_from_str(patch.as_ref())?;
+        let operations = match docs.first() {
+            Some(Yaml::Array(operations)) => operations,
+            _ => {
+                return Err(Error::InvalidPatch(
+                    "expected a sequence of operations".into(),
+                ))
+            }
+        };
+        operations
+            .iter()
+            .enumerate()
+            .map(|(index, operation)| self.locate_operation(index, operation))
+            .collect()
+    }
+
+    /// Lists the changes applying a [JSON Merge Patch](https://tools.ietf.org/html/rfc7396),
+    /// given as JSON or YAML text, would make to the document of this table
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{from_str, MergeOp, Position};
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = from_str("server:\n  port: 80\n  debug: true\n")?;
+    /// let changes = positions.merge_patch(r#"{"server": {"port": 8080, "debug": null}}"#)?;
+    /// assert_eq!(changes[0].op, MergeOp::Replace);
+    /// assert_eq!(changes[0].pointer, "/server/port");
+    /// assert_eq!(changes[0].position, Some(Position { line: 2, col: 2 }));
+    /// assert_eq!(changes[1].op, MergeOp::Remove);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn merge_patch<P>(
+        &self,
+        patch: P,
+    ) -> Result<Vec<MergeChange>>
+    where
+        P: AsRef<str>,
+    {
+        let patch = YamlLoader::load_from_str(patch.as_ref())?
+            .into_iter()
+            .next()
+            .unwrap_or(Yaml::Null);
+        let mut changes = Vec::new();
+        self.merge_changes(String::new(), &patch, &mut changes);
+        Ok(changes)
+    }
+
+    fn merge_changes(
+        &self,
+        pointer: String,
+        patch: &Yaml,
+        changes: &mut Vec<MergeChange>,
+    ) {
+        let entries = match patch {
+            Yaml::Hash(entries)
+                if pointer.is_empty() || self.kind(&pointer) == Some(NodeKind::Mapping) =>
+            {
+                entries
+            }
+            // anything but a mapping merged into a mapping replaces the value outright
+            _ => {
+                changes.push(MergeChange {
+                    op: MergeOp::Replace,
+                    position: self.locate_pointer(&pointer).map(|(position, _)| position),
+                    pointer,
+                });
+                return;
+            }
+        };
+        for (key, value) in entries {
+            let key = match key {
+                Yaml::String(key) | Yaml::Real(key) => key.clone(),
+                Yaml::Integer(key) => key.to_string(),
+                Yaml::Boolean(key) => key.to_string(),
+                _ => continue,
+            };
+            let child = format!("{}/{}", pointer, path::escape(&key));
+            let position = self.get(&child).cloned();
+            match (value, position) {
+                (Yaml::Null, None) => (),
+                (Yaml::Null, position) => changes.push(MergeChange {
+                    op: MergeOp::Remove,
+                    pointer: child,
+                    position,
+                }),
+                (_, Some(_)) => self.merge_changes(child, value, changes),
+                (_, None) => changes.push(MergeChange {
+                    op: MergeOp::Add,
+                    pointer: child,
+                    position: self.locate_pointer(&pointer).map(|(position, _)| position),
+                }),
+            }
+        }
+    }
+
+    fn locate_operation(
+        &self,
+        index: usize,
+        operation: &Yaml,
+    ) -> Result<PatchOperation> {
+        let field = |name: &str| operation[name].as_str().map(str::to_owned);
+        let invalid = |reason: &str| Error::InvalidPatch(format!("operation {} {}", index, reason));
+        let op = field("op").ok_or_else(|| invalid("has no op"))?;
+        let path = field("path").ok_or_else(|| invalid("has no path"))?;
+        let from = match op.as_str() {
+            "move" | "copy" => Some(field("from").ok_or_else(|| invalid("has no from"))?),
+            "add" | "remove" | "replace" | "test" => None,
+            other => return Err(invalid(&format!("has an unknown op {}", other))),
+        };
+        for pointer in std::iter::once(&path).chain(&from) {
+            Pointer::parse(pointer)?;
+        }
+        let target = path.as_str();
+        let locate = |ptr: &str| self.locate_pointer(ptr).map(|(position, _)| position);
+        let mut missing = None;
+        let from_position = from.as_ref().and_then(|from| {
+            let position = locate(from);
+            if position.is_none() {
+                missing = Some(from.clone());
+            }
+            position
+        });
+        let position = match locate(target) {
+            Some(position) => Some(position),
+            // additions may create the final token of their path, so long as its parent exists
+            None if matches!(op.as_str(), "add" | "move" | "copy") => {
+                let parent = target.rsplit_once('/').map_or("", |(parent, _)| parent);
+                let position = locate(parent);
+                if position.is_none() {
+                    missing = missing.or_else(|| Some(parent.to_owned()));
+                }
+                position
+            }
+            None => {
+                missing = missing.or_else(|| Some(target.to_owned()));
+                None
+            }
+        };
+        Ok(PatchOperation {
+            op,
+            path,
+            from,
+            position,
+            from_position,
+            missing,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{diff_patch, from_str, Error, MergeOp, Position, Result};
+
+    #[test]
+    fn locates_operations() -> Result<()> {
+        let positions = from_str("a:\n  b: 1\n  c/d: [x]\n")?;
+        let operations = positions.locate_patch(
+            "- {op: add, path: /a/e, value: 2}\n\
+             - {op: add, path: /z/e, value: 2}\n\
+             - {op: move, from: /a/c~1d/0, path: /a/b}\n\
+             - {op: copy, from: /a/f, path: /a/g}\n\
+             - {op: test, path: '', value: {}}\n",
+        )?;
+        let located = operations
+            .iter()
+            .map(|op| (op.position, op.from_position, op.missing.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            located,
+            vec![
+                (Some(Position { line: 1, col: 0 }), None, None),
+                (None, None, Some("/z")),
+                (
+                    Some(Position { line: 2, col: 2 }),
+                    Some(Position { line: 3, col: 8 }),
+                    None
+                ),
+                (Some(Position { line: 1, col: 0 }), None, Some("/a/f")),
+                (Some(Position { line: 1, col: 0 }), None, None),
+            ]
+        );
+        assert!(operations[0].applies());
+        Ok(())
+    }
+
+    #[test]
+    fn lists_merge_changes() -> Result<()> {
+        let positions = from_str("a:\n  b: 1\n  c: [x]\nd: 2\n")?;
+        let changes = positions
+            .merge_patch("a: {b: {e: 1}, c: null, f: 3}\nd: {g: 4}\nh: null\n")?
+            .into_iter()
+            .map(|change| (change.op, change.pointer, change.position))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    MergeOp::Replace,
+                    "/a/b".into(),
+                    Some(Position { line: 2, col: 2 })
+                ),
+                (
+                    MergeOp::Remove,
+                    "/a/c".into(),
+                    Some(Position { line: 3, col: 2 })
+                ),
+                (
+                    MergeOp::Add,
+                    "/a/f".into(),
+                    Some(Position { line: 1, col: 0 })
+                ),
+                (
+                    MergeOp::Replace,
+                    "/d".into(),
+                    Some(Position { line: 4, col: 0 })
+                ),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn diff_patches_apply_in_order() -> Result<()> {
+        let operations = diff_patch(
+            "items: [a, b, c, d]\nname: 'x~y'\nmeta: {k: v}\n",
+            "items: [a, z]\nname: \"q\\\"\"\nmeta: [1.5, null]\nextra: {'a~b': [true]}\n",
+        )?
+        .into_iter()
+        .map(|operation| (operation.to_string(), operation.old, operation.new))
+        .collect::<Vec<_>>();
+        assert_eq!(
+            operations,
+            vec![
+                (
+                    r#"{"op":"remove","path":"/items/3"}"#.into(),
+                    Some(Position { line: 1, col: 17 }),
+                    None
+                ),
+                (
+                    r#"{"op":"remove","path":"/items/2"}"#.into(),
+                    Some(Position { line: 1, col: 14 }),
+                    None
+                ),
+                (
+                    r#"{"op":"replace","path":"/items/1","value":"z"}"#.into(),
+                    Some(Position { line: 1, col: 11 }),
+                    Some(Position { line: 1, col: 11 })
+                ),
+                (
+                    r#"{"op":"replace","path":"/meta","value":[1.5,null]}"#.into(),
+                    Some(Position { line: 3, col: 0 }),
+                    Some(Position { line: 3, col: 0 })
+                ),
+                (
+                    r#"{"op":"replace","path":"/name","value":"q\""}"#.into(),
+                    Some(Position { line: 2, col: 0 }),
+                    Some(Position { line: 2, col: 0 })
+                ),
+                (
+                    r#"{"op":"add","path":"/extra","value":{"a~b":[true]}}"#.into(),
+                    None,
+                    Some(Position { line: 4, col: 0 })
+                ),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_invalid_patches() -> Result<()> {
+        let positions = from_str("a: 1")?;
+        assert!(matches!(
+            positions.locate_patch("{op: add}"),
+            Err(Error::InvalidPatch(_))
+        ));
+        assert!(matches!(
+            positions.locate_patch("[{op: jump, path: /a}]"),
+            Err(Error::InvalidPatch(_))
+        ));
+        assert!(matches!(
+            positions.locate_patch("[{op: move, from: a, path: /b}]"),
+            Err(Error::InvalidPointer(err)) if err.pointer() == "a"
+        ));
+        Ok(())
+    }
+}
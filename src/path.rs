@@ -1,35 +1,78 @@
-use std::fmt::{self, Display};
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Write},
+};
 
-#[derive(Copy, Clone)]
-pub enum Path<'a> {
-    Root,
-    Seq { parent: &'a Path<'a>, index: usize },
-    Map { parent: &'a Path<'a>, key: &'a str },
+/// The pointer of the node being indexed, built up a token at a time as collections are
+/// descended into, so pointers aren't formatted anew from every one of their ancestors
+#[derive(Default)]
+pub struct Path {
+    pointer: String,
 }
 
-impl<'a> Display for Path<'a> {
+impl Path {
+    /// Appends a token, escaping any `~` or `/` within it, returning the length to
+    /// [`pop`](#method.pop) back to once the node it names is indexed
+    pub fn push<T>(
+        &mut self,
+        token: T,
+    ) -> usize
+    where
+        T: Display,
+    {
+        let len = self.pointer.len();
+        self.pointer.push('/');
+        // writing to a string can't fail
+        let _ = write!(self.pointer, "{}", token);
+        if let Cow::Owned(escaped) = escape(&self.pointer[len + 1..]) {
+            self.pointer.truncate(len + 1);
+            self.pointer.push_str(&escaped);
+        }
+        len
+    }
+
+    /// Removes the tokens appended since the pointer was `len` bytes long
+    pub fn pop(
+        &mut self,
+        len: usize,
+    ) {
+        self.pointer.truncate(len);
+    }
+
+    /// The pointer, the root being `""` so an empty key of a root mapping is distinguished
+    /// from it as `/`
+    pub fn as_str(&self) -> &str {
+        &self.pointer
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.pointer.is_empty()
+    }
+}
+
+impl Display for Path {
     fn fmt(
         &self,
         formatter: &mut fmt::Formatter,
     ) -> Result<(), fmt::Error> {
-        struct Parent<'a>(&'a Path<'a>);
-
-        impl<'a> Display for Parent<'a> {
-            fn fmt(
-                &self,
-                formatter: &mut fmt::Formatter,
-            ) -> Result<(), fmt::Error> {
-                match *self.0 {
-                    Path::Root => formatter.write_str("/"),
-                    ref path => write!(formatter, "{}/", path),
-                }
-            }
-        }
+        formatter.write_str(&self.pointer)
+    }
+}
 
-        match *self {
-            Path::Root => formatter.write_str("/"),
-            Path::Seq { parent, index } => write!(formatter, "{}/{}", parent, index),
-            Path::Map { parent, key } => write!(formatter, "{}{}", Parent(parent), key),
-        }
+/// Escapes a token's `~` and `/` as `~0` and `~1`, in that order, as pointers are indexed
+pub(crate) fn escape(token: &str) -> Cow<'_, str> {
+    if token.contains(['~', '/']) {
+        Cow::Owned(token.replace('~', "~0").replace('/', "~1"))
+    } else {
+        Cow::Borrowed(token)
+    }
+}
+
+/// Decodes a token's `~1` and `~0` escapes, in that order
+pub(crate) fn unescape(token: &str) -> Cow<'_, str> {
+    if token.contains('~') {
+        Cow::Owned(token.replace("~1", "/").replace("~0", "~"))
+    } else {
+        Cow::Borrowed(token)
     }
 }
@@ -0,0 +1,98 @@
+use crate::{Position, Positions};
+use serde_path_to_error::{Error, Path, Segment};
+
+/// Converts a [serde_path_to_error](https://docs.rs/serde_path_to_error) path, such as `foo.bar[2].baz`,
+/// into a JSON Pointer, such as `/foo/bar/2/baz`
+///
+/// Paths are followed up to any segment serde_path_to_error couldn't identify
+pub fn path_pointer(path: &Path) -> String {
+    let mut pointer = String::new();
+    for segment in path {
+        let token = match segment {
+            Segment::Seq { index } => index.to_string(),
+            Segment::Map { key } => key.clone(),
+            Segment::Enum { variant } => variant.clone(),
+            Segment::Unknown => break,
+        };
+        pointer.push('/');
+        pointer.push_str(&token);
+    }
+    pointer
+}
+
+impl Positions {
+    /// Returns the position of the value at a [serde_path_to_error](https://docs.rs/serde_path_to_error) path
+    pub fn get_path(
+        &self,
+        path: &Path,
+    ) -> Option<&Position> {
+        self.get(path_pointer(path))
+    }
+
+    /// Returns the position of the value a [serde_path_to_error](https://docs.rs/serde_path_to_error)
+    /// error occurred at
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{from_str, Position};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct Config {
+    ///     ports: Vec<u16>,
+    /// }
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let json = "{\n  \"ports\": [80, \"443\"]\n}";
+    /// let err = serde_path_to_error::deserialize::<_, Config>(
+    ///     &mut serde_json::Deserializer::from_str(json),
+    /// )
+    /// .unwrap_err();
+    /// let positions = from_str(json)?;
+    /// assert_eq!(positions.error_position(&err), Some(&Position { line: 2, col: 16 }));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn error_position<E>(
+        &self,
+        err: &Error<E>,
+    ) -> Option<&Position> {
+        self.get_path(err.path())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_str, Result};
+    use serde::Deserialize;
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Config {
+        services: BTreeMap<String, Service>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Service {
+        replicas: u8,
+    }
+
+    #[test]
+    fn resolves_error_paths() -> Result<()> {
+        let json =
+            "{\"services\": {\n  \"web\": {\"replicas\": 1},\n  \"db\": {\"replicas\": -1}\n}}";
+        let err = serde_path_to_error::deserialize::<_, Config>(
+            &mut serde_json::Deserializer::from_str(json),
+        )
+        .unwrap_err();
+        assert_eq!(path_pointer(err.path()), "/services/db/replicas");
+        let positions = from_str(json)?;
+        assert_eq!(
+            positions.error_position(&err),
+            Some(&Position { line: 3, col: 9 })
+        );
+        Ok(())
+    }
+}
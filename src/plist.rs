@@ -0,0 +1,363 @@
+use crate::{backend::Event, Backend, Error, Result, ScalarStyle};
+use roxmltree::{Document, Node};
+
+/// A [`Backend`](trait.Backend.html) for property lists, such as macOS `Info.plist`
+/// and launchd files, in either their XML format or the older ASCII format
+///
+/// Dictionaries are indexed as mappings and arrays as sequences. Values of XML property lists
+/// are tagged as the YAML core schema type of their element, so `<string>1</string>` is a string.
+/// Requires the `plist` feature
+///
+/// ```rust,edition2018
+/// use lincolns::{from_str_using, Options, Plist, Position};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+/// <plist version="1.0">
+/// <dict>
+///   <key>Label</key>
+///   <string>com.example.agent</string>
+///   <key>ProgramArguments</key>
+///   <array>
+///     <string>/usr/bin/true</string>
+///   </array>
+/// </dict>
+/// </plist>"#;
+/// let positions = from_str_using(&Plist, xml, Options::default())?;
+/// assert_eq!(positions.get("/ProgramArguments/0"), Some(&Position { line: 8, col: 12 }));
+///
+/// let ascii = "{ Label = com.example.agent; Count = 1; }";
+/// let positions = from_str_using(&Plist, ascii, Options::default())?;
+/// assert_eq!(positions.get("/Count"), Some(&Position { line: 1, col: 29 }));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Plist;
+
+impl Backend for Plist {
+    fn parse(
+        &self,
+        text: &str,
+        sink: &mut dyn FnMut(Event, usize),
+    ) -> Result<()> {
+        if text.trim_start().starts_with('<') {
+            xml(text, sink)
+        } else {
+            let mut cursor = Cursor { text, pos: 0, sink };
+            let start = cursor.skip()?;
+            (cursor.sink)(Event::DocumentStart, start);
+            cursor.value()?;
+            if cursor.skip()? < text.len() {
+                return Err(cursor.error("unexpected content"));
+            }
+            (cursor.sink)(Event::DocumentEnd, text.len());
+            Ok(())
+        }
+    }
+}
+
+fn xml(
+    text: &str,
+    sink: &mut dyn FnMut(Event, usize),
+) -> Result<()> {
+    let document = Document::parse(text).map_err(|err| Error::Backend(Box::new(err)))?;
+    let root = document.root_element();
+    let value = root
+        .children()
+        .find(Node::is_element)
+        .ok_or_else(|| Error::Backend("property list has no value".into()))?;
+    sink(Event::DocumentStart, value.range().start);
+    element(value, sink)?;
+    sink(Event::DocumentEnd, text.len());
+    Ok(())
+}
+
+/// Emits the events of an XML property list value
+fn element(
+    node: Node,
+    sink: &mut dyn FnMut(Event, usize),
+) -> Result<()> {
+    let tag = match node.tag_name().name() {
+        "dict" => {
+            sink(Event::MappingStart { anchor: 0 }, node.range().start);
+            for child in node.children().filter(Node::is_element) {
+                match child.tag_name().name() {
+                    "key" => text(child, "!!str", sink),
+                    _ => element(child, sink)?,
+                }
+            }
+            sink(Event::MappingEnd, node.range().end);
+            return Ok(());
+        }
+        "array" => {
+            sink(Event::SequenceStart { anchor: 0 }, node.range().start);
+            for child in node.children().filter(Node::is_element) {
+                element(child, sink)?;
+            }
+            sink(Event::SequenceEnd, node.range().end);
+            return Ok(());
+        }
+        "true" | "false" => {
+            sink(
+                Event::Scalar {
+                    value: node.tag_name().name().into(),
+                    style: ScalarStyle::Plain,
+                    tag: Some("!!bool".into()),
+                },
+                node.range().start,
+            );
+            return Ok(());
+        }
+        "integer" => "!!int",
+        "real" => "!!float",
+        "string" | "date" | "data" => "!!str",
+        other => {
+            return Err(Error::Backend(
+                format!("unknown property list element <{}>", other).into(),
+            ))
+        }
+    };
+    text(node, tag, sink);
+    Ok(())
+}
+
+/// Emits the text of an element as a scalar tagged `tag`
+fn text(
+    node: Node,
+    tag: &str,
+    sink: &mut dyn FnMut(Event, usize),
+) {
+    let content = node.children().find(Node::is_text);
+    let raw = content
+        .and_then(|content| content.text())
+        .unwrap_or_default();
+    let offset = content.map_or(node.range().end, |content| {
+        content.range().start + (raw.len() - raw.trim_start().len())
+    });
+    sink(
+        Event::Scalar {
+            value: raw.trim().into(),
+            style: ScalarStyle::Plain,
+            tag: Some(tag.into()),
+        },
+        offset,
+    );
+}
+
+/// Reads the ASCII property list format
+struct Cursor<'t, 's> {
+    text: &'t str,
+    pos: usize,
+    sink: &'s mut dyn FnMut(Event, usize),
+}
+
+impl<'t, 's> Cursor<'t, 's> {
+    fn error(
+        &self,
+        reason: &str,
+    ) -> Error {
+        Error::Backend(format!("{} at byte {}", reason, self.pos).into())
+    }
+
+    fn rest(&self) -> &'t str {
+        &self.text[self.pos..]
+    }
+
+    /// Advances past whitespace and comments to the start of the next token
+    fn skip(&mut self) -> Result<usize> {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.pos += rest.len() - trimmed.len();
+            if trimmed.starts_with("//") {
+                self.pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if trimmed.starts_with("/*") {
+                let len = trimmed
+                    .find("*/")
+                    .ok_or_else(|| self.error("unterminated comment"))?;
+                self.pos += len + 2;
+            } else {
+                return Ok(self.pos);
+            }
+        }
+    }
+
+    /// Consumes `expected` following any whitespace and comments
+    fn expect(
+        &mut self,
+        expected: char,
+    ) -> Result<usize> {
+        let at = self.skip()?;
+        if !self.rest().starts_with(expected) {
+            return Err(self.error(&format!("expected {}", expected)));
+        }
+        self.pos += 1;
+        Ok(at)
+    }
+
+    fn value(&mut self) -> Result<()> {
+        let at = self.skip()?;
+        match self.rest().chars().next() {
+            Some('{') => {
+                (self.sink)(Event::MappingStart { anchor: 0 }, at);
+                self.pos += 1;
+                while !self.rest_starts_with('}')? {
+                    self.string()?;
+                    self.expect('=')?;
+                    self.value()?;
+                    self.expect(';')?;
+                }
+                (self.sink)(Event::MappingEnd, self.pos);
+                self.pos += 1;
+            }
+            Some('(') => {
+                (self.sink)(Event::SequenceStart { anchor: 0 }, at);
+                self.pos += 1;
+                while !self.rest_starts_with(')')? {
+                    self.value()?;
+                    if !self.rest_starts_with(')')? {
+                        self.expect(',')?;
+                    }
+                }
+                (self.sink)(Event::SequenceEnd, self.pos);
+                self.pos += 1;
+            }
+            Some('<') => {
+                let len = self
+                    .rest()
+                    .find('>')
+                    .ok_or_else(|| self.error("unterminated data"))?;
+                self.scalar(self.rest()[..=len].to_owned(), ScalarStyle::Plain, at);
+                self.pos += len + 1;
+            }
+            Some(_) => self.string()?,
+            None => return Err(self.error("expected a value")),
+        }
+        Ok(())
+    }
+
+    /// Whether the next token starts with `c`
+    fn rest_starts_with(
+        &mut self,
+        c: char,
+    ) -> Result<bool> {
+        self.skip()?;
+        if self.rest().is_empty() {
+            return Err(self.error("unexpected end of content"));
+        }
+        Ok(self.rest().starts_with(c))
+    }
+
+    /// Reads a quoted or unquoted string
+    fn string(&mut self) -> Result<()> {
+        let at = self.skip()?;
+        if self.rest().starts_with('"') {
+            let mut value = String::new();
+            let mut chars = self.rest().char_indices().skip(1);
+            while let Some((idx, c)) = chars.next() {
+                match c {
+                    '"' => {
+                        self.scalar(value, ScalarStyle::DoubleQuoted, at);
+                        self.pos += idx + 1;
+                        return Ok(());
+                    }
+                    '\\' => match chars.next().map(|(_, c)| c) {
+                        Some('n') => value.push('\n'),
+                        Some('t') => value.push('\t'),
+                        Some('r') => value.push('\r'),
+                        Some('U') => {
+                            let hex = chars.by_ref().take(4).map(|(_, c)| c).collect::<String>();
+                            match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                                Some(decoded) => value.push(decoded),
+                                None => value.push_str(&hex),
+                            }
+                        }
+                        Some(other) => value.push(other),
+                        None => (),
+                    },
+                    c => value.push(c),
+                }
+            }
+            return Err(self.error("unterminated string"));
+        }
+        let len = self
+            .rest()
+            .find(|c: char| !(c.is_alphanumeric() || "_$+/:.-".contains(c)))
+            .unwrap_or(self.rest().len());
+        if len == 0 {
+            return Err(self.error("expected a string"));
+        }
+        self.scalar(self.rest()[..len].to_owned(), ScalarStyle::Plain, at);
+        self.pos += len;
+        Ok(())
+    }
+
+    fn scalar(
+        &mut self,
+        value: String,
+        style: ScalarStyle,
+        at: usize,
+    ) {
+        (self.sink)(
+            Event::Scalar {
+                value,
+                style,
+                tag: None,
+            },
+            at,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_str_using, NodeKind, Options, Position};
+
+    #[test]
+    fn indexes_xml_property_lists() -> Result<()> {
+        let xml = "<plist version=\"1.0\">\n<dict>\n  <key>Version</key>\n  <string>1</string>\n  <key>Count</key>\n  <integer>2</integer>\n  <key>Enabled</key>\n  <true/>\n  <key>Empty</key>\n  <dict/>\n</dict>\n</plist>\n";
+        let positions = from_str_using(&Plist, xml, Options::default())?;
+        assert_eq!(
+            positions.keys().collect::<Vec<_>>(),
+            vec!["/Count", "/Empty", "/Enabled", "/Version"]
+        );
+        assert_eq!(
+            positions.get("/Version"),
+            Some(&Position { line: 3, col: 7 })
+        );
+        assert_eq!(positions.kind("/Version"), Some(NodeKind::String));
+        assert_eq!(positions.kind("/Count"), Some(NodeKind::Integer));
+        assert_eq!(positions.kind("/Enabled"), Some(NodeKind::Bool));
+        assert_eq!(positions.kind("/Empty"), Some(NodeKind::Mapping));
+        assert!(matches!(
+            from_str_using(&Plist, "<plist><set/></plist>", Options::default()),
+            Err(Error::Backend(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn indexes_ascii_property_lists() -> Result<()> {
+        let ascii = "// settings\n{\n  Name = \"My \\\"App\\\"\";\n  Files = (a.txt, /* b */ \"c d\",);\n  Data = <0fbd 7777>;\n}\n";
+        let positions = from_str_using(&Plist, ascii, Options::default())?;
+        assert_eq!(
+            positions.get_value("/Name"),
+            Some(("My \"App\"", &Position { line: 3, col: 2 }))
+        );
+        assert_eq!(
+            positions.get_value("/Files/1"),
+            Some(("c d", &Position { line: 4, col: 26 }))
+        );
+        assert_eq!(
+            positions.get_value("/Data").map(|(value, _)| value),
+            Some("<0fbd 7777>")
+        );
+        assert!(matches!(
+            from_str_using(&Plist, "{ a = b }", Options::default()),
+            Err(Error::Backend(_))
+        ));
+        Ok(())
+    }
+}
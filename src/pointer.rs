@@ -0,0 +1,392 @@
+use crate::path;
+use std::{borrow::Cow, cmp::Ordering, error::Error as StdError, fmt, str::FromStr};
+
+/// An [RFC6901](https://tools.ietf.org/html/rfc6901) JSON Pointer, held in its escaped form,
+/// which may be used to look up positions
+///
+/// Tokens containing `~` or `/` are escaped as `~0` and `~1` when joined, and unescaped
+/// when read back
+///
+/// ```rust,edition2018
+/// use lincolns::Pointer;
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let ptr = Pointer::root().join("paths").join("/users/{id}");
+/// assert_eq!(ptr.as_str(), "/paths/~1users~1{id}");
+/// assert_eq!(ptr.last_token().as_deref(), Some("/users/{id}"));
+/// assert_eq!(ptr.parent(), Some("/paths".parse()?));
+///
+/// let positions = lincolns::from_str("paths:\n  /users/{id}: {}\n")?;
+/// assert!(positions.get(&ptr).is_some());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Pointers are ordered token by token, with [`Pointer::compare`](#method.compare), so
+/// `/items/2` comes before `/items/10`
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Pointer(String);
+
+/// Why a string isn't a valid JSON Pointer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PointerError {
+    /// A pointer which was neither empty nor started with `/`
+    MissingSlash { pointer: String },
+    /// A `~` at byte `offset` which wasn't followed by `0` or `1`
+    InvalidEscape { pointer: String, offset: usize },
+    /// A URI fragment whose percent-encoding wasn't valid UTF-8
+    InvalidEncoding { pointer: String },
+}
+
+impl PointerError {
+    /// The invalid pointer
+    pub fn pointer(&self) -> &str {
+        match self {
+            PointerError::MissingSlash { pointer }
+            | PointerError::InvalidEscape { pointer, .. }
+            | PointerError::InvalidEncoding { pointer } => pointer,
+        }
+    }
+}
+
+impl fmt::Display for PointerError {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            PointerError::MissingSlash { pointer } => write!(
+                f,
+                "invalid JSON Pointer {:?}: must be empty or start with /",
+                pointer
+            ),
+            PointerError::InvalidEscape { pointer, offset } => write!(
+                f,
+                "invalid JSON Pointer {:?}: ~ at byte {} must be followed by 0 or 1",
+                pointer, offset
+            ),
+            PointerError::InvalidEncoding { pointer } => write!(
+                f,
+                "invalid JSON Pointer {:?}: percent-encoding is not valid UTF-8",
+                pointer
+            ),
+        }
+    }
+}
+
+impl StdError for PointerError {}
+
+impl Pointer {
+    /// Parses an escaped pointer, such as `/paths/~1users`
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{Pointer, PointerError};
+    ///
+    /// assert!(Pointer::parse("/a~1b").is_ok());
+    /// assert_eq!(
+    ///     Pointer::parse("a/b"),
+    ///     Err(PointerError::MissingSlash { pointer: "a/b".into() })
+    /// );
+    /// assert_eq!(
+    ///     Pointer::parse("/a~2"),
+    ///     Err(PointerError::InvalidEscape { pointer: "/a~2".into(), offset: 2 })
+    /// );
+    /// ```
+    pub fn parse(s: &str) -> Result<Pointer, PointerError> {
+        if !s.is_empty() && !s.starts_with('/') {
+            return Err(PointerError::MissingSlash {
+                pointer: s.to_owned(),
+            });
+        }
+        let bytes = s.as_bytes();
+        for (offset, _) in s.match_indices('~') {
+            if !matches!(bytes.get(offset + 1), Some(b'0') | Some(b'1')) {
+                return Err(PointerError::InvalidEscape {
+                    pointer: s.to_owned(),
+                    offset,
+                });
+            }
+        }
+        Ok(Pointer(s.to_owned()))
+    }
+
+    /// Parses a pointer written in any of its equivalent forms into its canonical,
+    /// escaped form, accepting the URI fragment form, `#/a%20b`, as well as plain pointers
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::Pointer;
+    ///
+    /// # fn main() -> Result<(), lincolns::PointerError>  {
+    /// assert_eq!(Pointer::normalize("#/paths/~1users%7Bid%7D")?.as_str(), "/paths/~1users{id}");
+    /// assert_eq!(Pointer::normalize("#")?, Pointer::root());
+    /// assert_eq!(Pointer::normalize("/a")?.as_str(), "/a");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn normalize(s: &str) -> Result<Pointer, PointerError> {
+        match s.strip_prefix('#') {
+            Some(fragment) => {
+                let decoded =
+                    percent_decode(fragment).ok_or_else(|| PointerError::InvalidEncoding {
+                        pointer: s.to_owned(),
+                    })?;
+                Pointer::parse(&decoded).map_err(|err| match err {
+                    PointerError::MissingSlash { .. } => PointerError::MissingSlash {
+                        pointer: s.to_owned(),
+                    },
+                    PointerError::InvalidEscape { offset, .. } => PointerError::InvalidEscape {
+                        pointer: s.to_owned(),
+                        offset,
+                    },
+                    err => err,
+                })
+            }
+            None => Pointer::parse(s),
+        }
+    }
+
+    /// The root pointer, `""`, referring to a whole document
+    pub fn root() -> Self {
+        Pointer::default()
+    }
+
+    /// Builds a pointer from unescaped tokens
+    pub fn from_tokens<I, T>(tokens: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<str>,
+    {
+        tokens
+            .into_iter()
+            .fold(Pointer::root(), |ptr, token| ptr.join(token))
+    }
+
+    /// Returns true for the root pointer
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the escaped form of this pointer
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns an iterator over this pointer's unescaped tokens
+    pub fn tokens(&self) -> impl Iterator<Item = Cow<'_, str>> {
+        self.0.split('/').skip(1).map(path::unescape)
+    }
+
+    /// Returns the pointer to this pointer's parent, or `None` for the root
+    pub fn parent(&self) -> Option<Pointer> {
+        self.0
+            .rfind('/')
+            .map(|idx| Pointer(self.0[..idx].to_owned()))
+    }
+
+    /// Returns the unescaped final token of this pointer, or `None` for the root
+    pub fn last_token(&self) -> Option<Cow<'_, str>> {
+        self.0
+            .rfind('/')
+            .map(|idx| path::unescape(&self.0[idx + 1..]))
+    }
+
+    /// Returns a new pointer to a child of this one, escaping its unescaped `token`
+    pub fn join<T>(
+        &self,
+        token: T,
+    ) -> Pointer
+    where
+        T: AsRef<str>,
+    {
+        Pointer(format!("{}/{}", self.0, path::escape(token.as_ref())))
+    }
+
+    /// Compares pointers token by token, comparing tokens made of digits, such as sequence
+    /// indices, by their numeric value and others as strings. Parents come before their children
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::Pointer;
+    /// use std::cmp::Ordering;
+    ///
+    /// assert_eq!(Pointer::compare("/items/2", "/items/10"), Ordering::Less);
+    /// assert_eq!(Pointer::compare("/items/10", "/items/2/name"), Ordering::Greater);
+    /// assert_eq!(Pointer::compare("/a", "/a/b"), Ordering::Less);
+    /// ```
+    pub fn compare(
+        a: &str,
+        b: &str,
+    ) -> Ordering {
+        let numeric = |token: &str| !token.is_empty() && token.bytes().all(|b| b.is_ascii_digit());
+        let mut a = a.split('/');
+        let mut b = b.split('/');
+        loop {
+            let ordering = match (a.next(), b.next()) {
+                (None, None) => return Ordering::Equal,
+                (None, Some(_)) => return Ordering::Less,
+                (Some(_), None) => return Ordering::Greater,
+                (Some(a), Some(b)) if numeric(a) && numeric(b) => {
+                    let (x, y) = (a.trim_start_matches('0'), b.trim_start_matches('0'));
+                    x.len().cmp(&y.len()).then(x.cmp(y)).then(a.cmp(b))
+                }
+                (Some(a), Some(b)) => a.cmp(b),
+            };
+            if ordering != Ordering::Equal {
+                return ordering;
+            }
+        }
+    }
+
+    /// Returns true if `prefix` is this pointer or one of its ancestors, comparing whole tokens,
+    /// so `/ab` doesn't start with `/a`
+    pub fn starts_with(
+        &self,
+        prefix: &Pointer,
+    ) -> bool {
+        self.0.starts_with(&prefix.0)
+            && (self.0.len() == prefix.0.len() || self.0[prefix.0.len()..].starts_with('/'))
+    }
+}
+
+/// Decodes the `%XX` escapes of a URI fragment, or `None` when they're malformed
+fn percent_decode(fragment: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(fragment.len());
+    let mut rest = fragment.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+impl PartialOrd for Pointer {
+    fn partial_cmp(
+        &self,
+        other: &Self,
+    ) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Pointer {
+    fn cmp(
+        &self,
+        other: &Self,
+    ) -> Ordering {
+        Pointer::compare(&self.0, &other.0)
+    }
+}
+
+impl FromStr for Pointer {
+    type Err = PointerError;
+
+    /// Parses an escaped pointer, as [`Pointer::parse`](#method.parse) does
+    fn from_str(s: &str) -> Result<Self, PointerError> {
+        Pointer::parse(s)
+    }
+}
+
+impl fmt::Display for Pointer {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for Pointer {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<Pointer> for String {
+    fn from(ptr: Pointer) -> String {
+        ptr.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Result;
+
+    #[test]
+    fn joins_escaped_tokens() -> Result<()> {
+        let ptr = Pointer::from_tokens(vec!["a/b", "c~d", "0"]);
+        assert_eq!(ptr.to_string(), "/a~1b/c~0d/0");
+        assert_eq!(ptr.tokens().collect::<Vec<_>>(), vec!["a/b", "c~d", "0"]);
+        assert_eq!(ptr.last_token().as_deref(), Some("0"));
+        assert_eq!(ptr.parent(), Some("/a~1b/c~0d".parse()?));
+        assert_eq!(Pointer::root().parent(), None);
+        assert_eq!(Pointer::root().last_token(), None);
+        assert_eq!("/a".parse::<Pointer>()?.parent(), Some(Pointer::root()));
+        Ok(())
+    }
+
+    #[test]
+    fn starts_with_whole_tokens() -> Result<()> {
+        let ptr: Pointer = "/a/bc".parse()?;
+        assert!(ptr.starts_with(&Pointer::root()));
+        assert!(ptr.starts_with(&"/a".parse()?));
+        assert!(ptr.starts_with(&ptr));
+        assert!(!ptr.starts_with(&"/a/b".parse()?));
+        Ok(())
+    }
+
+    #[test]
+    fn orders_indices_numerically() -> Result<()> {
+        let mut pointers = ["/b", "/a/10", "/a/9/x", "/a/09", "/a", "/a/x", ""]
+            .iter()
+            .map(|ptr| ptr.parse())
+            .collect::<std::result::Result<Vec<Pointer>, _>>()?;
+        pointers.sort();
+        assert_eq!(
+            pointers.iter().map(Pointer::as_str).collect::<Vec<_>>(),
+            vec!["", "/a", "/a/09", "/a/9/x", "/a/10", "/a/x", "/b"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_invalid_pointers() {
+        assert_eq!(
+            Pointer::parse("x"),
+            Err(PointerError::MissingSlash {
+                pointer: "x".into()
+            })
+        );
+        assert_eq!(
+            Pointer::parse("/a~"),
+            Err(PointerError::InvalidEscape {
+                pointer: "/a~".into(),
+                offset: 2
+            })
+        );
+        assert_eq!(
+            Pointer::normalize("#/a%2"),
+            Err(PointerError::InvalidEncoding {
+                pointer: "#/a%2".into()
+            })
+        );
+        assert_eq!(
+            Pointer::normalize("#a"),
+            Err(PointerError::MissingSlash {
+                pointer: "#a".into()
+            })
+        );
+        assert_eq!(
+            Pointer::normalize("#/%7E2"),
+            Err(PointerError::InvalidEscape {
+                pointer: "#/%7E2".into(),
+                offset: 1
+            })
+        );
+    }
+}
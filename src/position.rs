@@ -1,59 +1,251 @@
-use crate::Path;
+use crate::{
+    anchor::{self, Alias, Anchor},
+    backend,
+    comment::{self, Comment},
+    document::{self, Directive, Document},
+    path,
+    source::{self, Columns, LineEnding, LineIndex, Source},
+    stream::SpannedEvent,
+    DuplicateKeys, Entry, MergeKeys, NodeKind, Options, Path, Pointer, PointerError, ScalarStyle,
+    Warning, YamlVersion,
+};
 use log::debug;
-use std::collections::BTreeMap;
-use yaml_rust::{
-    parser::{Event as YamlEvent, MarkedEventReceiver},
-    scanner::{Marker, TScalarStyle, TokenType},
+use std::{
+    collections::{btree_map, BTreeMap, HashMap, HashSet},
+    fmt,
+    iter::FromIterator,
+    mem,
+    ops::{Index, Range},
+    sync::{Arc, OnceLock},
 };
+use yaml_rust::scanner::Marker;
 
 /// Line and column position of content in a file
-#[derive(Debug, PartialEq)]
+///
+/// By default lines are numbered starting at 1 and columns starting at 0.
+/// Use [`Options::one_based`](struct.Options.html#method.one_based) to number columns starting at 1
+///
+/// Positions order as they appear in a document, by line and then column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Position {
     pub line: usize,
     pub col: usize,
 }
 
-impl Into<Position> for Marker {
-    fn into(self) -> Position {
-        let (line, col) = (self.line(), self.col());
+/// Formats a position as `line:col`
+impl fmt::Display for Position {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.col)
+    }
+}
+
+impl From<Marker> for Position {
+    fn from(marker: Marker) -> Self {
+        let (line, col) = (marker.line(), marker.col());
         Position { line, col }
     }
 }
 
+/// The region of content a node occupies in a file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    /// Position of the first character of the node
+    pub start: Position,
+    /// Position just past the last character of the node
+    pub end: Position,
+    /// Byte offsets of the node within the source text
+    pub bytes: Range<usize>,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 enum Event {
-    Scalar(String, TScalarStyle, Option<TokenType>),
+    Scalar(Scalar),
+    DocumentStart,
+    DocumentEnd,
+    Alias(usize),
     SequenceStart,
     SequenceEnd,
-    MappingStart,
+    MappingStart(usize),
     MappingEnd,
 }
 
-#[doc(hidden)]
-impl Default for Positions {
-    fn default() -> Self {
-        Self {
-            pos: 0,
-            events: Vec::new(),
-            index: BTreeMap::new(),
+/// A key that appeared more than once within the same mapping
+#[derive(Debug, PartialEq)]
+pub struct Duplicate {
+    /// JSON Pointer path of the repeated key
+    pub pointer: String,
+    /// Position of the key's first occurrence
+    pub first: Position,
+    /// Position of the repeated occurrence
+    pub second: Position,
+}
+
+/// A scalar's parsed value and how it was written
+#[derive(Debug, PartialEq, Clone)]
+struct Scalar {
+    value: String,
+    style: ScalarStyle,
+    tag: Option<String>,
+    /// Position of the first line of a block scalar's content
+    content: Option<Position>,
+}
+
+impl Scalar {
+    fn kind(
+        &self,
+        version: YamlVersion,
+    ) -> NodeKind {
+        NodeKind::scalar(&self.value, self.style, self.tag.as_deref(), version)
+    }
+
+    /// Byte range of this scalar marked at `offset`, noting where the content of
+    /// block scalars begins
+    fn locate(
+        &mut self,
+        offset: usize,
+        prev: usize,
+        scalars: &mut Vec<Range<usize>>,
+        source: &Source,
+    ) -> Range<usize> {
+        let bytes = source.scalar(offset, self.style, &self.value, prev);
+        scalars.push(bytes.clone());
+        if self.style.is_block() {
+            // block scalars are marked at the indentation of their first non-blank line
+            let header = source.position(bytes.start);
+            let col = source.position(offset).col;
+            self.content = Some(Position {
+                line: header.line + 1,
+                col,
+            });
         }
+        bytes
     }
 }
 
+/// A `<<` merge key at byte offset `site`, with the anchor id and byte range of each alias it merges
+struct Merge {
+    site: usize,
+    aliases: Vec<(usize, Range<usize>)>,
+}
+
+/// An indexed node
+#[derive(Debug, Clone)]
+struct Node {
+    position: Position,
+    span: Span,
+    /// Span of the key of a mapping entry
+    key: Option<Span>,
+    /// Span of the `-` indicator of a block sequence item
+    indicator: Option<Span>,
+    kind: NodeKind,
+    scalar: Option<Scalar>,
+    /// Explicit tag of a collection, those of scalars being kept with their value
+    tag: Option<String>,
+}
+
+/// Hashes text with 64-bit FNV-1a
+fn content_hash(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
 /// A table of [`Position`](struct.Position.html) information
+#[derive(Default)]
 pub struct Positions {
     pos: usize,
-    events: Vec<(Event, Marker)>,
-    index: BTreeMap<String, Position>,
+    /// Events and the byte offsets they occurred at
+    events: Vec<(Event, usize)>,
+    /// Nodes by pointer, shared with the hash index when there is one
+    index: BTreeMap<String, Arc<Node>>,
+    duplicates: Vec<Duplicate>,
+    /// Every position of pointers defined more than once, by repeated keys or by keys
+    /// overriding merged ones, in document order
+    occurrences: HashMap<String, Vec<Position>>,
+    warnings: Vec<Warning>,
+    /// The first document's root, which has no pointer of its own to be indexed by
+    root: Option<Node>,
+    /// Positions just past the last item of each sequence, by the `-` pointer referring to them
+    appends: HashMap<String, Position>,
+    /// Whether the text began with a byte order mark
+    bom: bool,
+    line_ending: LineEnding,
+    /// Pointer prefixes of anchored mappings by anchor id
+    prefixes: HashMap<usize, String>,
+    columns: Columns,
+    /// Byte ranges of every scalar, including keys, collected while indexing
+    scalars: Vec<Range<usize>>,
+    comments: Vec<Comment>,
+    anchors: Vec<Anchor>,
+    directives: Vec<Directive>,
+    aliases: Vec<Alias>,
+    documents: Vec<Document>,
+    /// Events recorded while indexing, when requested
+    stream: Option<Vec<(SpannedEvent, Position)>>,
+    source: Option<(String, LineIndex)>,
+    /// Index of the first item of a root sequence, which shards of one start past 0
+    first_item: usize,
+    /// A hash of the text indexed
+    content_hash: u64,
+    /// The number of lines of the text indexed
+    lines: usize,
+    /// The YAML version plain scalars were resolved with
+    yaml_version: YamlVersion,
+    /// Indexed pointers by their lowercased form, when looked up case-insensitively
+    folded: Option<HashMap<String, String>>,
+    /// Indexed pointers in document order, sorted when first looked up by position
+    by_position: OnceLock<Vec<(Position, String)>>,
+    /// The nodes of the index by pointer for exact lookups, when hashed
+    #[cfg(feature = "hash-index")]
+    hashed: Option<rustc_hash::FxHashMap<String, Arc<Node>>>,
 }
 
 impl Positions {
+    /// Starts building a table with [`Options`](struct.Options.html), which load content
+    /// once configured
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{ColumnUnit, Positions};
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = Positions::builder()
+    ///     .column_unit(ColumnUnit::Utf16)
+    ///     .case_insensitive(true)
+    ///     .load("Name: 😀")?;
+    /// assert!(positions.contains("/name"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn builder() -> Options {
+        Options::default()
+    }
+
     /// Gets a yaml field's position within a document given its JSON Pointer path
     ///
     /// JSON Pointer defines a string syntax for identifying a specific value
     /// within a JavaScript Object Notation (JSON) document.
     ///
     /// For more information read [RFC6901](https://tools.ietf.org/html/rfc6901).
+    ///
+    /// The root pointer, `""`, refers to the root of the first document.
+    /// A final `-` token refers to the item after the last of a sequence,
+    /// positioned just past it, where another would be appended
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::Position;
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("items: [a, b]\n")?;
+    /// assert_eq!(positions.get("/items/1"), Some(&Position { line: 1, col: 11 }));
+    /// assert_eq!(positions.get("/items/-"), Some(&Position { line: 1, col: 12 }));
+    /// assert_eq!(positions.get(""), Some(&Position { line: 1, col: 0 }));
+    /// # Ok(())
+    /// # }
+    /// ```
     pub fn get<P>(
         &self,
         ptr: P,
@@ -61,129 +253,3639 @@ impl Positions {
     where
         P: AsRef<str>,
     {
-        self.index.get(ptr.as_ref())
+        let ptr = ptr.as_ref();
+        self.node(ptr)
+            .map(|node| &node.position)
+            .or_else(|| self.append(ptr).map(|(_, position)| position))
     }
 
-    fn next(&mut self) -> Option<(Event, Position)> {
-        self.events.clone().get(self.pos).map(|event| {
-            self.pos += 1;
-            (event.clone().0, event.1.into())
-        })
+    /// Gets a node's position given its JSON Pointer path, as [`get`](#method.get) does,
+    /// failing with a [`PointerError`](enum.PointerError.html) when the pointer isn't valid
+    /// rather than treating it as a path which doesn't exist
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{Position, PointerError};
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("a: 1\n")?;
+    /// assert_eq!(positions.try_get("/a"), Ok(Some(&Position { line: 1, col: 0 })));
+    /// assert_eq!(positions.try_get("/b"), Ok(None));
+    /// assert_eq!(
+    ///     positions.try_get("a"),
+    ///     Err(PointerError::MissingSlash { pointer: "a".into() })
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn try_get<P>(
+        &self,
+        ptr: P,
+    ) -> Result<Option<&Position>, PointerError>
+    where
+        P: AsRef<str>,
+    {
+        let ptr = Pointer::parse(ptr.as_ref())?;
+        Ok(self.get(ptr))
+    }
+
+    /// Gets every position of a JSON Pointer path, in document order
+    ///
+    /// Most pointers have one position, that of [`get`](#method.get). Keys repeated within
+    /// a mapping, and keys overriding those a mapping merges with `<<` when loaded with
+    /// [`MergeKeys`](enum.MergeKeys.html) expanded, have one for each occurrence, whichever
+    /// is indexed
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::Position;
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("a: 1\nb: 2\na: 3\n")?;
+    /// assert_eq!(
+    ///     positions.get_all("/a"),
+    ///     &[Position { line: 1, col: 0 }, Position { line: 3, col: 0 }]
+    /// );
+    /// assert_eq!(positions.get_all("/b"), &[Position { line: 2, col: 0 }]);
+    /// assert!(positions.get_all("/c").is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_all<P>(
+        &self,
+        ptr: P,
+    ) -> &[Position]
+    where
+        P: AsRef<str>,
+    {
+        let ptr = ptr.as_ref();
+        if let Some((key, _)) = self.entry(ptr) {
+            if let Some(occurrences) = self.occurrences.get(key) {
+                return occurrences;
+            }
+        }
+        self.get(ptr).map_or(&[], std::slice::from_ref)
     }
 
-    /// Returns an iterator over positions
-    pub fn iter(&self) -> impl IntoIterator<Item = (&String, &Position)> {
-        self.index.iter()
+    /// Gets the positions of many JSON Pointer paths at once, aligned with the pointers given
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::Position;
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("kind: Pod\nspec:\n  image: nginx\n")?;
+    /// assert_eq!(
+    ///     positions.get_many(&["/spec/image", "/missing", "/kind"]),
+    ///     vec![
+    ///         Some(&Position { line: 3, col: 2 }),
+    ///         None,
+    ///         Some(&Position { line: 1, col: 0 })
+    ///     ]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_many<P>(
+        &self,
+        ptrs: &[P],
+    ) -> Vec<Option<&Position>>
+    where
+        P: AsRef<str>,
+    {
+        ptrs.iter().map(|ptr| self.get(ptr)).collect()
     }
 
-    pub(crate) fn collect(
+    /// Gets the positions of many JSON Pointer paths at once, keyed by pointer.
+    /// Pointers which aren't indexed are left out
+    ///
+    /// ```rust,edition2018
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("kind: Pod\nspec:\n  image: nginx\n")?;
+    /// let found = positions.get_many_map(&["/spec/image", "/missing"]);
+    /// assert_eq!(found.len(), 1);
+    /// assert_eq!(found["/spec/image"].line, 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_many_map<'a, P>(
+        &self,
+        ptrs: &'a [P],
+    ) -> HashMap<&'a str, &Position>
+    where
+        P: AsRef<str>,
+    {
+        ptrs.iter()
+            .filter_map(|ptr| {
+                let ptr = ptr.as_ref();
+                self.get(ptr).map(|position| (ptr, position))
+            })
+            .collect()
+    }
+
+    /// Looks up the position after the last item of a sequence given a pointer ending with `-`
+    fn append(
+        &self,
+        ptr: &str,
+    ) -> Option<(&String, &Position)> {
+        let parent = ptr.strip_suffix("/-")?;
+        let parent = self
+            .entry(parent)
+            .map_or(parent, |(parent, _)| parent.as_str());
+        self.appends.get_key_value(&format!("{}/-", parent))
+    }
+
+    /// Returns true if a node is indexed at a JSON Pointer path
+    pub fn contains<P>(
+        &self,
+        ptr: P,
+    ) -> bool
+    where
+        P: AsRef<str>,
+    {
+        self.node(ptr.as_ref()).is_some()
+    }
+
+    /// Removes every indexed node for which `keep` returns false given its pointer and position
+    ///
+    /// ```rust,edition2018
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let mut positions = lincolns::from_str("kind: Pod\nspec:\n  image: nginx\n")?;
+    /// positions.retain(|ptr, _| ptr.starts_with("/spec"));
+    /// assert_eq!(positions.keys().collect::<Vec<_>>(), vec!["/spec", "/spec/image"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn retain<F>(
         &mut self,
-        path: &Path,
-    ) {
-        if let Some((ev, _)) = self.next() {
-            match ev {
-                Event::SequenceStart => {
-                    self.collect_seq(0, path);
-                    self.collect(path);
-                }
-                Event::MappingStart => {
-                    self.collect_map(path);
-                    self.collect(path);
-                }
-                other => debug!("unhandled {:?} in collect", other),
-            }
+        mut keep: F,
+    ) where
+        F: FnMut(&str, &Position) -> bool,
+    {
+        self.index.retain(|ptr, node| keep(ptr, &node.position));
+        self.by_position.take();
+        if self.folded.is_some() {
+            self.fold_case();
+        }
+        #[cfg(feature = "hash-index")]
+        if self.hashed.is_some() {
+            self.hash();
         }
     }
 
-    fn collect_seq(
-        &mut self,
-        index: usize,
-        path: &Path,
-    ) {
-        if let Some((ev, pos)) = self.next() {
-            match ev {
-                Event::SequenceEnd => (),
-                Event::Scalar(_, _, _) => {
-                    self.index.insert(
-                        format!(
-                            "{}",
-                            Path::Seq {
-                                parent: &path,
-                                index: index
-                            }
-                        ),
-                        pos,
-                    );
-                    self.collect_seq(index + 1, &path);
-                }
-                Event::MappingStart => {
-                    self.collect_map(&Path::Seq {
-                        parent: &path,
-                        index,
-                    });
-                    self.collect_seq(index + 1, &path);
-                }
-                other => debug!("unhandled {:?} in collect_seq", other),
-            }
+    /// Returns a new table of the node at a JSON Pointer path and everything nested within it,
+    /// keeping their pointers, positions, and any retained source
+    ///
+    /// See [`subtree_rebased`](#method.subtree_rebased) for pointers relative to the node
+    pub fn subtree<P>(
+        &self,
+        ptr: P,
+    ) -> Positions
+    where
+        P: AsRef<str>,
+    {
+        self.extract(ptr.as_ref(), false)
+    }
+
+    /// Returns a new table of everything nested within the node at a JSON Pointer path,
+    /// with pointers relative to that node, as if it were the root of its own document.
+    /// Positions remain those within the original document
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::Position;
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("jobs:\n  build:\n    runs-on: linux\n")?;
+    /// let build = positions.subtree_rebased("/jobs/build");
+    /// assert_eq!(build.get("/runs-on"), Some(&Position { line: 3, col: 4 }));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn subtree_rebased<P>(
+        &self,
+        ptr: P,
+    ) -> Positions
+    where
+        P: AsRef<str>,
+    {
+        self.extract(ptr.as_ref(), true)
+    }
+
+    fn extract(
+        &self,
+        ptr: &str,
+        rebase: bool,
+    ) -> Positions {
+        let ptr = match self.entry(ptr) {
+            Some((ptr, _)) => ptr.as_str(),
+            None if ptr.is_empty() => ptr,
+            None => return Positions::default(),
+        };
+        let index = self
+            .index
+            .range(ptr.to_owned()..)
+            .take_while(|(key, _)| key.starts_with(ptr))
+            .filter(|(key, _)| key.len() == ptr.len() || key[ptr.len()..].starts_with('/'))
+            .filter(|(key, _)| !rebase || key.len() > ptr.len())
+            .map(|(key, node)| {
+                let key = if rebase { &key[ptr.len()..] } else { key };
+                (key.to_owned(), node.clone())
+            })
+            .collect();
+        let mut positions = Positions {
+            index,
+            // a rebased node becomes the root of its own table
+            root: if rebase || ptr.is_empty() {
+                self.node(ptr).cloned()
+            } else {
+                None
+            },
+            columns: self.columns,
+            source: self.source.clone(),
+            ..Positions::default()
+        };
+        if self.folded.is_some() {
+            positions.fold_case();
+        }
+        #[cfg(feature = "hash-index")]
+        if self.hashed.is_some() {
+            positions.hash();
         }
+        positions
     }
 
-    fn collect_map(
-        &mut self,
-        path: &Path,
-    ) {
-        if let Some((ev, pos)) = self.next() {
-            match ev {
-                Event::MappingEnd => (),
-                Event::Scalar(key, _, _) => {
-                    let this_path = Path::Map {
-                        parent: &path,
-                        key: &key,
-                    };
-                    self.index.insert(format!("{}", this_path), pos);
-                    match self.next() {
-                        Some((Event::MappingStart, _)) => {
-                            self.collect_map(&this_path);
-                        }
-                        Some((Event::SequenceStart, _)) => {
-                            self.collect_seq(0, &this_path);
-                        }
-                        _ => (),
-                    }
-                    self.collect_map(&path);
-                }
-                other => debug!("unhandled {:?} in collect_map", other),
+    /// Returns the number of indexed pointers
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Returns true if no pointers were indexed, as for empty or scalar documents
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Returns the number of items or entries a sequence or mapping has given its JSON Pointer path,
+    /// or `None` when it refers to a scalar or nothing at all
+    ///
+    /// ```rust,edition2018
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("steps:\n  - run: make\n  - run: make test\n")?;
+    /// assert_eq!(positions.children_count("/steps"), Some(2));
+    /// assert_eq!(positions.children_count(""), Some(1));
+    /// assert_eq!(positions.children_count("/steps/0/run"), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn children_count<P>(
+        &self,
+        ptr: P,
+    ) -> Option<usize>
+    where
+        P: AsRef<str>,
+    {
+        let ptr = ptr.as_ref();
+        let ptr = self.entry(ptr).map_or(ptr, |(ptr, _)| ptr.as_str());
+        match self.describe(ptr)? {
+            (_, _, NodeKind::Sequence, _) | (_, _, NodeKind::Mapping, _) => {
+                Some(self.children_of(ptr).count())
             }
+            _ => None,
         }
     }
-}
 
-#[doc(hidden)]
-impl MarkedEventReceiver for Positions {
-    fn on_event(
-        &mut self,
-        event: YamlEvent,
-        marker: Marker,
-    ) {
-        let event = match event {
-            YamlEvent::Nothing
-            | YamlEvent::StreamStart
-            | YamlEvent::StreamEnd
-            | YamlEvent::DocumentStart
-            | YamlEvent::DocumentEnd
-            | YamlEvent::Alias(_) /*come back to Alias later*/=> return,
-            YamlEvent::Scalar(value, style, _, tag) => {
-                Event::Scalar(value, style, tag)
+    /// Returns the pointers and positions of the items or entries of a sequence or mapping
+    /// given its JSON Pointer path, in document order. Scalars and missing nodes have none
+    ///
+    /// ```rust,edition2018
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("jobs:\n  test: {}\n  build: {}\n")?;
+    /// assert_eq!(
+    ///     positions.children("/jobs").into_iter().map(|(ptr, _)| ptr).collect::<Vec<_>>(),
+    ///     vec!["/jobs/test", "/jobs/build"]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn children<P>(
+        &self,
+        ptr: P,
+    ) -> Vec<(&str, &Position)>
+    where
+        P: AsRef<str>,
+    {
+        let ptr = ptr.as_ref();
+        let ptr = self.entry(ptr).map_or(ptr, |(ptr, _)| ptr.as_str());
+        let mut nodes = self
+            .children_of(ptr)
+            .map(|(ptr, node)| (ptr.as_str(), &node.position))
+            .collect::<Vec<_>>();
+        nodes.sort_by_key(|(_, position)| **position);
+        nodes
+    }
+
+    /// Indexed nodes immediately nested within the node at `ptr`, ordered by pointer
+    fn children_of(
+        &self,
+        ptr: &str,
+    ) -> impl Iterator<Item = (&String, &Node)> {
+        let prefix = format!("{}/", ptr);
+        let len = prefix.len();
+        self.index
+            .range(prefix.clone()..)
+            .take_while(move |(key, _)| key.starts_with(&prefix))
+            .filter(move |(key, _)| !key[len..].contains('/'))
+            .map(|(key, node)| (key, &**node))
+    }
+
+    /// Gets the pointer and position of the nearest indexed node along a JSON Pointer path,
+    /// being the node itself or, when it's missing, its closest existing ancestor
+    ///
+    /// ```rust,edition2018
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("server:\n  port: 80\n")?;
+    /// assert_eq!(
+    ///     positions.get_closest("/server/timeout/seconds"),
+    ///     Some(("/server", &lincolns::Position { line: 1, col: 0 }))
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_closest<P>(
+        &self,
+        ptr: P,
+    ) -> Option<(&str, &Position)>
+    where
+        P: AsRef<str>,
+    {
+        let mut ptr = ptr.as_ref();
+        loop {
+            if let Some((ptr, node)) = self.entry(ptr) {
+                return Some((ptr.as_str(), &node.position));
             }
-            YamlEvent::SequenceStart(_) => {
-                Event::SequenceStart
+            if let Some((ptr, position)) = self.append(ptr) {
+                return Some((ptr.as_str(), position));
             }
-            YamlEvent::SequenceEnd => Event::SequenceEnd,
-            YamlEvent::MappingStart(_) => {
-                Event::MappingStart
+            if ptr.is_empty() {
+                return self.root.as_ref().map(|root| ("", &root.position));
             }
-            YamlEvent::MappingEnd => Event::MappingEnd,
+            ptr = &ptr[..ptr.rfind('/')?];
+        }
+    }
+
+    /// Returns the pointers and positions of a node's ancestors, from the outermost down,
+    /// followed by the node's own, for rendering breadcrumbs such as `jobs (l.1) → build (l.2)`.
+    /// The document root is left out, and missing nodes have none
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::Position;
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("jobs:\n  build:\n    steps: [checkout, make]\n")?;
+    /// assert_eq!(
+    ///     positions.ancestors("/jobs/build/steps/1"),
+    ///     vec![
+    ///         ("/jobs", &Position { line: 1, col: 0 }),
+    ///         ("/jobs/build", &Position { line: 2, col: 2 }),
+    ///         ("/jobs/build/steps", &Position { line: 3, col: 4 }),
+    ///         ("/jobs/build/steps/1", &Position { line: 3, col: 22 }),
+    ///     ]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn ancestors<P>(
+        &self,
+        ptr: P,
+    ) -> Vec<(&str, &Position)>
+    where
+        P: AsRef<str>,
+    {
+        let ptr = match self.entry(ptr.as_ref()) {
+            Some((ptr, _)) => ptr.as_str(),
+            None => return Vec::new(),
         };
-        self.events.push((event, marker));
+        ptr.match_indices('/')
+            .skip(1)
+            .map(|(idx, _)| &ptr[..idx])
+            .chain(std::iter::once(ptr))
+            // ancestors dropped by `retain` no longer have a node
+            .filter_map(|ancestor| self.index.get_key_value(ancestor))
+            .map(|(ptr, node)| (ptr.as_str(), &node.position))
+            .collect()
+    }
+
+    /// Gets the parsed value of a scalar along with its position given its JSON Pointer path
+    ///
+    /// Values are returned as they were resolved by the parser, with quoting removed,
+    /// escapes applied, and lines folded. Mappings and sequences have no scalar value
+    ///
+    /// ```rust,edition2018
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("image: 'nginx:1.19'")?;
+    /// assert_eq!(
+    ///     positions.get_value("/image"),
+    ///     Some(("nginx:1.19", &lincolns::Position { line: 1, col: 0 }))
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get_value<P>(
+        &self,
+        ptr: P,
+    ) -> Option<(&str, &Position)>
+    where
+        P: AsRef<str>,
+    {
+        self.node(ptr.as_ref())
+            .and_then(|node| Some((node.scalar.as_ref()?.value.as_str(), &node.position)))
+    }
+
+    /// Returns an iterator over the [`Entry`](struct.Entry.html) of each scalar whose parsed
+    /// value equals `value`, ordered by pointer
+    ///
+    /// ```rust,edition2018
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("web:\n  tag: latest\ndb:\n  tag: '13'\nworker:\n  tag: latest\n")?;
+    /// assert_eq!(
+    ///     positions.find_value("latest").map(|entry| entry.pointer).collect::<Vec<_>>(),
+    ///     vec!["/web/tag", "/worker/tag"]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn find_value<'a>(
+        &'a self,
+        value: &'a str,
+    ) -> impl Iterator<Item = Entry<'a>> + 'a {
+        self.entries()
+            .filter(move |entry| entry.value == Some(value))
+    }
+
+    /// Returns an iterator over the [`Entry`](struct.Entry.html) of each mapping entry
+    /// whose key is `key`, at any depth, ordered by pointer
+    ///
+    /// ```rust,edition2018
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("db:\n  password: x\nusers:\n  - name: a\n    password: y\n")?;
+    /// assert_eq!(
+    ///     positions.find_key("password").map(|entry| entry.pointer).collect::<Vec<_>>(),
+    ///     vec!["/db/password", "/users/0/password"]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn find_key<'a>(
+        &'a self,
+        key: &'a str,
+    ) -> impl Iterator<Item = Entry<'a>> + 'a {
+        self.entries().filter(move |entry| {
+            entry.key_span.is_some()
+                && entry
+                    .pointer
+                    .rsplit('/')
+                    .next()
+                    .is_some_and(|token| path::unescape(token) == key)
+        })
+    }
+
+    /// Gets the [`NodeKind`](enum.NodeKind.html) of the value a JSON Pointer path refers to
+    ///
+    /// Scalars are classified by their explicit tag, if any, then by their style and value
+    /// following the YAML core schema, so `"1"` is a string while `1` is an integer
+    pub fn kind<P>(
+        &self,
+        ptr: P,
+    ) -> Option<NodeKind>
+    where
+        P: AsRef<str>,
+    {
+        self.node(ptr.as_ref()).map(|node| node.kind)
+    }
+
+    /// Gets the [`ScalarStyle`](enum.ScalarStyle.html) a scalar was written in given its JSON Pointer path
+    ///
+    /// Mappings and sequences have no scalar style
+    pub fn style<P>(
+        &self,
+        ptr: P,
+    ) -> Option<ScalarStyle>
+    where
+        P: AsRef<str>,
+    {
+        self.node(ptr.as_ref())
+            .and_then(|node| Some(node.scalar.as_ref()?.style))
+    }
+
+    /// Gets the explicit tag of a node, as written, given its JSON Pointer path
+    ///
+    /// Members of `!!set` mappings are indexed by key, spanning their keys rather than their
+    /// empty values, and the pairs of `!!omap` sequences by index, as `/omap/0/key`
+    ///
+    /// ```rust,edition2018
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("version: !!str 1.10\nrun: !Ref Cmd\nenvs: !!set {dev, prod}")?;
+    /// assert_eq!(positions.tag("/version"), Some("!!str"));
+    /// assert_eq!(positions.tag("/run"), Some("!Ref"));
+    /// assert_eq!(positions.tag("/envs"), Some("!!set"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn tag<P>(
+        &self,
+        ptr: P,
+    ) -> Option<&str>
+    where
+        P: AsRef<str>,
+    {
+        self.node(ptr.as_ref()).and_then(|node| match &node.scalar {
+            Some(scalar) => scalar.tag.as_deref(),
+            None => node.tag.as_deref(),
+        })
+    }
+
+    /// Gets the position of a line within the content of a `|` or `>` block scalar
+    /// given its JSON Pointer path and the zero-based line number within its content
+    ///
+    /// Lines are counted as written in the file, following the block scalar's header,
+    /// and positioned at the content's indentation. Other scalars and lines past
+    /// the end of the content have no position
+    ///
+    /// ```rust,edition2018
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("run: |\n  make\n  make test\n")?;
+    /// assert_eq!(
+    ///     positions.scalar_line("/run", 1),
+    ///     Some(lincolns::Position { line: 3, col: 2 })
+    /// );
+    /// assert_eq!(positions.scalar_line("/run", 2), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn scalar_line<P>(
+        &self,
+        ptr: P,
+        n: usize,
+    ) -> Option<Position>
+    where
+        P: AsRef<str>,
+    {
+        let node = self.node(ptr.as_ref())?;
+        let content = node.scalar.as_ref()?.content?;
+        let line = content.line + n;
+        (line <= node.span.end.line).then_some(Position { line, ..content })
+    }
+
+    /// Gets the position of a byte offset within a scalar's parsed value given its JSON Pointer path
+    ///
+    /// Quoting, escapes, indentation, and line folding are accounted for, making it possible
+    /// to point at errors within embedded content such as expressions or inline JSON.
+    /// This requires the source to be retained with [`Options::keep_source`](struct.Options.html#method.keep_source)
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{from_str_with, Options, Position};
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = from_str_with(
+    ///     "if: \"${{ github.ref == \\\"main\\\" }}\"",
+    ///     Options::default().keep_source(true),
+    /// )?;
+    /// let (value, _) = positions.get_value("/if").unwrap();
+    /// let offset = value.find("main").unwrap();
+    /// assert_eq!(
+    ///     positions.position_within("/if", offset),
+    ///     Some(Position { line: 1, col: 25 })
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn position_within<P>(
+        &self,
+        ptr: P,
+        byte_offset: usize,
+    ) -> Option<Position>
+    where
+        P: AsRef<str>,
+    {
+        let (text, lines) = self.source.as_ref()?;
+        let node = self.node(ptr.as_ref())?;
+        let scalar = node.scalar.as_ref()?;
+        let raw = text.get(node.span.bytes.clone())?;
+        let offset = source::value_offset(raw, scalar.style, &scalar.value, byte_offset)?;
+        Some(lines.position_with(text, node.span.bytes.start + offset, self.columns))
+    }
+
+    /// Gets the [`Span`](struct.Span.html) of the value a JSON Pointer path refers to
+    pub fn span<P>(
+        &self,
+        ptr: P,
+    ) -> Option<&Span>
+    where
+        P: AsRef<str>,
+    {
+        self.node(ptr.as_ref()).map(|node| &node.span)
+    }
+
+    /// Gets the [`Span`](struct.Span.html) of the key of the mapping entry a JSON Pointer path refers to
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::from_str;
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = from_str("list:\n  - \"quoted key\": 1")?;
+    /// assert_eq!(positions.key_span("/list/0/quoted key").map(|span| span.bytes.clone()), Some(10..22));
+    /// assert_eq!(positions.key_span("/list/0"), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn key_span<P>(
+        &self,
+        ptr: P,
+    ) -> Option<&Span>
+    where
+        P: AsRef<str>,
+    {
+        self.node(ptr.as_ref())?.key.as_ref()
+    }
+
+    /// Gets the [`Span`](struct.Span.html) of the `-` indicator of the block sequence item a
+    /// JSON Pointer path refers to, where items are spliced in or out of a sequence
+    ///
+    /// Items of flow sequences have no indicator
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{from_str, Position};
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = from_str("ports:\n  -   80\n  - # https\n    443\n")?;
+    /// assert_eq!(positions.get("/ports/0"), Some(&Position { line: 2, col: 6 }));
+    /// assert_eq!(
+    ///     positions.indicator_span("/ports/0").map(|span| span.start),
+    ///     Some(Position { line: 2, col: 2 })
+    /// );
+    /// assert_eq!(
+    ///     positions.indicator_span("/ports/1").map(|span| span.bytes.clone()),
+    ///     Some(18..19)
+    /// );
+    /// assert_eq!(positions.indicator_span("/ports"), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn indicator_span<P>(
+        &self,
+        ptr: P,
+    ) -> Option<&Span>
+    where
+        P: AsRef<str>,
+    {
+        self.node(ptr.as_ref())?.indicator.as_ref()
+    }
+
+    /// Position, span, kind, and scalar value of the node a pointer refers to,
+    /// including the first document's root
+    /// The YAML version plain scalars were resolved with
+    pub(crate) fn yaml_version(&self) -> YamlVersion {
+        self.yaml_version
+    }
+
+    pub(crate) fn describe(
+        &self,
+        ptr: &str,
+    ) -> Option<(&Position, &Span, NodeKind, Option<&str>)> {
+        let node = self.node(ptr)?;
+        let value = node.scalar.as_ref().map(|scalar| scalar.value.as_str());
+        Some((&node.position, &node.span, node.kind, value))
+    }
+
+    /// Position and span of the value a pointer refers to, with the root pointer
+    /// spanning the content of the first document when its root wasn't recorded
+    pub(crate) fn locate_pointer(
+        &self,
+        ptr: &str,
+    ) -> Option<(Position, Span)> {
+        match self.node(ptr) {
+            Some(node) => Some((node.position, node.span.clone())),
+            None if ptr.is_empty() => {
+                let span = self.documents.first()?.content.clone();
+                Some((span.start, span))
+            }
+            None => None,
+        }
+    }
+
+    /// Gets the original source text of the value a JSON Pointer path refers to,
+    /// including any quoting
+    ///
+    /// This requires the source to be retained with [`Options::keep_source`](struct.Options.html#method.keep_source)
+    pub fn text_at<P>(
+        &self,
+        ptr: P,
+    ) -> Option<&str>
+    where
+        P: AsRef<str>,
+    {
+        let source = self.source()?;
+        self.span(ptr)
+            .and_then(|span| source.get(span.bytes.clone()))
+    }
+
+    /// Lists keys that appeared more than once within the same mapping, in document order
+    ///
+    /// Like most YAML parsers, the last occurrence of a key wins and is the one indexed
+    ///
+    /// ```rust,edition2018
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("a: 1\nb: 2\na: 3")?;
+    /// let duplicate = &positions.duplicates()[0];
+    /// assert_eq!(duplicate.pointer, "/a");
+    /// assert_eq!((duplicate.first.line, duplicate.second.line), (1, 3));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn duplicates(&self) -> &[Duplicate] {
+        &self.duplicates
+    }
+
+    /// Lists content which was skipped while indexing, in document order
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{Position, Warning};
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("a: &a 1\nb:\n  *a : 2\n")?;
+    /// assert_eq!(
+    ///     positions.warnings(),
+    ///     &[Warning::AliasKey {
+    ///         pointer: "/b".into(),
+    ///         position: Position { line: 3, col: 2 }
+    ///     }]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    /// Lists the documents in the content, along with their boundaries
+    /// including their `---` and `...` markers
+    pub fn documents(&self) -> &[Document] {
+        &self.documents
+    }
+
+    /// Lists the `%YAML` and `%TAG` directives in the content, in document order
+    pub fn directives(&self) -> &[Directive] {
+        &self.directives
+    }
+
+    /// Lists the comments in the content, in document order
+    pub fn comments(&self) -> &[Comment] {
+        &self.comments
+    }
+
+    /// Lists the anchors, `&name`, in the content, in document order
+    pub fn anchors(&self) -> &[Anchor] {
+        &self.anchors
+    }
+
+    /// Lists the aliases, `*name`, in the content, in document order,
+    /// along with the anchors they refer to
+    pub fn aliases(&self) -> &[Alias] {
+        &self.aliases
+    }
+
+    /// Gets the comments associated with a JSON Pointer path: the block of comment lines
+    /// directly above it, followed by any comment trailing it on the same line
+    ///
+    /// ```rust,edition2018
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("# the image\n# to run\nimage: nginx # pinned")?;
+    /// let comments = positions
+    ///     .comments_for("/image")
+    ///     .into_iter()
+    ///     .map(|comment| comment.text.as_str())
+    ///     .collect::<Vec<_>>();
+    /// assert_eq!(comments, vec![" the image", " to run", " pinned"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn comments_for<P>(
+        &self,
+        ptr: P,
+    ) -> Vec<&Comment>
+    where
+        P: AsRef<str>,
+    {
+        let line = match self.get(ptr) {
+            Some(position) => position.line,
+            None => return Vec::new(),
+        };
+        let standalone = |line: usize| {
+            self.comments
+                .iter()
+                .find(|comment| comment.span.start.line == line && !comment.trailing)
+        };
+        let mut comments = (1..line).rev().map_while(standalone).collect::<Vec<_>>();
+        comments.reverse();
+        comments.extend(
+            self.comments
+                .iter()
+                .filter(|comment| comment.span.start.line == line && comment.trailing),
+        );
+        comments
+    }
+
+    /// Returns the source text content was loaded from, if it was retained
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_ref().map(|(text, _)| text.as_str())
+    }
+
+    pub(crate) fn source_lines(&self) -> Option<(&str, &LineIndex)> {
+        self.source
+            .as_ref()
+            .map(|(text, lines)| (text.as_str(), lines))
+    }
+
+    /// Gets the text of a line, excluding its line ending
+    ///
+    /// Lines are numbered the same as [`Position`](struct.Position.html) lines.
+    /// This requires the source to be retained with [`Options::keep_source`](struct.Options.html#method.keep_source)
+    pub fn line_text(
+        &self,
+        line: usize,
+    ) -> Option<&str> {
+        let (text, lines) = self.source.as_ref()?;
+        lines
+            .line_range(line.checked_sub(1)?, text)
+            .map(|range| &text[range])
+    }
+
+    /// Gets the numbered lines surrounding the position of a JSON Pointer path,
+    /// up to `n_lines` before and after it
+    ///
+    /// This requires the source to be retained with [`Options::keep_source`](struct.Options.html#method.keep_source)
+    pub fn context<P>(
+        &self,
+        ptr: P,
+        n_lines: usize,
+    ) -> Option<Vec<(usize, &str)>>
+    where
+        P: AsRef<str>,
+    {
+        let (_, lines) = self.source.as_ref()?;
+        let line = self.get(ptr)?.line;
+        let first = line.saturating_sub(n_lines).max(1);
+        let last = (line + n_lines).min(lines.len());
+        (first..=last)
+            .map(|line| self.line_text(line).map(|text| (line, text)))
+            .collect()
+    }
+
+    fn next(&mut self) -> Option<(Event, usize)> {
+        let event = self.events.get(self.pos).cloned();
+        if event.is_some() {
+            self.pos += 1;
+        }
+        event
+    }
+
+    /// Returns the pointer of the innermost node whose region encloses a line and column,
+    /// from its position to the end of its [`Span`](struct.Span.html)
+    ///
+    /// Unlike [`nearest`](#method.nearest), locations within a node's value, such as the
+    /// middle of a scalar or a flow collection, resolve to the node itself
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::from_str;
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = from_str("spec:\n  ports: [80, 443]\n  image: nginx\n")?;
+    /// assert_eq!(positions.enclosing(2, 17).map(|(ptr, _)| ptr), Some("/spec/ports/1"));
+    /// assert_eq!(positions.enclosing(2, 13).map(|(ptr, _)| ptr), Some("/spec/ports"));
+    /// assert_eq!(positions.enclosing(3, 11).map(|(ptr, _)| ptr), Some("/spec/image"));
+    /// assert_eq!(positions.enclosing(9, 0), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn enclosing(
+        &self,
+        line: usize,
+        col: usize,
+    ) -> Option<(&str, &Position)> {
+        let target = Position { line, col };
+        // the innermost node is the one with the smallest region enclosing the target
+        self.index
+            .iter()
+            .filter(|(_, node)| node.position <= target && target <= node.span.end)
+            .max_by_key(|(ptr, node)| {
+                (
+                    node.position,
+                    std::cmp::Reverse(&node.span.end),
+                    ptr.matches('/').count(),
+                )
+            })
+            .map(|(ptr, node)| (ptr.as_str(), &node.position))
+    }
+
+    /// Returns the pointer of the node starting nearest at or before a position,
+    /// preferring the innermost of nodes starting at the same place
+    ///
+    /// This describes where an error reported only by line and column occurred
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{from_str, Position};
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = from_str("spec:\n  image: nginx:latest\n")?;
+    /// assert_eq!(
+    ///     positions.nearest(&Position { line: 2, col: 14 }).map(|(ptr, _)| ptr),
+    ///     Some("/spec/image")
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn nearest(
+        &self,
+        position: &Position,
+    ) -> Option<(&str, &Position)> {
+        self.index
+            .iter()
+            .filter(|(_, node)| node.position <= *position)
+            .max_by_key(|(ptr, node)| (node.position, ptr.matches('/').count()))
+            .map(|(ptr, node)| (ptr.as_str(), &node.position))
+    }
+
+    /// Returns an iterator over the pointers of nodes starting on a line, from left to right
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::from_str;
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = from_str("name: web\nports: {http: 80, https: 443}\n")?;
+    /// assert_eq!(
+    ///     positions.on_line(2).map(|(ptr, _)| ptr).collect::<Vec<_>>(),
+    ///     vec!["/ports", "/ports/http", "/ports/https"]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn on_line(
+        &self,
+        line: usize,
+    ) -> impl Iterator<Item = (&str, &Position)> {
+        let nodes = self.ordered();
+        let first = nodes.partition_point(|(position, _)| position.line < line);
+        nodes[first..]
+            .iter()
+            .take_while(move |(position, _)| position.line == line)
+            .map(|(position, ptr)| (ptr.as_str(), position))
+    }
+
+    /// Returns an iterator over the pointers of nodes positioned within a selection, from
+    /// `start` up to but not including `end`, in document order
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{from_str, Position};
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = from_str("name: web\nports:\n  http: 80\n  https: 443\nimage: nginx\n")?;
+    /// assert_eq!(
+    ///     positions
+    ///         .in_range(&Position { line: 2, col: 0 }, &Position { line: 4, col: 0 })
+    ///         .map(|(ptr, _)| ptr)
+    ///         .collect::<Vec<_>>(),
+    ///     vec!["/ports", "/ports/http"]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn in_range(
+        &self,
+        start: &Position,
+        end: &Position,
+    ) -> impl Iterator<Item = (&str, &Position)> {
+        let nodes = self.ordered();
+        let first = nodes.partition_point(|(position, _)| position < start);
+        let last = nodes.partition_point(|(position, _)| position < end);
+        nodes[first..last.max(first)]
+            .iter()
+            .map(|(position, ptr)| (ptr.as_str(), position))
+    }
+
+    /// Returns an iterator over the pointers and positions for which `predicate` returns true,
+    /// ordered by pointer
+    ///
+    /// ```rust,edition2018
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("a: 1\nb:\n  c: 2\n")?;
+    /// assert_eq!(
+    ///     positions.iter_filtered(|_, position| position.line > 1).map(|(ptr, _)| ptr).collect::<Vec<_>>(),
+    ///     vec!["/b", "/b/c"]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_filtered<F>(
+        &self,
+        mut predicate: F,
+    ) -> impl Iterator<Item = (&str, &Position)>
+    where
+        F: FnMut(&str, &Position) -> bool,
+    {
+        self.iter()
+            .map(|(ptr, position)| (ptr.as_str(), position))
+            .filter(move |(ptr, position)| predicate(ptr, position))
+    }
+
+    /// Returns an iterator over the pointers and positions of everything nested within the node
+    /// a JSON Pointer path refers to, ordered by pointer. Only the nested nodes are visited
+    ///
+    /// ```rust,edition2018
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("a:\n  b: [1]\nab: 2\n")?;
+    /// assert_eq!(
+    ///     positions.under("/a").map(|(ptr, _)| ptr).collect::<Vec<_>>(),
+    ///     vec!["/a/b", "/a/b/0"]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn under<P>(
+        &self,
+        ptr: P,
+    ) -> impl Iterator<Item = (&str, &Position)>
+    where
+        P: AsRef<str>,
+    {
+        let ptr = ptr.as_ref();
+        let prefix = format!("{}/", self.entry(ptr).map_or(ptr, |(key, _)| key.as_str()));
+        self.index
+            .range(prefix.clone()..)
+            .take_while(move |(key, _)| key.starts_with(&prefix))
+            .map(|(key, node)| (key.as_str(), &node.position))
+    }
+
+    /// Returns an iterator over the pointers and positions of nodes nested `depth` deep,
+    /// starting at 0 for the root's children, ordered by pointer
+    ///
+    /// ```rust,edition2018
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("a:\n  b: [1]\nc: 2\n")?;
+    /// assert_eq!(
+    ///     positions.at_depth(1).map(|(ptr, _)| ptr).collect::<Vec<_>>(),
+    ///     vec!["/a/b"]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn at_depth(
+        &self,
+        depth: usize,
+    ) -> impl Iterator<Item = (&str, &Position)> {
+        self.iter_filtered(move |ptr, _| ptr.matches('/').count() == depth + 1)
+    }
+
+    /// Converts a one-based line and column, as most parsers report them,
+    /// into a position numbered as this table numbers them
+    #[cfg(any(feature = "serde_json", feature = "serde_yaml"))]
+    pub(crate) fn one_based(
+        &self,
+        line: usize,
+        col: usize,
+    ) -> Position {
+        Position {
+            line,
+            col: col.saturating_sub(1) + self.columns.base(),
+        }
+    }
+
+    /// Returns an iterator over positions, ordered by pointer
+    ///
+    /// Pointers are compared as strings, so `/items/10` comes before `/items/2`. See
+    /// [`iter_natural`](#method.iter_natural) for sequence items in numeric order and
+    /// [`iter_by_position`](#method.iter_by_position) for document order
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            inner: self.index.iter(),
+        }
+    }
+
+    /// Moves the pointers and positions of this table into a map, without copying them
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::Position;
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let map = lincolns::from_str("a: [1]\n")?.into_map();
+    /// assert_eq!(map.get("/a/0"), Some(&Position { line: 1, col: 4 }));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_map(self) -> BTreeMap<String, Position> {
+        self.into_iter().collect()
+    }
+
+    /// Moves the pointers and positions of this table into a vector, ordered by pointer,
+    /// without copying them
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::Position;
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// assert_eq!(
+    ///     lincolns::from_str("b: 1\na: 2\n")?.into_vec(),
+    ///     vec![
+    ///         ("/a".to_string(), Position { line: 2, col: 0 }),
+    ///         ("/b".to_string(), Position { line: 1, col: 0 }),
+    ///     ]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_vec(self) -> Vec<(String, Position)> {
+        self.into_iter().collect()
+    }
+
+    /// Returns an iterator over the [`Entry`](struct.Entry.html) of each indexed node, ordered by pointer,
+    /// holding everything known about it
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{from_str, NodeKind};
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = from_str("a:\n  b: 1")?;
+    /// let entry = positions.entries().last().unwrap();
+    /// assert_eq!(entry.pointer, "/a/b");
+    /// assert_eq!(entry.kind, NodeKind::Integer);
+    /// assert_eq!(entry.value, Some("1"));
+    /// assert_eq!(entry.key_span.map(|span| span.bytes.clone()), Some(5..6));
+    /// assert_eq!(entry.span.bytes, 8..9);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn entries(&self) -> impl Iterator<Item = Entry<'_>> {
+        self.index
+            .iter()
+            .map(|(pointer, node)| Self::entry_of(pointer, node))
+    }
+
+    /// The [`Entry`](struct.Entry.html) of the node indexed at a pointer
+    pub(crate) fn entry_at(
+        &self,
+        ptr: &str,
+    ) -> Option<Entry<'_>> {
+        self.entry(ptr)
+            .map(|(pointer, node)| Self::entry_of(pointer, node))
+    }
+
+    fn entry_of<'a>(
+        pointer: &'a str,
+        node: &'a Node,
+    ) -> Entry<'a> {
+        Entry {
+            pointer,
+            depth: pointer.matches('/').count().saturating_sub(1),
+            position: &node.position,
+            key_span: node.key.as_ref(),
+            indicator_span: node.indicator.as_ref(),
+            span: &node.span,
+            kind: node.kind,
+            value: node.scalar.as_ref().map(|scalar| scalar.value.as_str()),
+        }
+    }
+
+    /// Returns an iterator over indexed pointers, in order
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::from_str;
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = from_str("b: 1\na: [x]\n")?;
+    /// assert_eq!(positions.keys().collect::<Vec<_>>(), vec!["/a", "/a/0", "/b"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.index.keys().map(String::as_str)
+    }
+
+    /// Returns an iterator over positions, in the order of their pointers
+    pub fn positions(&self) -> impl Iterator<Item = &Position> {
+        self.index.values().map(|node| &node.position)
+    }
+
+    /// Returns an iterator over pointers and positions, ordered by pointer with tokens
+    /// compared as [`Pointer::compare`](struct.Pointer.html#method.compare) does, so sequence
+    /// items come in numeric order
+    ///
+    /// ```rust,edition2018
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("[a, b, c, d, e, f, g, h, i, j, k]")?;
+    /// let pointers = positions.iter_natural().map(|(ptr, _)| ptr).collect::<Vec<_>>();
+    /// assert_eq!(pointers[2..], ["/2", "/3", "/4", "/5", "/6", "/7", "/8", "/9", "/10"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_natural(&self) -> impl Iterator<Item = (&str, &Position)> {
+        let mut nodes = self
+            .index
+            .iter()
+            .map(|(ptr, node)| (ptr.as_str(), &node.position))
+            .collect::<Vec<_>>();
+        nodes.sort_by(|(a, _), (b, _)| Pointer::compare(a, b));
+        nodes.into_iter()
+    }
+
+    /// Returns an iterator over positions in document order, top to bottom and left to right
+    ///
+    /// Containers come before the entries they contain when both start at the same place
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::from_str;
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = from_str("zone: a\nname: b\n")?;
+    /// assert_eq!(
+    ///     positions.iter_by_position().map(|(ptr, _)| ptr).collect::<Vec<_>>(),
+    ///     vec!["/zone", "/name"]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_by_position(&self) -> impl Iterator<Item = (&str, &Position)> {
+        self.ordered()
+            .iter()
+            .map(|(position, ptr)| (ptr.as_str(), position))
+    }
+
+    /// Indexed pointers ordered by position, sorting them on first use
+    fn ordered(&self) -> &[(Position, String)] {
+        self.by_position.get_or_init(|| {
+            let mut nodes = self
+                .index
+                .iter()
+                .map(|(ptr, node)| (node.position, ptr.clone()))
+                .collect::<Vec<_>>();
+            nodes.sort_by_key(|(position, ptr)| (*position, ptr.matches('/').count()));
+            nodes
+        })
+    }
+
+    pub(crate) fn collect(
+        &mut self,
+        source: &Source,
+    ) {
+        self.collect_nodes(source);
+        self.count_lines(source.text());
+        let scanning = phase!("scan");
+        let scalars = std::mem::take(&mut self.scalars);
+        self.comments = comment::scan(source, &scalars);
+        self.directives = document::directives(source, &scalars);
+        self.anchors = anchor::scan(source, &scalars, &self.comments);
+        let aliases = self
+            .events
+            .iter()
+            .filter_map(|(event, offset)| match event {
+                Event::Alias(_) => Some(*offset),
+                _ => None,
+            });
+        self.aliases = anchor::resolve(source, aliases, &self.anchors);
+        scanning.finish(self.comments.len() + self.anchors.len() + self.aliases.len());
+    }
+
+    /// Indexes the nodes of the events received, leaving the comments, anchors, and the like
+    /// around them to [`collect`](#method.collect)
+    pub(crate) fn collect_nodes(
+        &mut self,
+        source: &Source,
+    ) {
+        let indexing = phase!("index");
+        self.columns = source.columns();
+        self.yaml_version = source.yaml_version();
+        self.line_ending = LineEnding::detect(source.text());
+        let mut document = 0;
+        let mut opened = None;
+        let mut content = None;
+        let mut path = Path::default();
+        while let Some((ev, offset)) = self.next() {
+            let start = offset;
+            match ev {
+                Event::DocumentStart => {
+                    document = start;
+                    opened = self.emit(start, source, || SpannedEvent::DocumentStart);
+                }
+                Event::DocumentEnd => {
+                    let content = content.take().unwrap_or(start..start);
+                    let document = Document::new(document, content, start, source);
+                    if let (Some(stream), Some(opened)) = (self.stream.as_mut(), opened) {
+                        // implicitly started documents start with their content
+                        if document.start.is_none() {
+                            stream[opened].1 = document.content.start;
+                        }
+                        stream.push((SpannedEvent::DocumentEnd, source.position(start)));
+                    }
+                    self.documents.push(document);
+                }
+                Event::Scalar(mut scalar) => {
+                    let bytes = scalar.locate(start, start, &mut self.scalars, source);
+                    self.emit_scalar(&path, &scalar, &bytes, source);
+                    self.root(
+                        start,
+                        &bytes,
+                        scalar.kind(source.yaml_version()),
+                        Some(scalar),
+                        source,
+                    );
+                    content = Some(bytes);
+                }
+                Event::SequenceStart => {
+                    let bytes = self.collect_seq(&mut path, start, source);
+                    self.root(bytes.start, &bytes, NodeKind::Sequence, None, source);
+                    content = Some(bytes);
+                }
+                Event::MappingStart(anchor) => {
+                    self.anchor(anchor, &path);
+                    let bytes = self.collect_map(&mut path, start, source);
+                    self.root(bytes.start, &bytes, NodeKind::Mapping, None, source);
+                    content = Some(bytes);
+                }
+                other => {
+                    debug!("unhandled {:?} in collect", other);
+                    self.warnings.push(Warning::Unhandled {
+                        pointer: String::new(),
+                        position: source.position(start),
+                    });
+                }
+            }
+        }
+        indexing.finish(self.index.len());
+    }
+
+    pub(crate) fn count_lines(
+        &mut self,
+        text: &str,
+    ) {
+        self.lines = text.lines().count();
+    }
+
+    /// Starts a root sequence's items at `first`, when indexing a shard of one
+    #[cfg(feature = "rayon")]
+    pub(crate) fn first_item(
+        &mut self,
+        first: usize,
+    ) {
+        self.first_item = first;
+    }
+
+    /// Adds the nodes of the shard of a root sequence following this one. Shards share
+    /// their root, though only the last spans to its end
+    #[cfg(feature = "rayon")]
+    pub(crate) fn absorb(
+        &mut self,
+        mut shard: Positions,
+    ) {
+        self.index.append(&mut shard.index);
+        // the last shard's `-` pointer is the root sequence's
+        self.appends.extend(shard.appends);
+        self.occurrences.extend(shard.occurrences);
+        self.duplicates.append(&mut shard.duplicates);
+        self.warnings.append(&mut shard.warnings);
+        self.root = shard.root;
+        self.documents = shard.documents;
+    }
+
+    /// Receives an event from a backend
+    pub(crate) fn push(
+        &mut self,
+        event: backend::Event,
+        offset: usize,
+    ) {
+        let event = match event {
+            backend::Event::DocumentStart => Event::DocumentStart,
+            backend::Event::DocumentEnd => Event::DocumentEnd,
+            backend::Event::Scalar { value, style, tag } => Event::Scalar(Scalar {
+                value,
+                style,
+                tag,
+                content: None,
+            }),
+            backend::Event::Alias { anchor } => Event::Alias(anchor),
+            backend::Event::SequenceStart { .. } => Event::SequenceStart,
+            backend::Event::SequenceEnd => Event::SequenceEnd,
+            backend::Event::MappingStart { anchor } => Event::MappingStart(anchor),
+            backend::Event::MappingEnd => Event::MappingEnd,
+        };
+        self.events.push((event, offset));
+    }
+
+    /// Starts recording events as content is indexed
+    pub(crate) fn record(&mut self) {
+        self.stream = Some(Vec::new());
+    }
+
+    /// Takes the events recorded while indexing
+    pub(crate) fn take_stream(&mut self) -> Vec<(SpannedEvent, Position)> {
+        self.stream.take().unwrap_or_default()
+    }
+
+    /// Records an event at `offset` when recording, returning its index
+    fn emit<F>(
+        &mut self,
+        offset: usize,
+        source: &Source,
+        event: F,
+    ) -> Option<usize>
+    where
+        F: FnOnce() -> SpannedEvent,
+    {
+        let stream = self.stream.as_mut()?;
+        stream.push((event(), source.position(offset)));
+        Some(stream.len() - 1)
+    }
+
+    fn emit_alias(
+        &mut self,
+        path: &Path,
+        bytes: &Range<usize>,
+        source: &Source,
+    ) {
+        self.emit(bytes.start, source, || SpannedEvent::Alias {
+            pointer: path.to_string(),
+            span: source.span(bytes.clone()),
+        });
+    }
+
+    fn emit_scalar(
+        &mut self,
+        path: &Path,
+        scalar: &Scalar,
+        bytes: &Range<usize>,
+        source: &Source,
+    ) {
+        self.emit(bytes.start, source, || SpannedEvent::Scalar {
+            pointer: path.to_string(),
+            value: scalar.value.clone(),
+            style: scalar.style,
+            tag: scalar.tag.clone(),
+            span: source.span(bytes.clone()),
+        });
+    }
+
+    /// Records the end of a container spanning `bytes`, positioning its recorded start event
+    /// now that the start of the container is known
+    fn emit_end<F>(
+        &mut self,
+        opened: Option<usize>,
+        bytes: &Range<usize>,
+        source: &Source,
+        event: F,
+    ) where
+        F: FnOnce(Span) -> SpannedEvent,
+    {
+        if let (Some(stream), Some(opened)) = (self.stream.as_mut(), opened) {
+            stream[opened].1 = source.position(bytes.start);
+            let end = source.position(bytes.end);
+            stream.push((event(source.span(bytes.clone())), end));
+        }
+    }
+
+    pub(crate) fn mark_bom(&mut self) {
+        self.bom = true;
+    }
+
+    /// Returns true if the text began with a byte order mark, which is excluded
+    /// from the content indexed and the columns of the first line
+    pub fn has_bom(&self) -> bool {
+        self.bom
+    }
+
+    /// Returns the line ending of the text, detected by its first line
+    ///
+    /// Columns and spans are the same either way, excluding the `\r` of `\r\n` line endings,
+    /// as editors count them
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// Returns a hash of the text this table indexed, for telling whether a stored table
+    /// still matches its file. The hash is 64-bit FNV-1a, stable across runs and platforms,
+    /// but not meant to resist deliberate collisions
+    pub fn content_hash(&self) -> u64 {
+        self.content_hash
+    }
+
+    /// Returns true if `text` differs from the text this table indexed, meaning the
+    /// table should be reloaded before its positions are relied on
+    ///
+    /// ```rust,edition2018
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("a: 1\n")?;
+    /// assert!(!positions.is_stale("a: 1\n"));
+    /// assert!(positions.is_stale("a: 2\n"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_stale<S>(
+        &self,
+        text: S,
+    ) -> bool
+    where
+        S: AsRef<str>,
+    {
+        content_hash(text.as_ref()) != self.content_hash
+    }
+
+    /// The number of lines of the text indexed, not counting an empty line after a final line ending
+    pub(crate) fn line_count(&self) -> usize {
+        self.lines
+    }
+
+    pub(crate) fn mark_content(
+        &mut self,
+        text: &str,
+    ) {
+        self.content_hash = content_hash(text);
+    }
+
+    /// Drops the events and other buffers only needed while indexing, and shrinks what
+    /// remains to fit, for tables kept around long after they're loaded
+    ///
+    /// Events recorded for [`events`](fn.events.html) streams are dropped along with them
+    ///
+    /// ```rust,edition2018
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let mut positions = lincolns::from_str("a: [1, 2, 3]\n")?;
+    /// let before = positions.approx_memory_bytes();
+    /// positions.shrink_to_fit();
+    /// assert!(positions.approx_memory_bytes() < before);
+    /// assert!(positions.contains("/a/2"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        self.events = Vec::new();
+        self.pos = 0;
+        self.prefixes = HashMap::new();
+        self.stream = None;
+        self.appends.shrink_to_fit();
+        self.duplicates.shrink_to_fit();
+        self.occurrences.shrink_to_fit();
+        self.warnings.shrink_to_fit();
+        self.scalars = Vec::new();
+        self.comments.shrink_to_fit();
+        self.anchors.shrink_to_fit();
+        self.directives.shrink_to_fit();
+        self.aliases.shrink_to_fit();
+        self.documents.shrink_to_fit();
+        if let Some((text, _)) = self.source.as_mut() {
+            text.shrink_to_fit();
+        }
+        if let Some(folded) = self.folded.as_mut() {
+            folded.shrink_to_fit();
+        }
+        #[cfg(feature = "hash-index")]
+        {
+            if let Some(hashed) = self.hashed.as_mut() {
+                hashed.shrink_to_fit();
+            }
+        }
+    }
+
+    /// Estimates the bytes this table occupies, itself and what it allocated, for
+    /// monitoring services holding many of them
+    ///
+    /// The estimate counts the capacity of buffers and the text of pointers, values, and
+    /// the like, but not the bookkeeping of the allocator and maps, so the true footprint
+    /// is somewhat larger
+    pub fn approx_memory_bytes(&self) -> usize {
+        fn vec<T>(items: &Vec<T>) -> usize {
+            items.capacity() * mem::size_of::<T>()
+        }
+        fn node(node: &Node) -> usize {
+            node.tag.as_ref().map_or(0, String::capacity) + node.scalar.as_ref().map_or(0, scalar)
+        }
+        fn scalar(scalar: &Scalar) -> usize {
+            scalar.value.capacity() + scalar.tag.as_ref().map_or(0, String::capacity)
+        }
+        fn entries<'a, K, V, I>(entries: I) -> usize
+        where
+            K: 'a,
+            V: 'a,
+            I: ExactSizeIterator<Item = (&'a K, &'a V)>,
+        {
+            entries.len() * (mem::size_of::<K>() + mem::size_of::<V>())
+        }
+
+        let mut bytes = mem::size_of::<Self>()
+            + vec(&self.events)
+            + entries(self.index.iter())
+            + self.root.as_ref().map_or(0, node)
+            + vec(&self.duplicates)
+            + entries(self.occurrences.iter())
+            + vec(&self.warnings)
+            + entries(self.appends.iter())
+            + entries(self.prefixes.iter())
+            + vec(&self.scalars)
+            + vec(&self.comments)
+            + vec(&self.anchors)
+            + vec(&self.directives)
+            + vec(&self.aliases)
+            + vec(&self.documents);
+        bytes += self
+            .events
+            .iter()
+            .map(|(event, _)| match event {
+                Event::Scalar(value) => scalar(value),
+                _ => 0,
+            })
+            .sum::<usize>();
+        bytes += self
+            .index
+            .iter()
+            .map(|(ptr, value)| ptr.capacity() + mem::size_of::<Node>() + node(value))
+            .sum::<usize>();
+        bytes += self
+            .duplicates
+            .iter()
+            .map(|duplicate| duplicate.pointer.capacity())
+            .chain(self.warnings.iter().map(|warning| warning.pointer().len()))
+            .chain(self.comments.iter().map(|comment| comment.text.capacity()))
+            .chain(self.anchors.iter().map(|anchor| anchor.name.capacity()))
+            .chain(self.aliases.iter().map(|alias| alias.name.capacity()))
+            .sum::<usize>();
+        bytes += self
+            .occurrences
+            .iter()
+            .map(|(ptr, positions)| ptr.capacity() + vec(positions))
+            .chain(self.appends.keys().map(String::capacity))
+            .chain(self.prefixes.values().map(String::capacity))
+            .sum::<usize>();
+        if let Some(stream) = &self.stream {
+            bytes += vec(stream);
+        }
+        if let Some(ordered) = self.by_position.get() {
+            bytes += vec(ordered) + ordered.iter().map(|(_, ptr)| ptr.capacity()).sum::<usize>();
+        }
+        if let Some((text, lines)) = &self.source {
+            bytes += text.capacity() + lines.len() * mem::size_of::<usize>();
+        }
+        if let Some(folded) = &self.folded {
+            bytes += entries(folded.iter())
+                + folded
+                    .iter()
+                    .map(|(lower, ptr)| lower.capacity() + ptr.capacity())
+                    .sum::<usize>();
+        }
+        #[cfg(feature = "hash-index")]
+        {
+            if let Some(hashed) = &self.hashed {
+                bytes +=
+                    entries(hashed.iter()) + hashed.keys().map(String::capacity).sum::<usize>();
+            }
+        }
+        bytes
+    }
+
+    /// Allows pointers to be looked up without regard to case
+    pub(crate) fn fold_case(&mut self) {
+        let mut folded = HashMap::new();
+        for ptr in self.index.keys() {
+            folded
+                .entry(ptr.to_lowercase())
+                .or_insert_with(|| ptr.clone());
+        }
+        self.folded = Some(folded);
+    }
+
+    /// Shares the nodes of the index with a hash map for faster exact lookups
+    #[cfg(feature = "hash-index")]
+    pub(crate) fn hash(&mut self) {
+        self.hashed = Some(
+            self.index
+                .iter()
+                .map(|(ptr, node)| (ptr.clone(), node.clone()))
+                .collect(),
+        );
+    }
+
+    /// Looks up the entry of a pointer exactly as it was indexed
+    fn exact(
+        &self,
+        ptr: &str,
+    ) -> Option<(&String, &Node)> {
+        #[cfg(feature = "hash-index")]
+        if let Some(hashed) = &self.hashed {
+            return hashed.get_key_value(ptr).map(|(ptr, node)| (ptr, &**node));
+        }
+        self.index
+            .get_key_value(ptr)
+            .map(|(ptr, node)| (ptr, &**node))
+    }
+
+    /// Looks up the entry of a pointer, falling back on one differing only in case
+    /// when loaded with [`Options::case_insensitive`](struct.Options.html#method.case_insensitive)
+    fn entry(
+        &self,
+        ptr: &str,
+    ) -> Option<(&String, &Node)> {
+        self.exact(ptr).or_else(|| {
+            let ptr = self.folded.as_ref()?.get(&ptr.to_lowercase())?;
+            self.index
+                .get_key_value(ptr)
+                .map(|(ptr, node)| (ptr, &**node))
+        })
+    }
+
+    /// Looks up the node of a pointer, the root pointer referring to the first document's root
+    fn node(
+        &self,
+        ptr: &str,
+    ) -> Option<&Node> {
+        if ptr.is_empty() {
+            return self.root.as_ref();
+        }
+        self.entry(ptr).map(|(_, node)| node)
+    }
+
+    /// Copies the nodes of this table, indexed within `old` text, into a table of them within
+    /// `new` text given where each pointer and byte offset moved to, `offset` being told whether
+    /// an offset ends a span. Nodes whose pointer moves to `None` are left out, and a root moved
+    /// to a pointer is indexed by it. As with [`subtree`](#method.subtree), the table's
+    /// comments, anchors, and the like aren't copied
+    pub(crate) fn remap<P, O>(
+        &self,
+        old: &str,
+        new: &str,
+        pointer: P,
+        offset: O,
+    ) -> Positions
+    where
+        P: Fn(&str) -> Option<String>,
+        O: Fn(usize, bool) -> usize,
+    {
+        let (old_lines, new_lines) = (LineIndex::new(old), LineIndex::new(new));
+        let columns = self.columns;
+        let position = |position: &Position, end: bool| {
+            let moved = offset(old_lines.offset_with(old, position, columns), end);
+            new_lines.position_with(new, moved, columns)
+        };
+        let span = |span: &Span| {
+            let bytes = offset(span.bytes.start, false)..offset(span.bytes.end, true);
+            Span {
+                start: new_lines.position_with(new, bytes.start, columns),
+                end: new_lines.position_with(new, bytes.end, columns),
+                bytes,
+            }
+        };
+        let node = |node: &Node| Node {
+            position: position(&node.position, false),
+            span: span(&node.span),
+            key: node.key.as_ref().map(&span),
+            indicator: node.indicator.as_ref().map(&span),
+            kind: node.kind,
+            scalar: node.scalar.clone().map(|mut scalar| {
+                scalar.content = scalar.content.map(|content| position(&content, false));
+                scalar
+            }),
+            tag: node.tag.clone(),
+        };
+        let mut positions = Positions {
+            bom: self.bom,
+            line_ending: self.line_ending,
+            columns,
+            content_hash: content_hash(new),
+            lines: new.lines().count(),
+            source: self
+                .source
+                .as_ref()
+                .map(|_| (new.to_owned(), new_lines.clone())),
+            ..Positions::default()
+        };
+        let nodes = self
+            .root
+            .iter()
+            .map(|root| ("", root))
+            .chain(self.index.iter().map(|(ptr, node)| (ptr.as_str(), &**node)));
+        for (ptr, indexed) in nodes {
+            match pointer(ptr) {
+                Some(ptr) if ptr.is_empty() => positions.root = Some(node(indexed)),
+                Some(ptr) => {
+                    positions.index.insert(ptr, Arc::new(node(indexed)));
+                }
+                None => (),
+            }
+        }
+        for (ptr, append) in &self.appends {
+            if let Some(parent) = ptr.strip_suffix("/-").and_then(&pointer) {
+                positions
+                    .appends
+                    .insert(format!("{}/-", parent), position(append, true));
+            }
+        }
+        if self.folded.is_some() {
+            positions.fold_case();
+        }
+        #[cfg(feature = "hash-index")]
+        if self.hashed.is_some() {
+            positions.hash();
+        }
+        positions
+    }
+
+    /// Indexes the nodes of another table already remapped into this one's text, keeping the
+    /// keys of any nodes they replace and widening the collections containing them to fit
+    pub(crate) fn graft(
+        &mut self,
+        other: Positions,
+    ) {
+        for (ptr, mut node) in other.index {
+            if let Some(Node {
+                key: Some(key),
+                position,
+                ..
+            }) = self.index.get(&ptr).map(|node| &**node)
+            {
+                if node.key.is_none() {
+                    let node = Arc::make_mut(&mut node);
+                    node.key = Some(key.clone());
+                    node.position = *position;
+                }
+            }
+            let mut parent = ptr.as_str();
+            while let Some(idx) = parent.rfind('/') {
+                parent = &parent[..idx];
+                let ancestor = if parent.is_empty() {
+                    self.root.as_mut()
+                } else {
+                    self.index.get_mut(parent).map(Arc::make_mut)
+                };
+                if let Some(ancestor) = ancestor {
+                    let outer = &mut ancestor.span;
+                    let inner = node.key.as_ref().unwrap_or(&node.span);
+                    if inner.bytes.start < outer.bytes.start {
+                        outer.start = inner.start;
+                        outer.bytes.start = inner.bytes.start;
+                    }
+                    if node.span.bytes.end > outer.bytes.end {
+                        outer.end = node.span.end;
+                        outer.bytes.end = node.span.bytes.end;
+                    }
+                }
+            }
+            if let Some((parent, _)) = ptr.rsplit_once('/') {
+                if let Some(append) = self.appends.get_mut(&format!("{}/-", parent)) {
+                    *append = node.span.end.max(*append);
+                }
+            }
+            self.index.insert(ptr, node);
+        }
+        self.by_position.take();
+        if self.folded.is_some() {
+            self.fold_case();
+        }
+        #[cfg(feature = "hash-index")]
+        if self.hashed.is_some() {
+            self.hash();
+        }
+    }
+
+    pub(crate) fn retain_source(
+        &mut self,
+        text: String,
+        lines: LineIndex,
+    ) {
+        self.source = Some((text, lines));
+    }
+
+    /// Indexes a node, returning it for further annotation
+    fn insert(
+        &mut self,
+        path: &Path,
+        offset: usize,
+        bytes: Range<usize>,
+        kind: NodeKind,
+        scalar: Option<Scalar>,
+        source: &Source,
+    ) -> &mut Node {
+        let node = self.node_of(path.as_str(), offset, bytes, kind, scalar, source);
+        let node = match self.index.entry(path.to_string()) {
+            btree_map::Entry::Occupied(mut entry) => {
+                entry.insert(Arc::new(node));
+                entry.into_mut()
+            }
+            btree_map::Entry::Vacant(entry) => entry.insert(Arc::new(node)),
+        };
+        Arc::make_mut(node)
+    }
+
+    /// Records the root of the first document
+    fn root(
+        &mut self,
+        offset: usize,
+        bytes: &Range<usize>,
+        kind: NodeKind,
+        scalar: Option<Scalar>,
+        source: &Source,
+    ) {
+        if self.documents.is_empty() && self.root.is_none() {
+            self.root = Some(self.node_of("", offset, bytes.clone(), kind, scalar, source));
+        }
+    }
+
+    /// Builds the node of a pointer, reading the tag of collections. The members of sets,
+    /// mappings tagged `!!set`, are spanned by their keys, their values being empty
+    fn node_of(
+        &mut self,
+        pointer: &str,
+        offset: usize,
+        bytes: Range<usize>,
+        kind: NodeKind,
+        scalar: Option<Scalar>,
+        source: &Source,
+    ) -> Node {
+        let tag = match kind {
+            NodeKind::Mapping | NodeKind::Sequence => {
+                source.collection_tag(bytes.start).map(String::from)
+            }
+            _ => None,
+        };
+        if kind == NodeKind::Mapping
+            && matches!(
+                tag.as_deref(),
+                Some("!!set") | Some("!<tag:yaml.org,2002:set>")
+            )
+        {
+            let prefix = format!("{}/", pointer);
+            for (ptr, member) in self.index.range_mut(prefix.clone()..) {
+                if !ptr.starts_with(&prefix) {
+                    break;
+                }
+                if let (false, Some(key)) = (ptr[prefix.len()..].contains('/'), &member.key) {
+                    let span = key.clone();
+                    Arc::make_mut(member).span = span;
+                }
+            }
+        }
+        Node {
+            position: source.position(offset),
+            span: source.span(bytes),
+            key: None,
+            indicator: None,
+            kind,
+            scalar,
+            tag,
+        }
+    }
+
+    /// Records a repeated key at `offset`. Unless the first occurrence is kept, its entry
+    /// is dropped, along with anything nested within it, ahead of indexing the new one
+    fn duplicate(
+        &mut self,
+        path: &Path,
+        offset: usize,
+        source: &Source,
+    ) {
+        let pointer = path.to_string();
+        let first = match self.index.get(&pointer) {
+            Some(node) => node.position,
+            None => return,
+        };
+        if source.duplicate_keys() != DuplicateKeys::First {
+            self.index.remove(&pointer);
+            let nested = format!("{}/", pointer);
+            self.index.retain(|ptr, _| !ptr.starts_with(&nested));
+        }
+        let second = source.position(offset);
+        self.occur(&pointer, first, second);
+        self.duplicates.push(Duplicate {
+            pointer,
+            first,
+            second,
+        });
+    }
+
+    /// Records that a pointer defined at `first` is defined again at `second`
+    fn occur(
+        &mut self,
+        pointer: &str,
+        first: Position,
+        second: Position,
+    ) {
+        let occurrences = self
+            .occurrences
+            .entry(pointer.to_owned())
+            .or_insert_with(|| vec![first]);
+        occurrences.push(second);
+        occurrences.sort();
+        occurrences.dedup();
+    }
+
+    /// Byte range of a container starting at `start` given the end of its last child
+    fn container(
+        start: usize,
+        last: usize,
+        source: &Source,
+    ) -> Range<usize> {
+        if source.is_flow(start) {
+            start..source.flow_end(last.max(start + 1))
+        } else {
+            start..last.max(start)
+        }
+    }
+
+    fn collect_seq(
+        &mut self,
+        path: &mut Path,
+        start: usize,
+        source: &Source,
+    ) -> Range<usize> {
+        let opened = self.emit(start, source, || SpannedEvent::SequenceStart {
+            pointer: path.to_string(),
+        });
+        let bytes = self.collect_items(path, start, source);
+        self.emit_end(opened, &bytes, source, |span| SpannedEvent::SequenceEnd {
+            pointer: path.to_string(),
+            span,
+        });
+        bytes
+    }
+
+    fn collect_items(
+        &mut self,
+        path: &mut Path,
+        start: usize,
+        source: &Source,
+    ) -> Range<usize> {
+        let mut last = start;
+        let first = if path.is_root() { self.first_item } else { 0 };
+        let mut index = first;
+        // indicators are searched for past the previous one, which empty items don't end after
+        let (block, mut indicated) = (!source.is_flow(start), start);
+        while let Some((ev, offset)) = self.next() {
+            let from = last.max(indicated);
+            let parent = path.push(index);
+            let node = match ev {
+                Event::SequenceEnd => {
+                    path.pop(parent);
+                    // an empty flow sequence is appended to just within its brackets
+                    let end = if index == first && source.is_flow(start) {
+                        start + 1
+                    } else {
+                        last.max(start)
+                    };
+                    self.appends
+                        .insert(format!("{}/-", path), source.position(end));
+                    return Self::container(start, last, source);
+                }
+                Event::Scalar(mut scalar) => {
+                    let bytes = scalar.locate(offset, last, &mut self.scalars, source);
+                    last = bytes.end;
+                    self.emit_scalar(path, &scalar, &bytes, source);
+                    self.insert(
+                        path,
+                        offset,
+                        bytes,
+                        scalar.kind(source.yaml_version()),
+                        Some(scalar),
+                        source,
+                    )
+                }
+                Event::Alias(_) => {
+                    let bytes = source.alias(offset);
+                    last = bytes.end;
+                    self.emit_alias(path, &bytes, source);
+                    self.insert(path, offset, bytes, NodeKind::Alias, None, source)
+                }
+                Event::MappingStart(anchor) => {
+                    self.anchor(anchor, path);
+                    let bytes = self.collect_map(path, offset, source);
+                    last = bytes.end;
+                    self.insert(path, bytes.start, bytes, NodeKind::Mapping, None, source)
+                }
+                Event::SequenceStart => {
+                    let bytes = self.collect_seq(path, offset, source);
+                    last = bytes.end;
+                    self.insert(path, bytes.start, bytes, NodeKind::Sequence, None, source)
+                }
+                other => {
+                    path.pop(parent);
+                    debug!("unhandled {:?} in collect_items", other);
+                    self.warnings.push(Warning::Unhandled {
+                        pointer: path.to_string(),
+                        position: source.position(offset),
+                    });
+                    break;
+                }
+            };
+            let indicator = if block { source.indicator(from) } else { None };
+            if let Some(indicator) = indicator {
+                node.indicator = Some(source.span(indicator..indicator + 1));
+                indicated = indicator + 1;
+            }
+            path.pop(parent);
+            index += 1;
+        }
+        start..last
+    }
+
+    fn collect_map(
+        &mut self,
+        path: &mut Path,
+        start: usize,
+        source: &Source,
+    ) -> Range<usize> {
+        let opened = self.emit(start, source, || SpannedEvent::MappingStart {
+            pointer: path.to_string(),
+        });
+        let bytes = self.collect_entries(path, start, source);
+        self.emit_end(opened, &bytes, source, |span| SpannedEvent::MappingEnd {
+            pointer: path.to_string(),
+            span,
+        });
+        bytes
+    }
+
+    fn collect_entries(
+        &mut self,
+        path: &mut Path,
+        start: usize,
+        source: &Source,
+    ) -> Range<usize> {
+        let flow = source.is_flow(start);
+        let mut start = start;
+        let mut last = start;
+        let mut keys = HashSet::new();
+        let mut merges = Vec::new();
+        while let Some((ev, offset)) = self.next() {
+            let (key, key_bytes) = match ev {
+                Event::MappingEnd => {
+                    self.merge(path, &keys, merges, source);
+                    return Self::container(start, last, source);
+                }
+                Event::SequenceStart | Event::MappingStart(_) => {
+                    // complex keys are indexed under a flow-style rendering of their content
+                    if !flow && last == start {
+                        start = start.min(offset);
+                    }
+                    let key = self.complex_key(ev);
+                    if let Some((_, end)) = self.events.get(self.pos - 1) {
+                        last = last.max(end + usize::from(source.is_flow(offset)));
+                    }
+                    match key {
+                        Some(key) => (key, offset..last),
+                        None => {
+                            self.warnings.push(Warning::ComplexKey {
+                                pointer: path.to_string(),
+                                position: source.position(offset),
+                            });
+                            self.skip();
+                            continue;
+                        }
+                    }
+                }
+                Event::Alias(_) => {
+                    debug!("skipping entry with an alias key in collect_entries");
+                    self.warnings.push(Warning::AliasKey {
+                        pointer: path.to_string(),
+                        position: source.position(offset),
+                    });
+                    last = source.alias(offset).end;
+                    self.skip();
+                    continue;
+                }
+                Event::Scalar(Scalar {
+                    value: key, style, ..
+                }) => {
+                    let key_bytes = source.scalar(offset, style, &key, last);
+                    self.scalars.push(key_bytes.clone());
+                    if !flow && last == start {
+                        // block mappings are marked at their first key's separator
+                        start = start.min(key_bytes.start);
+                    }
+                    last = key_bytes.end;
+                    if key == "<<"
+                        && style == ScalarStyle::Plain
+                        && source.merge_keys() != MergeKeys::Literal
+                    {
+                        if let Some(aliases) = self.merge_aliases(source) {
+                            last = aliases
+                                .iter()
+                                .fold(last, |last, (_, alias)| last.max(alias.end));
+                            merges.push(Merge {
+                                site: offset,
+                                aliases,
+                            });
+                            continue;
+                        }
+                    }
+                    (key, key_bytes)
+                }
+                other => {
+                    debug!("unhandled {:?} in collect_entries", other);
+                    self.warnings.push(Warning::Unhandled {
+                        pointer: path.to_string(),
+                        position: source.position(offset),
+                    });
+                    break;
+                }
+            };
+            let parent = path.push(&key);
+            self.emit(key_bytes.start, source, || SpannedEvent::Key {
+                pointer: path.to_string(),
+                value: key.clone(),
+                span: source.span(key_bytes.clone()),
+            });
+            let duplicate = !keys.insert(key.clone());
+            if duplicate {
+                self.duplicate(path, offset, source);
+            }
+            // later occurrences of keys are collected but set aside when keeping the first
+            let kept = (duplicate && source.duplicate_keys() == DuplicateKeys::First).then(|| {
+                (
+                    std::mem::take(&mut self.index),
+                    std::mem::take(&mut self.appends),
+                    std::mem::take(&mut self.prefixes),
+                    std::mem::take(&mut self.occurrences),
+                )
+            });
+            let (bytes, kind, scalar) = match self.next() {
+                Some((Event::Scalar(mut scalar), at)) => {
+                    let bytes = scalar.locate(at, last, &mut self.scalars, source);
+                    self.emit_scalar(path, &scalar, &bytes, source);
+                    (bytes, scalar.kind(source.yaml_version()), Some(scalar))
+                }
+                Some((Event::Alias(_), at)) => {
+                    let bytes = source.alias(at);
+                    self.emit_alias(path, &bytes, source);
+                    (bytes, NodeKind::Alias, None)
+                }
+                Some((Event::MappingStart(anchor), at)) => {
+                    self.anchor(anchor, path);
+                    (self.collect_map(path, at, source), NodeKind::Mapping, None)
+                }
+                Some((Event::SequenceStart, at)) => {
+                    (self.collect_seq(path, at, source), NodeKind::Sequence, None)
+                }
+                other => {
+                    debug!("unhandled {:?} in collect_entries", other);
+                    if let Some((_, at)) = other {
+                        self.warnings.push(Warning::Unhandled {
+                            pointer: path.to_string(),
+                            position: source.position(at),
+                        });
+                    }
+                    (last..last, NodeKind::Null, None)
+                }
+            };
+            last = last.max(bytes.end);
+            match kept {
+                Some((index, appends, prefixes, occurrences)) => {
+                    self.index = index;
+                    self.appends = appends;
+                    self.prefixes = prefixes;
+                    self.occurrences = occurrences;
+                }
+                None => {
+                    let node = self.insert(path, offset, bytes, kind, scalar, source);
+                    node.key = Some(source.span(key_bytes));
+                }
+            }
+            path.pop(parent);
+        }
+        self.merge(path, &keys, merges, source);
+        start..last
+    }
+
+    /// Consumes the remaining events of a sequence or mapping used as a key,
+    /// rendering its content in flow style. Keys containing aliases have no rendering
+    fn complex_key(
+        &mut self,
+        start: Event,
+    ) -> Option<String> {
+        let mut key = String::new();
+        let mut rendered = true;
+        let mut stack = vec![(start, 0)];
+        while let Some((container, items)) = stack.pop() {
+            let (open, close, end) = match container {
+                Event::SequenceStart => ('[', ']', Event::SequenceEnd),
+                _ => ('{', '}', Event::MappingEnd),
+            };
+            if items == 0 {
+                key.push(open);
+            }
+            let (event, _) = match self.next() {
+                Some(next) => next,
+                None => break,
+            };
+            if event == end {
+                key.push(close);
+                continue;
+            }
+            if items > 0 {
+                key.push_str(if open == '{' && items % 2 == 1 {
+                    ": "
+                } else {
+                    ", "
+                });
+            }
+            stack.push((container, items + 1));
+            match event {
+                Event::Scalar(scalar) => key.push_str(&scalar.value),
+                Event::SequenceStart | Event::MappingStart(_) => stack.push((event, 0)),
+                _ => rendered = false,
+            }
+        }
+        Some(key).filter(|_| rendered)
+    }
+
+    /// Consumes the next node, along with anything nested within it, without indexing it
+    fn skip(&mut self) {
+        let mut depth = 0;
+        while let Some((event, _)) = self.next() {
+            match event {
+                Event::SequenceStart | Event::MappingStart(_) => depth += 1,
+                Event::SequenceEnd | Event::MappingEnd => depth -= 1,
+                _ => (),
+            }
+            if depth <= 0 {
+                break;
+            }
+        }
+    }
+
+    /// Consumes the value of a merge key when it is an alias or a sequence of aliases,
+    /// returning the anchor ids referred to along with the byte range of each alias
+    fn merge_aliases(
+        &mut self,
+        source: &Source,
+    ) -> Option<Vec<(usize, Range<usize>)>> {
+        let alias = |id: usize, offset: usize| (id, source.alias(offset));
+        let (aliases, next) = match self.events.get(self.pos)? {
+            (Event::Alias(id), offset) => (vec![alias(*id, *offset)], self.pos + 1),
+            (Event::SequenceStart, _) => {
+                let mut aliases = Vec::new();
+                let mut pos = self.pos + 1;
+                loop {
+                    match self.events.get(pos)? {
+                        (Event::Alias(id), offset) => aliases.push(alias(*id, *offset)),
+                        (Event::SequenceEnd, _) => break (aliases, pos + 1),
+                        _ => return None,
+                    }
+                    pos += 1;
+                }
+            }
+            _ => return None,
+        };
+        self.pos = next;
+        Some(aliases)
+    }
+
+    /// Indexes the entries of anchored mappings merged into the mapping at `path` by `<<` keys.
+    /// Keys the mapping defines itself take precedence, followed by those of earlier merges
+    fn merge(
+        &mut self,
+        path: &Path,
+        keys: &HashSet<String>,
+        merges: Vec<Merge>,
+        source: &Source,
+    ) {
+        let prefix = Self::prefix(path);
+        let mut taken = keys.clone();
+        for Merge { site, aliases } in merges {
+            for (id, alias) in aliases {
+                let anchor = match self.prefixes.get(&id) {
+                    Some(anchor) => anchor,
+                    None => continue,
+                };
+                let entries = self
+                    .index
+                    .range(anchor.clone()..)
+                    .take_while(|(ptr, _)| ptr.starts_with(anchor.as_str()))
+                    .map(|(ptr, node)| (ptr[anchor.len()..].to_owned(), node.clone()))
+                    .collect::<Vec<_>>();
+                let mut merged = HashSet::new();
+                for (rest, mut node) in entries {
+                    let token = rest.split('/').next().unwrap_or_default();
+                    let key = path::unescape(token);
+                    if taken.contains(key.as_ref()) {
+                        // the mapping's own key, or an earlier merge's, overrides this entry
+                        let ptr = format!("{}{}", prefix, rest);
+                        if rest == token {
+                            if let Some(defined) = self.index.get(&ptr).map(|node| node.position) {
+                                let merged = if source.merge_keys() == MergeKeys::Site {
+                                    source.position(site)
+                                } else {
+                                    node.position
+                                };
+                                self.occur(&ptr, defined, merged);
+                            }
+                        }
+                        continue;
+                    }
+                    merged.insert(key.into_owned());
+                    if source.merge_keys() == MergeKeys::Site {
+                        let node = Arc::make_mut(&mut node);
+                        node.position = source.position(site);
+                        node.span = source.span(alias.clone());
+                    }
+                    self.index.insert(format!("{}{}", prefix, rest), node);
+                }
+                taken.extend(merged);
+            }
+        }
+    }
+
+    /// Records the pointer prefix of an anchored mapping so merge keys may refer to it
+    fn anchor(
+        &mut self,
+        id: usize,
+        path: &Path,
+    ) {
+        if id > 0 {
+            self.prefixes.insert(id, Self::prefix(path));
+        }
+    }
+
+    /// The prefix shared by pointers of nodes nested within `path`
+    fn prefix(path: &Path) -> String {
+        format!("{}/", path)
+    }
+}
+
+/// Gets a node's position given its JSON Pointer path, panicking when there isn't one
+///
+/// The panic message suggests pointers resembling the missing one. Use
+/// [`Positions::get`](struct.Positions.html#method.get) for pointers which may not exist
+///
+/// ```rust,edition2018
+/// use lincolns::Position;
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let positions = lincolns::from_str("name: web\nports:\n  - 80\n")?;
+/// assert_eq!(positions["/ports/0"], Position { line: 3, col: 4 });
+/// # Ok(())
+/// # }
+/// ```
+impl<P> Index<P> for Positions
+where
+    P: AsRef<str>,
+{
+    type Output = Position;
+
+    fn index(
+        &self,
+        ptr: P,
+    ) -> &Position {
+        let ptr = ptr.as_ref();
+        match self.get(ptr) {
+            Some(position) => position,
+            None => match self.suggest(ptr, 3).as_slice() {
+                [] => panic!("no node at {:?}", ptr),
+                suggestions => panic!(
+                    "no node at {:?}, did you mean {}?",
+                    ptr,
+                    suggestions
+                        .iter()
+                        .map(|suggestion| format!("{:?}", suggestion))
+                        .collect::<Vec<_>>()
+                        .join(" or ")
+                ),
+            },
+        }
+    }
+}
+
+impl fmt::Debug for Positions {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Lists each pointer and its `line:col`, one per line, with positions aligned
+///
+/// ```rust,edition2018
+/// # fn main() -> lincolns::Result<()>  {
+/// let positions = lincolns::from_str("name: web\nports:\n  - 80\n")?;
+/// assert_eq!(
+///     positions.to_string(),
+///     "/name     1:0\n/ports    2:0\n/ports/0  3:4\n"
+/// );
+/// # Ok(())
+/// # }
+/// ```
+impl fmt::Display for Positions {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        let width = self
+            .keys()
+            .map(|ptr| ptr.chars().count())
+            .max()
+            .unwrap_or_default();
+        for (ptr, position) in self {
+            writeln!(f, "{:width$}  {}", ptr, position, width = width)?;
+        }
+        Ok(())
+    }
+}
+
+/// An iterator over the pointers and positions of a [`Positions`](struct.Positions.html) table,
+/// ordered by pointer
+pub struct Iter<'a> {
+    inner: btree_map::Iter<'a, String, Arc<Node>>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a String, &'a Position);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(ptr, node)| (ptr, &node.position))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next_back()
+            .map(|(ptr, node)| (ptr, &node.position))
+    }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+/// An owning iterator over the pointers and positions of a [`Positions`](struct.Positions.html) table,
+/// ordered by pointer
+pub struct IntoIter {
+    inner: btree_map::IntoIter<String, Arc<Node>>,
+}
+
+impl Iterator for IntoIter {
+    type Item = (String, Position);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(ptr, node)| (ptr, node.position))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for IntoIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next_back()
+            .map(|(ptr, node)| (ptr, node.position))
+    }
+}
+
+impl ExactSizeIterator for IntoIter {}
+
+impl<'a> IntoIterator for &'a Positions {
+    type Item = (&'a String, &'a Position);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for Positions {
+    type Item = (String, Position);
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.index.into_iter(),
+        }
+    }
+}
+
+/// Builds a table from pointers and positions rather than parsed content
+///
+/// Having no source, each node's span is empty and at its position, and its kind is
+/// [`NodeKind::Null`](enum.NodeKind.html#variant.Null)
+///
+/// ```rust,edition2018
+/// use lincolns::{Position, Positions};
+///
+/// let positions = vec![("/name".to_string(), Position { line: 1, col: 0 })]
+///     .into_iter()
+///     .collect::<Positions>();
+/// assert_eq!(positions.get("/name"), Some(&Position { line: 1, col: 0 }));
+/// ```
+impl FromIterator<(String, Position)> for Positions {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (String, Position)>,
+    {
+        let mut positions = Positions::default();
+        positions.extend(iter);
+        positions
+    }
+}
+
+/// Adds pointers and positions to a table, replacing any nodes already indexed at the same pointers
+impl Extend<(String, Position)> for Positions {
+    fn extend<I>(
+        &mut self,
+        iter: I,
+    ) where
+        I: IntoIterator<Item = (String, Position)>,
+    {
+        self.by_position.take();
+        for (pointer, position) in iter {
+            if let Some(folded) = self.folded.as_mut() {
+                folded
+                    .entry(pointer.to_lowercase())
+                    .or_insert_with(|| pointer.clone());
+            }
+            let node = Arc::new(Node {
+                position,
+                span: Span {
+                    start: position,
+                    end: position,
+                    bytes: 0..0,
+                },
+                key: None,
+                indicator: None,
+                kind: NodeKind::Null,
+                scalar: None,
+                tag: None,
+            });
+            #[cfg(feature = "hash-index")]
+            if let Some(hashed) = self.hashed.as_mut() {
+                hashed.insert(pointer.clone(), node.clone());
+            }
+            self.index.insert(pointer, node);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        from_str, from_str_with, ColumnUnit, Comment, Duplicate, DuplicateKeys, Error, LineEnding,
+        MergeKeys, NodeKind, Options, Pointer, Position, Positions, Result, ScalarStyle, Value,
+        Warning, YamlVersion,
+    };
+
+    #[test]
+    fn extend_replaces_existing_nodes() -> Result<()> {
+        let mut positions = from_str("a: 1\nb: 2\n")?;
+        positions.extend(vec![
+            ("/b".to_string(), Position { line: 9, col: 1 }),
+            ("/c".to_string(), Position { line: 10, col: 1 }),
+        ]);
+        assert_eq!(positions.get("/a"), Some(&Position { line: 1, col: 0 }));
+        assert_eq!(positions.get("/b"), Some(&Position { line: 9, col: 1 }));
+        assert_eq!(positions.kind("/b"), Some(NodeKind::Null));
+        assert_eq!(
+            positions.span("/c").map(|span| span.start),
+            Some(Position { line: 10, col: 1 })
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "no node at \"/jobs/buidl\", did you mean \"/jobs/build\"?")]
+    fn indexing_a_missing_pointer_panics_with_suggestions() {
+        let positions = from_str("jobs:\n  build: {}\n  test: {}\n").expect("invalid yaml");
+        assert_eq!(positions["/jobs/build"], Position { line: 2, col: 2 });
+        let _ = positions["/jobs/buidl"];
+    }
+
+    #[test]
+    fn retain_prunes_the_index() -> Result<()> {
+        let mut positions = from_str_with(
+            "A: 1\nb:\n  c: 2\n  d: 3\n",
+            Options::default().case_insensitive(true),
+        )?;
+        positions.retain(|ptr, position| ptr.starts_with("/b") && position.line < 4);
+        assert_eq!(positions.keys().collect::<Vec<_>>(), vec!["/b", "/b/c"]);
+        assert!(!positions.contains("/a"));
+        Ok(())
+    }
+
+    #[test]
+    fn subtrees() -> Result<()> {
+        let positions = from_str_with(
+            "a:\n  b: 1\n  c: [2]\nab: 3\n",
+            Options::default().keep_source(true),
+        )?;
+        let subtree = positions.subtree("/a");
+        assert_eq!(
+            subtree.keys().collect::<Vec<_>>(),
+            vec!["/a", "/a/b", "/a/c", "/a/c/0"]
+        );
+        assert_eq!(subtree.text_at("/a/c"), Some("[2]"));
+        let rebased = positions.subtree_rebased("/a");
+        assert_eq!(rebased.keys().collect::<Vec<_>>(), vec!["/b", "/c", "/c/0"]);
+        assert_eq!(rebased.get("/c/0"), Some(&Position { line: 3, col: 6 }));
+        assert_eq!(positions.subtree_rebased("").len(), positions.len());
+        assert!(positions.subtree("/missing").is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn position_displays_line_and_col() -> Result<()> {
+        let positions = from_str_with("a: 1", Options::default().one_based(true))?;
+        assert_eq!(
+            positions.get("/a").map(ToString::to_string),
+            Some("1:1".into())
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn position_serializes() {
+        let position = Position { line: 2, col: 4 };
+        let json = serde_json::to_string(&position).unwrap();
+        assert_eq!(json, r#"{"line":2,"col":4}"#);
+        assert_eq!(serde_json::from_str::<Position>(&json).unwrap(), position);
+    }
+
+    #[test]
+    fn warns_of_skipped_keys() -> Result<()> {
+        let positions = from_str("a: &k 1\nb:\n  ? [x, *k]\n  : 2\n  *k : 3\n  c: 4\n")?;
+        assert_eq!(
+            positions.warnings(),
+            &[
+                Warning::ComplexKey {
+                    pointer: "/b".into(),
+                    position: Position { line: 3, col: 4 }
+                },
+                Warning::AliasKey {
+                    pointer: "/b".into(),
+                    position: Position { line: 5, col: 2 }
+                }
+            ]
+        );
+        assert!(positions.contains("/b/c"));
+        assert!(from_str("a: [1]")?.warnings().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn hashes_content() -> Result<()> {
+        assert_eq!(from_str("")?.content_hash(), 0xcbf2_9ce4_8422_2325);
+        let positions = from_str("a: 1\n")?;
+        assert_eq!(positions.content_hash(), from_str("a: 1\n")?.content_hash());
+        assert!(positions.is_stale("a: 1\r\n"));
+        let mut editor = crate::edit::Editor::new("a: 1\n")?;
+        editor.set("/a", "2")?;
+        assert!(!editor.positions().is_stale(editor.text()));
+        Ok(())
+    }
+
+    #[test]
+    fn crlf_line_endings() -> Result<()> {
+        let positions = from_str_with(
+            "a: |\r\n  x\r\nb: plain # note\r\nc:\r\n",
+            Options::default().keep_source(true),
+        )?;
+        assert_eq!(positions.line_ending(), LineEnding::CrLf);
+        assert_eq!(
+            positions.span("/a").map(|span| span.end),
+            Some(Position { line: 2, col: 3 })
+        );
+        assert_eq!(positions.text_at("/b"), Some("plain"));
+        assert_eq!(positions.line_text(3), Some("b: plain # note"));
+        assert_eq!(
+            positions.comments()[0].span.end,
+            Position { line: 3, col: 15 }
+        );
+        assert_eq!(positions.get("/c"), Some(&Position { line: 4, col: 0 }));
+        assert_eq!(from_str("a: 1\n")?.line_ending(), LineEnding::Lf);
+        Ok(())
+    }
+
+    #[test]
+    fn counts_children() -> Result<()> {
+        let positions = from_str("a:\n  b: [1, 2, 3]\n  c: {}\n  d: x\n")?;
+        assert_eq!(positions.children_count(""), Some(1));
+        assert_eq!(positions.children_count("/a"), Some(3));
+        assert_eq!(positions.children_count("/a/b"), Some(3));
+        assert_eq!(positions.children_count("/a/c"), Some(0));
+        assert_eq!(positions.children_count("/a/d"), None);
+        assert_eq!(positions.children_count("/missing"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn lists_children_in_document_order() -> Result<()> {
+        let positions = from_str("a:\n  z: 1\n  y:\n    x: 2\nb: [3, 4]\n")?;
+        let children = |ptr| positions.children(ptr);
+        assert_eq!(
+            children("/a"),
+            vec![
+                ("/a/z", &Position { line: 2, col: 2 }),
+                ("/a/y", &Position { line: 3, col: 2 })
+            ]
+        );
+        assert_eq!(
+            children("")
+                .into_iter()
+                .map(|(ptr, _)| ptr)
+                .collect::<Vec<_>>(),
+            vec!["/a", "/b"]
+        );
+        assert_eq!(
+            children("/b")
+                .into_iter()
+                .map(|(ptr, _)| ptr)
+                .collect::<Vec<_>>(),
+            vec!["/b/0", "/b/1"]
+        );
+        assert!(children("/a/z").is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn len_and_contains() -> Result<()> {
+        let positions = from_str("a:\n  b: [1]\n")?;
+        assert_eq!(positions.len(), 3);
+        assert!(!positions.is_empty());
+        assert!(positions.contains("/a/b/0"));
+        assert!(!positions.contains("/a/c"));
+        assert!(from_str("")?.is_empty());
+        assert!(from_str("scalar")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn keys_and_positions() -> Result<()> {
+        let positions = from_str("b: 1\na: 2\n")?;
+        assert_eq!(positions.keys().collect::<Vec<_>>(), vec!["/a", "/b"]);
+        assert_eq!(
+            positions.positions().collect::<Vec<_>>(),
+            vec![&Position { line: 2, col: 0 }, &Position { line: 1, col: 0 }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn iter_by_position_follows_the_document() -> Result<()> {
+        let positions = from_str("b:\n  - z: 1\n    y: 2\na: [x]\n")?;
+        assert_eq!(
+            positions
+                .iter_by_position()
+                .map(|(ptr, _)| ptr)
+                .collect::<Vec<_>>(),
+            vec!["/b", "/b/0", "/b/0/z", "/b/0/y", "/a", "/a/0"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn finds_keys_at_any_depth() -> Result<()> {
+        let positions = from_str("'a/b': 1\nb: [b]\nc:\n  b: {b: 2}\n")?;
+        assert_eq!(
+            positions
+                .find_key("b")
+                .map(|entry| entry.pointer)
+                .collect::<Vec<_>>(),
+            vec!["/b", "/c/b", "/c/b/b"]
+        );
+        assert_eq!(positions.find_key("a/b").count(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn on_line_lists_nodes_starting_on_a_line() -> Result<()> {
+        let mut positions = from_str("a:\n  - b: 1\n    c: 2\n")?;
+        assert_eq!(
+            positions.on_line(2).collect::<Vec<_>>(),
+            vec![
+                ("/a/0", &Position { line: 2, col: 4 }),
+                ("/a/0/b", &Position { line: 2, col: 4 })
+            ]
+        );
+        assert_eq!(positions.on_line(4).count(), 0);
+        positions.retain(|ptr, _| ptr != "/a/0/b");
+        positions.extend(vec![("/d".to_owned(), Position { line: 2, col: 9 })]);
+        assert_eq!(
+            positions.on_line(2).map(|(ptr, _)| ptr).collect::<Vec<_>>(),
+            vec!["/a/0", "/d"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn in_range_lists_nodes_between_positions() -> Result<()> {
+        let positions = from_str("a: 1\nb: [2, 3]\nc: 4\n")?;
+        let between = |start: Position, end: Position| {
+            positions
+                .in_range(&start, &end)
+                .map(|(ptr, _)| ptr)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            between(Position { line: 2, col: 4 }, Position { line: 3, col: 0 }),
+            vec!["/b/0", "/b/1"]
+        );
+        assert_eq!(
+            between(Position { line: 3, col: 0 }, Position { line: 1, col: 0 }),
+            Vec::<&str>::new()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn case_insensitive_lookups() -> Result<()> {
+        let yaml = "Name: a\nname: b\nSpec:\n  Replicas: 3\n";
+        let positions = from_str(yaml)?;
+        assert_eq!(positions.get("/spec/replicas"), None);
+        let positions = from_str_with(yaml, Options::default().case_insensitive(true))?;
+        assert_eq!(
+            positions.get("/spec/REPLICAS"),
+            Some(&Position { line: 4, col: 2 })
+        );
+        assert_eq!(positions.get("/name"), Some(&Position { line: 2, col: 0 }));
+        assert_eq!(positions.get("/NAME"), Some(&Position { line: 1, col: 0 }));
+        assert_eq!(
+            positions.get_closest("/SPEC/missing"),
+            Some(("/Spec", &Position { line: 3, col: 0 }))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn text_at_requires_retained_source() -> Result<()> {
+        let positions = from_str("foo: bar")?;
+        assert!(positions.span("/foo").is_some());
+        assert_eq!(positions.text_at("/foo"), None);
+        assert_eq!(positions.line_text(1), None);
+        Ok(())
+    }
+
+    #[test]
+    fn get_value_returns_scalar_values() -> Result<()> {
+        let positions = from_str("foo:\n  bar: \"a\\tb\"\n  baz: [1]\n  nil:\n")?;
+        assert_eq!(
+            positions.get_value("/foo/bar"),
+            Some(("a\tb", &Position { line: 2, col: 2 }))
+        );
+        assert_eq!(
+            positions.get_value("/foo/baz/0"),
+            Some(("1", &Position { line: 3, col: 8 }))
+        );
+        assert_eq!(positions.get_value("/foo"), None);
+        assert_eq!(positions.get_value("/foo/baz"), None);
+        assert_eq!(
+            positions.get_value("/foo/nil").map(|(value, _)| value),
+            Some("~")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn kinds() -> Result<()> {
+        let positions = from_str(
+            "s: foo\nq: \"1\"\ni: 0x1f\nf: .inf\nb: true\nn:\nt: !!str 2\nm: &x {a: 1}\nl: [1]\nr: *x\n",
+        )?;
+        let kind = |ptr| positions.kind(ptr);
+        assert_eq!(kind("/s"), Some(NodeKind::String));
+        assert_eq!(kind("/q"), Some(NodeKind::String));
+        assert_eq!(kind("/i"), Some(NodeKind::Integer));
+        assert_eq!(kind("/f"), Some(NodeKind::Float));
+        assert_eq!(kind("/b"), Some(NodeKind::Bool));
+        assert_eq!(kind("/n"), Some(NodeKind::Null));
+        assert_eq!(kind("/t"), Some(NodeKind::String));
+        assert_eq!(kind("/m"), Some(NodeKind::Mapping));
+        assert_eq!(kind("/l"), Some(NodeKind::Sequence));
+        assert_eq!(kind("/l/0"), Some(NodeKind::Integer));
+        assert_eq!(kind("/r"), Some(NodeKind::Alias));
+        Ok(())
+    }
+
+    #[test]
+    fn kinds_by_yaml_version() -> Result<()> {
+        let yaml = "- yes\n- Off\n- y\n- True\n- NULL\n- 017\n- 0o17\n- 0b101\n- -0x1_f\n- 1_000\n- 190:20:30\n- 1e5\n- 1.5e+3\n- 20:30.5\n- -.INF\n- inf\n- 09\n";
+        let kinds = |version| -> Result<Vec<_>> {
+            let positions = from_str_with(yaml, Options::default().yaml_version(version))?;
+            Ok((0..17)
+                .map(|idx| positions.kind(format!("/{}", idx)).expect("no item"))
+                .collect())
+        };
+        use NodeKind::*;
+        assert_eq!(
+            kinds(YamlVersion::V1_2)?,
+            vec![
+                String, String, String, Bool, Null, Integer, Integer, String, String, String,
+                String, Float, Float, String, Float, String, Integer
+            ]
+        );
+        assert_eq!(
+            kinds(YamlVersion::V1_1)?,
+            vec![
+                Bool, Bool, Bool, Bool, Null, Integer, String, Integer, Integer, Integer, Integer,
+                String, Float, Float, Float, String, String
+            ]
+        );
+        let values = |version| -> Result<Vec<_>> {
+            let positions = from_str_with(yaml, Options::default().yaml_version(version))?;
+            Ok(match positions.annotated("").expect("no document").value {
+                Value::Sequence(items) => items.into_iter().map(|item| item.value).collect(),
+                other => panic!("unexpected {:?}", other),
+            })
+        };
+        let legacy = values(YamlVersion::V1_1)?;
+        assert_eq!(legacy[0], Value::Bool(true));
+        assert_eq!(legacy[5], Value::Integer(15));
+        assert_eq!(legacy[8], Value::Integer(-31));
+        assert_eq!(legacy[10], Value::Integer(685_230));
+        assert_eq!(legacy[13], Value::Float(1_230.5));
+        let core = values(YamlVersion::V1_2)?;
+        assert_eq!(core[5], Value::Integer(17));
+        assert_eq!(core[6], Value::Integer(15));
+        assert_eq!(core[14], Value::Float(f64::NEG_INFINITY));
+        Ok(())
+    }
+
+    #[test]
+    fn indicators_of_block_sequence_items() -> Result<()> {
+        let text = "- a\n- - b\n  - c\n-\n- &x {k: 1}\n- !!str d # - no\n-   # -x\n  e\n";
+        let positions = from_str(text)?;
+        let indicator = |ptr| {
+            positions
+                .indicator_span(ptr)
+                .map(|span| (span.start.line, span.start.col))
+        };
+        assert_eq!(indicator("/0"), Some((1, 0)));
+        assert_eq!(indicator("/1"), Some((2, 0)));
+        assert_eq!(indicator("/1/0"), Some((2, 2)));
+        assert_eq!(indicator("/1/1"), Some((3, 2)));
+        assert_eq!(indicator("/2"), Some((4, 0)));
+        assert_eq!(indicator("/3"), Some((5, 0)));
+        assert_eq!(indicator("/4"), Some((6, 0)));
+        assert_eq!(indicator("/5"), Some((7, 0)));
+        assert_eq!(positions.get("/5"), Some(&Position { line: 8, col: 2 }));
+        assert_eq!(indicator("/6"), None);
+        let positions = from_str("f:\n- [g, h]\n")?;
+        assert_eq!(
+            positions
+                .indicator_span("/f/0")
+                .map(|span| span.bytes.clone()),
+            Some(3..4)
+        );
+        assert_eq!(positions.indicator_span("/f/0/0"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn ancestors_of_nodes() -> Result<()> {
+        let positions = from_str_with(
+            "Jobs:\n  a/b:\n    - x\n",
+            Options::default().case_insensitive(true),
+        )?;
+        assert_eq!(
+            positions
+                .ancestors("/jobs/a~1b/0")
+                .into_iter()
+                .map(|(ptr, position)| (ptr, position.line))
+                .collect::<Vec<_>>(),
+            vec![("/Jobs", 1), ("/Jobs/a~1b", 2), ("/Jobs/a~1b/0", 3)]
+        );
+        assert!(positions.ancestors("/jobs/missing").is_empty());
+        assert!(positions.ancestors("").is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn get_closest_walks_up_to_existing_ancestors() -> Result<()> {
+        let positions = from_str("a:\n  - b: 1\n")?;
+        assert_eq!(
+            positions.get_closest("/a/0/b"),
+            Some(("/a/0/b", &Position { line: 2, col: 4 }))
+        );
+        assert_eq!(
+            positions.get_closest("/a/0/c/d"),
+            Some(("/a/0", &Position { line: 2, col: 4 }))
+        );
+        assert_eq!(
+            positions.get_closest("/a/1"),
+            Some(("/a", &Position { line: 1, col: 0 }))
+        );
+        assert_eq!(
+            positions.get_closest("/z"),
+            Some(("", &Position { line: 1, col: 0 }))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn resolves_the_root_pointer() -> Result<()> {
+        let positions = from_str("# config\n\nname: web\nports: [80]\n")?;
+        assert_eq!(positions.get(""), Some(&Position { line: 3, col: 0 }));
+        assert_eq!(positions.kind(""), Some(NodeKind::Mapping));
+        assert_eq!(
+            positions.span("").map(|span| span.end),
+            Some(Position { line: 4, col: 11 })
+        );
+        assert!(positions.contains(""));
+        assert!(!positions.keys().any(str::is_empty));
+        let ports = positions.subtree_rebased("/ports");
+        assert_eq!(ports.get(""), positions.get("/ports"));
+        assert_eq!(ports.kind(""), Some(NodeKind::Sequence));
+        let scalar = from_str("--- !!str 42\n")?;
+        assert_eq!(scalar.get(""), Some(&Position { line: 1, col: 10 }));
+        assert_eq!(
+            scalar.get_value(""),
+            Some(("42", &Position { line: 1, col: 10 }))
+        );
+        assert_eq!(from_str("")?.get(""), None);
+        Ok(())
+    }
+
+    #[test]
+    fn addresses_empty_keys() -> Result<()> {
+        let positions = from_str("\"\": 1\na:\n  \"\": {\"\": 2}\n")?;
+        assert_eq!(
+            positions.keys().collect::<Vec<_>>(),
+            vec!["/", "/a", "/a/", "/a//"]
+        );
+        assert_eq!(positions.get("/"), Some(&Position { line: 1, col: 0 }));
+        assert_eq!(positions.get("/a//"), Some(&Position { line: 3, col: 7 }));
+        assert_eq!(
+            positions.get_value("/a//").map(|(value, _)| value),
+            Some("2")
+        );
+        assert_eq!(positions.children_count(""), Some(2));
+        assert_eq!(positions.children_count("/a/"), Some(1));
+        assert_eq!(
+            positions.get(Pointer::root().join("a").join("")),
+            positions.get("/a/")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn lists_entries_with_key_spans() -> Result<()> {
+        let positions = from_str("a: {'b': [x]}\n")?;
+        let entries = positions
+            .entries()
+            .map(|entry| {
+                (
+                    entry.pointer,
+                    entry.depth,
+                    entry.key_span.map(|span| span.bytes.clone()),
+                    entry.span.bytes.clone(),
+                    entry.value,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            vec![
+                ("/a", 0, Some(0..1), 3..13, None),
+                ("/a/b", 1, Some(4..7), 9..12, None),
+                ("/a/b/0", 2, None, 10..11, Some("x")),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn iterates_nodes_under_pointers() -> Result<()> {
+        let positions = from_str("a/b:\n  c: 1\nd: 2\n")?;
+        let under = |ptr: &str| positions.under(ptr).map(|(ptr, _)| ptr).collect::<Vec<_>>();
+        assert_eq!(under(""), vec!["/a~1b", "/a~1b/c", "/d"]);
+        assert_eq!(under("/a~1b"), vec!["/a~1b/c"]);
+        assert!(under("/d").is_empty());
+        assert!(under("/missing").is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn escapes_tokens_of_keys() -> Result<()> {
+        let positions = from_str("a/b: 1\na:\n  b: 2\nc~1: 3\nc/: 4\n")?;
+        assert_eq!(positions.get("/a~1b"), Some(&Position { line: 1, col: 0 }));
+        assert_eq!(positions.get("/a/b"), Some(&Position { line: 3, col: 2 }));
+        assert_eq!(positions.get("/c~01"), Some(&Position { line: 4, col: 0 }));
+        assert_eq!(positions.get("/c~1"), Some(&Position { line: 5, col: 0 }));
+        assert_eq!(positions.get("/c~1/"), None);
+        assert!(positions.warnings().is_empty());
+        assert_eq!(positions.find_key("a/b").count(), 1);
+        Ok(())
+    }
+
+    #[cfg(feature = "hash-index")]
+    #[test]
+    fn hashed_lookups_follow_changes() -> Result<()> {
+        let mut positions =
+            from_str_with("a: 1\nb:\n  c~d: 2\n", Options::default().hash_index(true))?;
+        assert_eq!(
+            positions.get("/b/c~0d"),
+            Some(&Position { line: 3, col: 2 })
+        );
+        positions.retain(|ptr, _| ptr != "/a");
+        assert_eq!(positions.get("/a"), None);
+        positions.extend(vec![("/e".to_string(), Position { line: 9, col: 0 })]);
+        assert_eq!(positions.get("/e"), Some(&Position { line: 9, col: 0 }));
+        assert_eq!(
+            positions.subtree("/b").get("/b/c~0d"),
+            Some(&Position { line: 3, col: 2 })
+        );
+        let hashed = positions.hashed.as_ref().expect("not hashed");
+        assert!(hashed
+            .iter()
+            .all(|(ptr, node)| std::sync::Arc::ptr_eq(node, &positions.index[ptr])));
+        Ok(())
+    }
+
+    #[test]
+    fn resolves_append_tokens() -> Result<()> {
+        let positions = from_str("a:\n  - 1\n  - [x, y]\nb: []\nc: {-: z}\n")?;
+        assert_eq!(positions.get("/a/-"), Some(&Position { line: 3, col: 10 }));
+        assert_eq!(positions.get("/a/1/-"), Some(&Position { line: 3, col: 9 }));
+        assert_eq!(positions.get("/b/-"), Some(&Position { line: 4, col: 4 }));
+        assert_eq!(positions.get("/c/-"), Some(&Position { line: 5, col: 4 }));
+        assert_eq!(positions.get("/z/-"), None);
+        assert_eq!(
+            positions.get_closest("/a/-/b"),
+            Some(("/a/-", &Position { line: 3, col: 10 }))
+        );
+        assert_eq!(
+            from_str("- 1\n- 2\n")?.get("/-"),
+            Some(&Position { line: 2, col: 3 })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn indexes_sequence_items_of_any_kind() -> Result<()> {
+        let positions = from_str(
+            "- &x {a: 1}
+- [2]
+- b: 3
+- *x
+",
+        )?;
+        assert_eq!(positions.get("/0"), Some(&Position { line: 1, col: 5 }));
+        assert_eq!(positions.kind("/0"), Some(NodeKind::Mapping));
+        assert_eq!(positions.kind("/1"), Some(NodeKind::Sequence));
+        assert_eq!(positions.get("/2"), Some(&Position { line: 3, col: 2 }));
+        assert_eq!(positions.kind("/3"), Some(NodeKind::Alias));
+        assert_eq!(positions.get("/2/b"), Some(&Position { line: 3, col: 2 }));
+        Ok(())
+    }
+
+    #[test]
+    fn styles_and_tags() -> Result<()> {
+        let positions = from_str(
+            "a: x\nb: 'x'\nc: \"x\"\nd: |\n  x\ne: >\n  x\nf: !<tag:x> x\ng: ! x\nh: [x]\n",
+        )?;
+        assert_eq!(positions.style("/a"), Some(ScalarStyle::Plain));
+        assert_eq!(positions.style("/b"), Some(ScalarStyle::SingleQuoted));
+        assert_eq!(positions.style("/c"), Some(ScalarStyle::DoubleQuoted));
+        assert_eq!(positions.style("/d"), Some(ScalarStyle::Literal));
+        assert_eq!(positions.style("/e"), Some(ScalarStyle::Folded));
+        assert_eq!(positions.style("/h"), None);
+        assert_eq!(positions.tag("/a"), None);
+        assert_eq!(positions.tag("/f"), Some("!<tag:x>"));
+        assert_eq!(positions.tag("/g"), Some("!"));
+        Ok(())
+    }
+
+    #[test]
+    fn sets_and_ordered_maps() -> Result<()> {
+        let text = "s: !!set\n  ? a\n  ? b # c !x\nf: &f !!set {x, y}\no: !!omap\n  - k: 1\n  - j: 2\nm: # d !t\n  n: [!!pairs [p: 1]]\n";
+        let positions = from_str_with(text, Options::default().keep_source(true))?;
+        assert_eq!(positions.tag("/s"), Some("!!set"));
+        assert_eq!(positions.tag("/f"), Some("!!set"));
+        assert_eq!(positions.tag("/o"), Some("!!omap"));
+        assert_eq!(positions.tag("/m"), None);
+        assert_eq!(positions.tag("/m/n"), None);
+        assert_eq!(positions.tag("/m/n/0"), Some("!!pairs"));
+        assert_eq!(positions.text_at("/s/a"), Some("a"));
+        assert_eq!(positions.text_at("/s/b"), Some("b"));
+        assert_eq!(positions.text_at("/f/y"), Some("y"));
+        assert_eq!(positions.kind("/f/y"), Some(NodeKind::Null));
+        assert_eq!(positions.text_at("/o/1"), Some("j: 2"));
+        assert_eq!(positions.get("/o/1/j"), Some(&Position { line: 7, col: 4 }));
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_keys_keep_the_last_entry() -> Result<()> {
+        let positions = from_str("a:\n  x: 1\n  y: 2\nb:\n  a: 1\na:\n  x: 3\n")?;
+        assert_eq!(
+            positions.duplicates(),
+            &[Duplicate {
+                pointer: "/a".into(),
+                first: Position { line: 1, col: 0 },
+                second: Position { line: 6, col: 0 },
+            }]
+        );
+        assert_eq!(positions.get("/a/x"), Some(&Position { line: 7, col: 2 }));
+        assert_eq!(positions.get("/a/y"), None);
+        assert_eq!(
+            positions.get_all("/a"),
+            &[Position { line: 1, col: 0 }, Position { line: 6, col: 0 }]
+        );
+        assert_eq!(positions.get_all("/a/x"), &[Position { line: 7, col: 2 }]);
+        Ok(())
+    }
+
+    #[test]
+    fn duplicate_keys_policy() -> Result<()> {
+        let text = "a:\n  x: 1\n  y: 2\na:\n  x: 3\n";
+        let positions = from_str_with(
+            text,
+            Options::default().duplicate_keys(DuplicateKeys::First),
+        )?;
+        assert_eq!(positions.duplicates().len(), 1);
+        assert_eq!(positions.get("/a/x"), Some(&Position { line: 2, col: 2 }));
+        assert_eq!(positions.get("/a/y"), Some(&Position { line: 3, col: 2 }));
+        match from_str_with(
+            text,
+            Options::default().duplicate_keys(DuplicateKeys::Error),
+        ) {
+            Err(Error::DuplicateKey {
+                pointer,
+                first,
+                second,
+            }) => {
+                assert_eq!(pointer, "/a");
+                assert_eq!(first, Position { line: 1, col: 0 });
+                assert_eq!(second, Position { line: 4, col: 0 });
+            }
+            other => panic!("expected duplicate key error but found {:?}", other.err()),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn kept_first_keys_ignore_later_values_entirely() -> Result<()> {
+        let first = || Options::default().duplicate_keys(DuplicateKeys::First);
+        let positions = from_str_with("a: [1]\na: [1, 2, 3]\n", first())?;
+        assert_eq!(positions.get("/a/-"), Some(&Position { line: 1, col: 5 }));
+        let positions = from_str_with("a:\n  x: 1\na:\n  x: 2\n  x: 3\n", first())?;
+        assert_eq!(positions.get_all("/a/x"), &[Position { line: 2, col: 2 }]);
+        let positions = from_str_with("[{\"k\": 0, \"k\": [0]}]", first())?;
+        assert_eq!(positions.get("/0/k"), Some(&Position { line: 1, col: 2 }));
+        assert_eq!(positions.get("/0/k/-"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn merge_keys() -> Result<()> {
+        let text = "base: &base\n  a: 1\n  b: 2\n  nested:\n    c: 3\nextra: &extra\n  b: 4\n  d: 5\njob:\n  <<: [*extra, *base]\n  a: 0\n";
+        let positions = from_str(text)?;
+        assert!(positions.get("/job/<<").is_some());
+        assert_eq!(positions.get("/job/d"), None);
+
+        let positions = from_str_with(text, Options::default().merge_keys(MergeKeys::Anchor))?;
+        assert_eq!(positions.get("/job/<<"), None);
+        assert_eq!(
+            positions.get("/job/a"),
+            Some(&Position { line: 11, col: 2 })
+        );
+        assert_eq!(positions.get("/job/b"), Some(&Position { line: 7, col: 2 }));
+        assert_eq!(positions.get("/job/d"), Some(&Position { line: 8, col: 2 }));
+        assert_eq!(
+            positions.get("/job/nested/c"),
+            Some(&Position { line: 5, col: 4 })
+        );
+        assert_eq!(
+            positions.get_all("/job/a"),
+            &[Position { line: 2, col: 2 }, Position { line: 11, col: 2 }]
+        );
+        assert_eq!(
+            positions.get_all("/job/b"),
+            &[Position { line: 3, col: 2 }, Position { line: 7, col: 2 }]
+        );
+        assert_eq!(positions.get_all("/job/d"), &[Position { line: 8, col: 2 }]);
+
+        let positions = from_str_with(text, Options::default().merge_keys(MergeKeys::Site))?;
+        assert_eq!(
+            positions.get("/job/nested/c"),
+            Some(&Position { line: 10, col: 2 })
+        );
+        assert_eq!(
+            positions.get_all("/job/a"),
+            &[Position { line: 10, col: 2 }, Position { line: 11, col: 2 }]
+        );
+        assert_eq!(
+            positions.get_value("/job/b").map(|(value, _)| value),
+            Some("4")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn complex_keys() -> Result<()> {
+        let positions =
+            from_str("? [a, {b: c}]\n: 1\n? {x: [y]}\n: {z: 2}\n&k k: 3\n? *k\n: 4\nafter: 5\n")?;
+        assert_eq!(
+            positions.get("/[a, {b: c}]"),
+            Some(&Position { line: 1, col: 2 })
+        );
+        assert_eq!(
+            positions.get("/{x: [y]}/z"),
+            Some(&Position { line: 4, col: 3 })
+        );
+        assert_eq!(positions.get("/after"), Some(&Position { line: 8, col: 0 }));
+        assert_eq!(positions.iter().count(), 5);
+        Ok(())
+    }
+
+    #[test]
+    fn flow_collections_index_like_block_collections() -> Result<()> {
+        let flow = from_str("{a: 1, b: [2, {c: 3}], d: {e: [[4]], f: {}}}")?;
+        let block = from_str("a: 1\nb:\n  - 2\n  - c: 3\nd:\n  e:\n    - - 4\n  f: {}\n")?;
+        let pointers = |positions: &Positions| {
+            positions
+                .iter()
+                .map(|(ptr, _)| ptr.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(pointers(&flow), pointers(&block));
+        assert_eq!(flow.get("/b/1/c"), Some(&Position { line: 1, col: 15 }));
+        assert_eq!(flow.get("/d/e/0/0"), Some(&Position { line: 1, col: 32 }));
+        Ok(())
+    }
+
+    #[test]
+    fn flow_collections_span_their_brackets() -> Result<()> {
+        let positions = from_str_with(
+            "outer:\n  inner: [1, {k: v, }, ] # ]\n  next: { a: b, # }\n  }\n",
+            Options::default().keep_source(true),
+        )?;
+        assert_eq!(positions.text_at("/outer/inner"), Some("[1, {k: v, }, ]"));
+        assert_eq!(positions.text_at("/outer/next"), Some("{ a: b, # }\n  }"));
+        assert_eq!(
+            positions.get("/outer/inner/1/k"),
+            Some(&Position { line: 2, col: 14 })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn scalar_lines() -> Result<()> {
+        let positions = from_str(
+            "steps:\n  - run: |\n\n      echo a\n        echo b\n    other: >2\n       x\n      y\n  - plain\n",
+        )?;
+        let line = |ptr, n| positions.scalar_line(ptr, n);
+        assert_eq!(line("/steps/0/run", 0), Some(Position { line: 3, col: 6 }));
+        assert_eq!(line("/steps/0/run", 2), Some(Position { line: 5, col: 6 }));
+        assert_eq!(line("/steps/0/run", 3), None);
+        assert_eq!(
+            line("/steps/0/other", 1),
+            Some(Position { line: 8, col: 6 })
+        );
+        assert_eq!(line("/steps/1", 0), None);
+        Ok(())
+    }
+
+    #[test]
+    fn positions_within_scalars() -> Result<()> {
+        let positions = from_str_with(
+            "a: 'it''s x'\nb: \"\\u00e9\\t\\\n  y\"\nc: plain\n  folded z\nd: |\n  one\n    two\ne: >\n  three\n  four\n",
+            Options::default().keep_source(true),
+        )?;
+        let within = |ptr, needle: &str| {
+            let (value, _) = positions.get_value(ptr)?;
+            positions.position_within(ptr, value.find(needle)?)
+        };
+        assert_eq!(within("/a", "x"), Some(Position { line: 1, col: 10 }));
+        assert_eq!(within("/b", "\t"), Some(Position { line: 2, col: 10 }));
+        assert_eq!(within("/b", "y"), Some(Position { line: 3, col: 2 }));
+        assert_eq!(within("/c", "z"), Some(Position { line: 5, col: 9 }));
+        assert_eq!(within("/d", "two"), Some(Position { line: 8, col: 4 }));
+        assert_eq!(within("/e", "four"), Some(Position { line: 11, col: 2 }));
+        assert_eq!(positions.position_within("/a", 100), None);
+        Ok(())
+    }
+
+    #[test]
+    fn comments() -> Result<()> {
+        let positions = from_str(
+            "#top\n\n# about a\na: 1 # one\nb: 'x # y' #two\nc: |  # header\n  # content\nd: [e#f, g] # three\n",
+        )?;
+        let texts = |comments: Vec<&Comment>| {
+            comments
+                .into_iter()
+                .map(|comment| comment.text.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            texts(positions.comments().iter().collect()),
+            vec!["top", " about a", " one", "two", " header", " three"]
+        );
+        assert_eq!(
+            texts(positions.comments_for("/a")),
+            vec![" about a", " one"]
+        );
+        assert_eq!(texts(positions.comments_for("/b")), vec!["two"]);
+        assert_eq!(
+            positions.comments()[2].span.start,
+            Position { line: 4, col: 5 }
+        );
+        assert!(positions.comments()[2].trailing);
+        Ok(())
+    }
+
+    #[test]
+    fn document_boundaries() -> Result<()> {
+        let positions = from_str("a: 1\nb: [2]\n# trailing\n--- x\n...\n")?;
+        let documents = positions.documents();
+        assert_eq!(documents.len(), 2);
+        assert_eq!(documents[0].start, None);
+        assert_eq!(documents[0].content.bytes, 0..11);
+        assert_eq!(documents[0].end, None);
+        assert_eq!(
+            documents[1].start.as_ref().map(|span| &span.start),
+            Some(&Position { line: 4, col: 0 })
+        );
+        assert_eq!(documents[1].content.bytes, 27..28);
+        assert_eq!(
+            documents[1].end.as_ref().map(|span| span.bytes.clone()),
+            Some(29..32)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn columns_are_zero_based_by_default() -> Result<()> {
+        let positions = from_str("foo:\n  bar: baz")?;
+        assert_eq!(
+            positions.get("/foo/bar"),
+            Some(&Position { line: 2, col: 2 })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn column_units() -> Result<()> {
+        let text = "{\"🦀\": 1, \"é\": 2}";
+        let col = |unit| -> Result<usize> {
+            let positions = from_str_with(text, Options::default().column_unit(unit))?;
+            Ok(positions.get("/é").map(|pos| pos.col).unwrap_or_default())
+        };
+        assert_eq!(col(ColumnUnit::Char)?, 9);
+        assert_eq!(col(ColumnUnit::Utf16)?, 10);
+        assert_eq!(col(ColumnUnit::Utf8)?, 12);
+        Ok(())
+    }
+
+    #[test]
+    fn one_based_numbering() -> Result<()> {
+        let positions = from_str_with("foo:\n  bar: baz", Options::default().one_based(true))?;
+        assert_eq!(
+            positions.get("/foo/bar"),
+            Some(&Position { line: 2, col: 3 })
+        );
+        assert_eq!(
+            positions.span("/foo/bar").map(|span| &span.end),
+            Some(&Position { line: 2, col: 11 })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn context_surrounds_position() -> Result<()> {
+        let positions = from_str_with(
+            "a: 1\r\nb:\r\n  c: 2\r\nd: 3\r\n",
+            Options::default().keep_source(true),
+        )?;
+        assert_eq!(positions.line_text(2), Some("b:"));
+        assert_eq!(positions.line_text(0), None);
+        assert_eq!(
+            positions.context("/b/c", 1),
+            Some(vec![(2, "b:"), (3, "  c: 2"), (4, "d: 3")])
+        );
+        assert_eq!(
+            positions.context("/a", 1),
+            Some(vec![(1, "a: 1"), (2, "b:")])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn text_at_preserves_original_text() -> Result<()> {
+        let positions = from_str_with(
+            "a: 'it''s'\nb: \"q\\\"\" # note\nc: |\n  one\n  two\n\nd:\n  - x\n  - {y: [1, 2]}\ne:\nf: plain\n  folded\n",
+            Options::default().keep_source(true),
+        )?;
+        assert_eq!(positions.text_at("/a"), Some("'it''s'"));
+        assert_eq!(positions.text_at("/b"), Some("\"q\\\"\""));
+        assert_eq!(positions.text_at("/c"), Some("|\n  one\n  two"));
+        assert_eq!(positions.text_at("/d"), Some("- x\n  - {y: [1, 2]}"));
+        assert_eq!(positions.text_at("/d/1"), Some("{y: [1, 2]}"));
+        assert_eq!(positions.text_at("/d/1/y"), Some("[1, 2]"));
+        assert_eq!(positions.text_at("/e"), Some(""));
+        assert_eq!(positions.text_at("/f"), Some("plain\n  folded"));
+        assert_eq!(
+            positions.span("/d/1/y/1").map(|span| &span.end),
+            Some(&Position { line: 9, col: 13 })
+        );
+        Ok(())
     }
 }
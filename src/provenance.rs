@@ -0,0 +1,168 @@
+use crate::{NodeKind, Position, Positions, Result};
+
+/// Lookup tables of layered configuration, such as a base file, an environment overlay,
+/// and overrides, telling which layer the effective value of a pointer comes from
+///
+/// Layers are added in priority order, each overriding those before it. As most layered
+/// configuration loaders do, mappings merge key by key and anything else, including
+/// sequences, replaces what the layers before it had
+///
+/// ```rust,edition2018
+/// use lincolns::{Position, Provenance};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let mut provenance = Provenance::new();
+/// provenance
+///     .load("base.yml", "server:\n  host: 0.0.0.0\n  port: 80\n")?
+///     .load("prod.yml", "server:\n  port: 443\n")?;
+/// let origin = provenance.origin("/server/port").expect("no port");
+/// assert_eq!(origin.layer, "prod.yml");
+/// assert_eq!(origin.position, &Position { line: 2, col: 2 });
+/// assert_eq!(provenance.origin("/server/host").map(|origin| origin.layer), Some("base.yml"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct Provenance {
+    layers: Vec<(String, Positions)>,
+}
+
+/// Where a layer of a [`Provenance`](struct.Provenance.html) defines a pointer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Origin<'a> {
+    /// The name the layer was added under
+    pub layer: &'a str,
+    pub position: &'a Position,
+}
+
+impl Provenance {
+    /// Creates a provenance without any layers
+    pub fn new() -> Self {
+        Provenance::default()
+    }
+
+    /// Adds a layer overriding those already added
+    pub fn layer<N>(
+        &mut self,
+        name: N,
+        positions: Positions,
+    ) -> &mut Self
+    where
+        N: Into<String>,
+    {
+        self.layers.push((name.into(), positions));
+        self
+    }
+
+    /// Loads utf8 text as a layer overriding those already added
+    pub fn load<N, S>(
+        &mut self,
+        name: N,
+        text: S,
+    ) -> Result<&mut Self>
+    where
+        N: Into<String>,
+        S: AsRef<str>,
+    {
+        let positions = crate::from_str(text)?;
+        Ok(self.layer(name, positions))
+    }
+
+    /// Returns the names of the layers, from lowest to highest priority
+    pub fn layers(&self) -> impl Iterator<Item = &str> {
+        self.layers.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Returns where the effective value of a JSON Pointer path is defined, being the
+    /// highest priority layer defining it which isn't overridden by a layer replacing
+    /// one of its ancestors
+    pub fn origin<P>(
+        &self,
+        ptr: P,
+    ) -> Option<Origin<'_>>
+    where
+        P: AsRef<str>,
+    {
+        let ptr = ptr.as_ref();
+        let ancestors = ptr
+            .match_indices('/')
+            .map(|(idx, _)| &ptr[..idx])
+            .collect::<Vec<_>>();
+        for (index, (layer, positions)) in self.layers.iter().enumerate().rev() {
+            if let Some(position) = positions.get(ptr) {
+                // ancestors redefined by layers above this one only merge when they're mappings
+                let merged = ancestors.iter().all(|ancestor| {
+                    let mut above = self.layers[index + 1..]
+                        .iter()
+                        .filter_map(|(_, above)| above.kind(ancestor))
+                        .peekable();
+                    above.peek().is_none()
+                        || (positions.kind(ancestor) == Some(NodeKind::Mapping)
+                            && above.all(|kind| kind == NodeKind::Mapping))
+                });
+                return Some(Origin { layer, position }).filter(|_| merged);
+            }
+        }
+        None
+    }
+
+    /// Returns every layer defining a JSON Pointer path, from highest to lowest priority,
+    /// whether or not it's overridden
+    pub fn definitions<P>(
+        &self,
+        ptr: P,
+    ) -> Vec<Origin<'_>>
+    where
+        P: AsRef<str>,
+    {
+        let ptr = ptr.as_ref();
+        self.layers
+            .iter()
+            .rev()
+            .filter_map(|(layer, positions)| {
+                Some(Origin {
+                    layer,
+                    position: positions.get(ptr)?,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Position, Provenance, Result};
+
+    #[test]
+    fn finds_effective_origins() -> Result<()> {
+        let mut provenance = Provenance::new();
+        provenance
+            .load("base", "a:\n  b: 1\n  c: [x, y]\nd:\n  e: 2\n")?
+            .load("env", "a:\n  c: [z]\nd: none\n")?
+            .load("cli", "a:\n  b: 3\n")?;
+        let origin = |ptr: &str| provenance.origin(ptr).map(|origin| origin.layer);
+        assert_eq!(origin("/a/b"), Some("cli"));
+        assert_eq!(origin("/a/c/0"), Some("env"));
+        assert_eq!(origin("/a/c/1"), None);
+        assert_eq!(origin("/d"), Some("env"));
+        assert_eq!(origin("/d/e"), None);
+        assert_eq!(origin("/a"), Some("cli"));
+        assert_eq!(origin("/missing"), None);
+        assert_eq!(
+            provenance
+                .definitions("/a/b")
+                .into_iter()
+                .map(|origin| (origin.layer, *origin.position))
+                .collect::<Vec<_>>(),
+            vec![
+                ("cli", Position { line: 2, col: 2 }),
+                ("base", Position { line: 2, col: 2 })
+            ]
+        );
+        assert_eq!(
+            provenance.layers().collect::<Vec<_>>(),
+            vec!["base", "env", "cli"]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+//! A Python module built with [PyO3](https://pyo3.rs)
+//!
+//! Build an extension module with
+//! `cargo rustc --release --features python,pyo3/extension-module --crate-type cdylib`,
+//! naming the library `lincolns` with your platform's extension module suffix
+use crate::Positions;
+use pyo3::{
+    exceptions::{PyKeyError, PyValueError},
+    prelude::*,
+    types::PyIterator,
+};
+
+/// A [`Positions`](../struct.Positions.html) table exposed to Python as a read-only mapping
+/// of JSON Pointer paths to `(line, col)` tuples
+#[pyclass(name = "Positions", frozen, mapping)]
+pub struct PyPositions(Positions);
+
+#[pymethods]
+impl PyPositions {
+    /// Gets the `(line, col)` of a JSON Pointer path, or `default` when there is none
+    #[pyo3(signature = (pointer, default = None))]
+    fn get(
+        &self,
+        pointer: &str,
+        default: Option<(usize, usize)>,
+    ) -> Option<(usize, usize)> {
+        self.0
+            .get(pointer)
+            .map(|position| (position.line, position.col))
+            .or(default)
+    }
+
+    fn __getitem__(
+        &self,
+        pointer: &str,
+    ) -> PyResult<(usize, usize)> {
+        self.get(pointer, None)
+            .ok_or_else(|| PyKeyError::new_err(pointer.to_owned()))
+    }
+
+    fn __contains__(
+        &self,
+        pointer: &str,
+    ) -> bool {
+        self.0.contains(pointer)
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.len()
+    }
+
+    fn __iter__<'py>(
+        &self,
+        py: Python<'py>,
+    ) -> PyResult<Bound<'py, PyIterator>> {
+        self.keys().into_pyobject(py)?.try_iter()
+    }
+
+    /// Lists JSON Pointer paths, in order
+    fn keys(&self) -> Vec<String> {
+        self.0.keys().map(str::to_owned).collect()
+    }
+
+    /// Lists JSON Pointer paths and their `(line, col)`, in order
+    fn items(&self) -> Vec<(String, (usize, usize))> {
+        self.0
+            .iter()
+            .map(|(pointer, position)| (pointer.clone(), (position.line, position.col)))
+            .collect()
+    }
+}
+
+/// Loads a lookup table from text, raising `ValueError` when it can't be loaded
+#[pyfunction]
+fn from_str(text: &str) -> PyResult<PyPositions> {
+    crate::from_str(text)
+        .map(PyPositions)
+        .map_err(|err| PyValueError::new_err(err.to_string().trim_end().to_owned()))
+}
+
+/// The `lincolns` Python module
+#[pymodule]
+fn lincolns(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyPositions>()?;
+    module.add_function(wrap_pyfunction!(from_str, module)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exposes_a_mapping() -> PyResult<()> {
+        let positions = from_str("a:\n  b: 1\n")?;
+        assert_eq!(positions.__getitem__("/a/b")?, (2, 2));
+        assert_eq!(positions.get("/c", Some((0, 0))), Some((0, 0)));
+        assert!(positions.__contains__("/a"));
+        assert_eq!(positions.__len__(), 2);
+        assert_eq!(positions.keys(), vec!["/a", "/a/b"]);
+        assert!(from_str("a: [").is_err());
+        Ok(())
+    }
+}
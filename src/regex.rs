@@ -0,0 +1,83 @@
+use crate::{Entry, Positions};
+use regex::Regex;
+
+impl Positions {
+    /// Returns an iterator over the [`Entry`](struct.Entry.html) of each node whose
+    /// JSON Pointer path, as [`iter`](#method.iter) lists it, matches a regular expression,
+    /// ordered by pointer. Requires the `regex` feature
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::from_str;
+    /// use regex::Regex;
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = from_str("jobs:\n  test:\n    steps:\n      - run: make\n      - uses: x\n")?;
+    /// let runs = Regex::new(r"^/jobs/[^/]+/steps/\d+/run$").unwrap();
+    /// assert_eq!(
+    ///     positions.find(&runs).map(|entry| entry.pointer).collect::<Vec<_>>(),
+    ///     vec!["/jobs/test/steps/0/run"]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn find<'a>(
+        &'a self,
+        regex: &'a Regex,
+    ) -> impl Iterator<Item = Entry<'a>> + 'a {
+        self.entries()
+            .filter(move |entry| regex.is_match(entry.pointer))
+    }
+
+    /// Returns an iterator over the [`Entry`](struct.Entry.html) of each scalar whose parsed
+    /// value matches a regular expression, ordered by pointer. Requires the `regex` feature
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::from_str;
+    /// use regex::Regex;
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = from_str("web:\n  image: nginx:latest\ndb:\n  image: postgres:13\n")?;
+    /// let latest = Regex::new(r":latest$").unwrap();
+    /// assert_eq!(
+    ///     positions.find_value_matching(&latest).map(|entry| entry.pointer).collect::<Vec<_>>(),
+    ///     vec!["/web/image"]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn find_value_matching<'a>(
+        &'a self,
+        regex: &'a Regex,
+    ) -> impl Iterator<Item = Entry<'a>> + 'a {
+        self.entries()
+            .filter(move |entry| entry.value.is_some_and(|value| regex.is_match(value)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_str, Position, Result};
+    use regex::Regex;
+
+    #[test]
+    fn finds_matching_pointers() -> Result<()> {
+        let positions = from_str("a:\n  b: 1\n  c: [2, 3]\nd: 4\n")?;
+        let found = |pattern: &str| {
+            let regex = Regex::new(pattern).unwrap();
+            positions
+                .find(&regex)
+                .map(|entry| (entry.pointer.to_owned(), *entry.position))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            found(r"/\d+$"),
+            vec![
+                ("/a/c/0".into(), Position { line: 3, col: 6 }),
+                ("/a/c/1".into(), Position { line: 3, col: 9 }),
+            ]
+        );
+        assert_eq!(found("^/d$").len(), 1);
+        assert!(found("^/z").is_empty());
+        Ok(())
+    }
+}
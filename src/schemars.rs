@@ -0,0 +1,138 @@
+use crate::{path, Error, Positions, Result, SchemaError, Value};
+use jsonschema::error::ValidationErrorKind;
+use schemars::schema::RootSchema;
+use serde_json::{Map, Number};
+
+/// Converts a loaded value into JSON, aliases and floats JSON can't represent becoming `null`
+fn json(value: Value) -> serde_json::Value {
+    match value {
+        Value::Null | Value::Alias => serde_json::Value::Null,
+        Value::Bool(value) => serde_json::Value::Bool(value),
+        Value::Integer(value) => serde_json::Value::Number(value.into()),
+        Value::Float(value) => {
+            Number::from_f64(value).map_or(serde_json::Value::Null, serde_json::Value::Number)
+        }
+        Value::String(value) => serde_json::Value::String(value),
+        Value::Sequence(items) => items.into_iter().map(|item| json(item.value)).collect(),
+        Value::Mapping(entries) => serde_json::Value::Object(
+            entries
+                .into_iter()
+                .map(|(key, entry)| (key, json(entry.value)))
+                .collect::<Map<_, _>>(),
+        ),
+    }
+}
+
+impl Positions {
+    /// Validates the document against a [schemars](https://docs.rs/schemars) schema, such as one
+    /// derived with `#[derive(JsonSchema)]`, locating each problem found
+    ///
+    /// Missing required fields are located at the mapping lacking them, values of the wrong
+    /// type at the values, and each unknown property, rejected by `additionalProperties`
+    /// as `#[serde(deny_unknown_fields)]` implies, at the property itself
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{from_str, Position};
+    /// use schemars::{schema_for, JsonSchema};
+    ///
+    /// #[derive(JsonSchema)]
+    /// #[serde(deny_unknown_fields)]
+    /// struct Config {
+    ///     name: String,
+    ///     replicas: u8,
+    /// }
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = from_str("name: web\nreplicas: many\nrepicas: 2\n")?;
+    /// let errors = positions.validate_schema(&schema_for!(Config))?;
+    /// assert_eq!(errors[0].pointer, "/replicas");
+    /// assert_eq!(errors[0].position, Some(Position { line: 2, col: 0 }));
+    /// assert_eq!(errors[1].pointer, "/repicas");
+    /// assert_eq!(errors[1].position, Some(Position { line: 3, col: 0 }));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn validate_schema(
+        &self,
+        schema: &RootSchema,
+    ) -> Result<Vec<SchemaError>> {
+        let schema =
+            serde_json::to_value(schema).map_err(|err| Error::InvalidSchema(err.to_string()))?;
+        let validator = jsonschema::validator_for(&schema)
+            .map_err(|err| Error::InvalidSchema(err.to_string()))?;
+        let instance = self
+            .annotated("")
+            .map_or(serde_json::Value::Null, |root| json(root.value));
+        let mut errors = Vec::new();
+        for err in validator.iter_errors(&instance) {
+            let error = self.schema_error(&err);
+            match err.kind() {
+                ValidationErrorKind::AdditionalProperties { unexpected } => {
+                    errors.extend(unexpected.iter().map(|property| {
+                        let pointer = format!("{}/{}", error.pointer, path::escape(property));
+                        let location = self.locate_pointer(&pointer);
+                        SchemaError {
+                            message: format!(
+                                "Additional properties are not allowed ('{}' was unexpected)",
+                                property
+                            ),
+                            position: location.as_ref().map(|(position, _)| *position),
+                            span: location.map(|(_, span)| span),
+                            pointer,
+                            schema_path: error.schema_path.clone(),
+                        }
+                    }))
+                }
+                _ => errors.push(error),
+            }
+        }
+        Ok(errors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_str, Position, Result};
+    use schemars::{schema_for, JsonSchema};
+    use std::collections::BTreeMap;
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Config {
+        version: u8,
+        services: BTreeMap<String, Service>,
+    }
+
+    #[derive(JsonSchema)]
+    #[serde(deny_unknown_fields)]
+    #[allow(dead_code)]
+    struct Service {
+        image: String,
+        ports: Vec<u16>,
+    }
+
+    #[test]
+    fn locates_schema_diagnostics() -> Result<()> {
+        let positions = from_str(
+            "services:\n  web:\n    image: nginx\n    ports: [80, http]\n    enviroment: {}\n",
+        )?;
+        let errors = positions.validate_schema(&schema_for!(Config))?;
+        let mut located = errors
+            .iter()
+            .map(|err| (err.pointer.as_str(), err.position))
+            .collect::<Vec<_>>();
+        located.sort();
+        assert_eq!(
+            located,
+            vec![
+                ("", Some(Position { line: 1, col: 0 })),
+                (
+                    "/services/web/enviroment",
+                    Some(Position { line: 5, col: 4 })
+                ),
+                ("/services/web/ports/1", Some(Position { line: 4, col: 16 })),
+            ]
+        );
+        Ok(())
+    }
+}
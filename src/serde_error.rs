@@ -0,0 +1,79 @@
+use crate::{Position, Positions};
+
+impl Positions {
+    /// Returns the pointer and position of the node a [serde_json](https://docs.rs/serde_json)
+    /// error occurred within, as [`Positions::nearest`](struct.Positions.html#method.nearest) finds it
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::from_str;
+    /// use std::collections::HashMap;
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let json = "{\n  \"spec\": {\n    \"replicas\": \"three\"\n  }\n}";
+    /// let err = serde_json::from_str::<HashMap<String, HashMap<String, u8>>>(json).unwrap_err();
+    /// let positions = from_str(json)?;
+    /// assert_eq!(
+    ///     positions.json_error_pointer(&err).map(|(ptr, _)| ptr),
+    ///     Some("/spec/replicas")
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "serde_json")]
+    pub fn json_error_pointer(
+        &self,
+        err: &serde_json::Error,
+    ) -> Option<(&str, &Position)> {
+        self.nearest(&self.one_based(err.line(), err.column()))
+    }
+
+    /// Returns the pointer and position of the node a [serde_yaml](https://docs.rs/serde_yaml)
+    /// error occurred within, as [`Positions::nearest`](struct.Positions.html#method.nearest) finds it
+    #[cfg(feature = "serde_yaml")]
+    pub fn yaml_error_pointer(
+        &self,
+        err: &serde_yaml::Error,
+    ) -> Option<(&str, &Position)> {
+        let location = err.location()?;
+        self.nearest(&self.one_based(location.line(), location.column()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_str, Result};
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Container {
+        image: String,
+        ports: Vec<u16>,
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn json_error_pointer() -> Result<()> {
+        let json = "[\n  {\"image\": \"web\", \"ports\": [80]},\n  {\"image\": \"db\", \"ports\": [\"x\"]}\n]";
+        let err = serde_json::from_str::<Vec<Container>>(json).unwrap_err();
+        let positions = from_str(json)?;
+        assert_eq!(
+            positions.json_error_pointer(&err).map(|(ptr, _)| ptr),
+            Some("/1/ports/0")
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "serde_yaml")]
+    #[test]
+    fn yaml_error_pointer() -> Result<()> {
+        let yaml = "- image: web\n  ports: [80]\n- image: db\n  ports: 443\n";
+        let err = serde_yaml::from_str::<Vec<Container>>(yaml).unwrap_err();
+        let positions = from_str(yaml)?;
+        assert_eq!(
+            positions.yaml_error_pointer(&err).map(|(ptr, _)| ptr),
+            Some("/1/ports")
+        );
+        Ok(())
+    }
+}
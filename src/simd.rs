@@ -0,0 +1,158 @@
+use crate::{backend::Event, Backend, Error, Result, ScalarStyle};
+use simd_json::{tape::Node, to_tape};
+
+/// A [`Backend`](trait.Backend.html) for JSON content built on [simd-json](https://docs.rs/simd-json),
+/// which indexes large documents considerably faster than the YAML parsers
+///
+/// Content is validated as strict JSON. Requires the `simd` feature
+///
+/// ```rust,edition2018
+/// use lincolns::{from_str_using, Options, Position, SimdJson};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let positions = from_str_using(&SimdJson, r#"{"a": [1, true]}"#, Options::default())?;
+/// assert_eq!(positions.get("/a/1"), Some(&Position { line: 1, col: 10 }));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SimdJson;
+
+impl Backend for SimdJson {
+    fn parse(
+        &self,
+        text: &str,
+        sink: &mut dyn FnMut(Event, usize),
+    ) -> Result<()> {
+        // simd-json parses in place, so it gets a copy to keep the original text for locating nodes
+        let mut bytes = text.as_bytes().to_vec();
+        let tape = to_tape(&mut bytes).map_err(|err| Error::Backend(Box::new(err)))?;
+        // the tape records no offsets, so a cursor follows along in the text,
+        // which simd-json has already validated
+        let mut cursor = Cursor { text, pos: 0 };
+        let mut nodes = tape.0.iter();
+        sink(Event::DocumentStart, cursor.token());
+        cursor.value(&mut nodes, sink);
+        sink(Event::DocumentEnd, text.len());
+        Ok(())
+    }
+}
+
+struct Cursor<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    /// Advances past whitespace and separators to the start of the next token
+    fn token(&mut self) -> usize {
+        self.pos += self.text.as_bytes()[self.pos..]
+            .iter()
+            .take_while(|b| matches!(b, b' ' | b'\t' | b'\n' | b'\r' | b',' | b':'))
+            .count();
+        self.pos
+    }
+
+    /// Emits the events of the next value on the tape
+    fn value<'t, I>(
+        &mut self,
+        nodes: &mut I,
+        sink: &mut dyn FnMut(Event, usize),
+    ) where
+        I: Iterator<Item = &'t Node<'t>>,
+    {
+        let at = self.token();
+        match nodes.next() {
+            Some(Node::String(value)) => {
+                sink(
+                    Event::Scalar {
+                        value: (*value).to_owned(),
+                        style: ScalarStyle::DoubleQuoted,
+                        tag: None,
+                    },
+                    at,
+                );
+                self.pos = self.string_end(at);
+            }
+            Some(Node::Static(_)) => {
+                let len = self.text.as_bytes()[at..]
+                    .iter()
+                    .take_while(|b| !matches!(b, b' ' | b'\t' | b'\n' | b'\r' | b',' | b']' | b'}'))
+                    .count();
+                self.pos = at + len;
+                sink(
+                    Event::Scalar {
+                        value: self.text[at..self.pos].to_owned(),
+                        style: ScalarStyle::Plain,
+                        tag: None,
+                    },
+                    at,
+                );
+            }
+            Some(Node::Object { len, .. }) => {
+                sink(Event::MappingStart { anchor: 0 }, at);
+                self.pos += 1;
+                for _ in 0..*len * 2 {
+                    self.value(nodes, sink);
+                }
+                sink(Event::MappingEnd, self.token());
+                self.pos += 1;
+            }
+            Some(Node::Array { len, .. }) => {
+                sink(Event::SequenceStart { anchor: 0 }, at);
+                self.pos += 1;
+                for _ in 0..*len {
+                    self.value(nodes, sink);
+                }
+                sink(Event::SequenceEnd, self.token());
+                self.pos += 1;
+            }
+            None => (),
+        }
+    }
+
+    /// Byte offset just past the closing quote of a string starting at `start`
+    fn string_end(
+        &self,
+        start: usize,
+    ) -> usize {
+        let mut bytes = self.text.as_bytes()[start + 1..].iter().enumerate();
+        while let Some((idx, b)) = bytes.next() {
+            match b {
+                b'\\' => {
+                    bytes.next();
+                }
+                b'"' => return start + idx + 2,
+                _ => (),
+            }
+        }
+        self.text.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_str, from_str_using, Options};
+
+    #[test]
+    fn indexes_like_yaml() -> Result<()> {
+        for text in &[
+            include_str!("../tests/data/example.json"),
+            "{\"a\\\"b\": [\"x\\\\\", {}, [], -1.5e3],\n  \"é\": {\"c\": null}}",
+        ] {
+            let expected = from_str(text)?;
+            let positions = from_str_using(&SimdJson, text, Options::default())?;
+            assert_eq!(
+                positions.iter().collect::<Vec<_>>(),
+                expected.iter().collect::<Vec<_>>()
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_invalid_json() {
+        assert!(from_str_using(&SimdJson, "{a: 1}", Options::default()).is_err());
+    }
+}
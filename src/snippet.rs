@@ -0,0 +1,82 @@
+use crate::Positions;
+use std::fmt::{Display, Write};
+
+impl Positions {
+    /// Renders the source line holding the value a JSON Pointer path refers to,
+    /// with carets under the value followed by `message`
+    ///
+    /// ```text
+    ///  --> /foo/bar at 2:2
+    ///   |
+    /// 2 |   bar: baz
+    ///   |        ^^^ unexpected value
+    /// ```
+    ///
+    /// This requires the source to be retained with [`Options::keep_source`](struct.Options.html#method.keep_source)
+    pub fn snippet<P, M>(
+        &self,
+        ptr: P,
+        message: M,
+    ) -> Option<String>
+    where
+        P: AsRef<str>,
+        M: Display,
+    {
+        let ptr = ptr.as_ref();
+        let (text, lines) = self.source_lines()?;
+        let position = self.get(ptr)?;
+        let bytes = &self.span(ptr)?.bytes;
+        let line = lines.line(bytes.start);
+        let range = lines.line_range(line, text)?;
+        let content = &text[range.clone()];
+        let lead = &text[range.start..bytes.start];
+        let width = text[bytes.start..bytes.end.min(range.end)]
+            .chars()
+            .count()
+            .max(1);
+        let number = (line + 1).to_string();
+        let gutter = " ".repeat(number.len());
+        let indent = lead
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+
+        let mut out = String::new();
+        let _ = writeln!(out, "{}--> {} at {}", gutter, ptr, position);
+        let _ = writeln!(out, "{} |", gutter);
+        let _ = writeln!(out, "{} | {}", number, content);
+        let _ = write!(
+            out,
+            "{} | {}{} {}",
+            gutter,
+            indent,
+            "^".repeat(width),
+            message
+        );
+        Some(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_str_with, Options, Result};
+
+    #[test]
+    fn snippet_underlines_value() -> Result<()> {
+        let positions = from_str_with(
+            "foo:\n  bar: baz\n  nothing:\n",
+            Options::default().keep_source(true),
+        )?;
+        assert_eq!(
+            positions.snippet("/foo/bar", "unexpected value").as_deref(),
+            Some(" --> /foo/bar at 2:2\n  |\n2 |   bar: baz\n  |        ^^^ unexpected value")
+        );
+        assert_eq!(
+            positions
+                .snippet("/foo/nothing", "missing value")
+                .as_deref(),
+            Some(" --> /foo/nothing at 3:2\n  |\n3 |   nothing:\n  |          ^ missing value")
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,618 @@
+use crate::{
+    backend, ColumnUnit, DuplicateKeys, MergeKeys, Options, Position, ScalarStyle, Span,
+    YamlVersion,
+};
+use std::ops::Range;
+
+/// The byte order mark, which may begin a YAML stream
+pub(crate) const BOM: char = '\u{feff}';
+
+/// The sequence of characters ending lines of text
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`, as on Unix. This is assumed of text without line endings
+    #[default]
+    Lf,
+    /// `\r\n`, as on Windows
+    CrLf,
+}
+
+impl LineEnding {
+    /// Detects the line ending of a text by its first line
+    pub(crate) fn detect(text: &str) -> LineEnding {
+        match text.find('\n') {
+            Some(idx) if text[..idx].ends_with('\r') => LineEnding::CrLf,
+            _ => LineEnding::Lf,
+        }
+    }
+
+    /// The characters ending a line
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+/// Byte offsets of the start of each line in a text, converting between byte offsets
+/// and [`Position`](struct.Position.html)s
+///
+/// Positions are numbered as [`Options::default`](struct.Options.html) numbers them,
+/// with lines starting at 1 and columns counting chars starting at 0
+///
+/// ```rust,edition2018
+/// use lincolns::{LineIndex, Position};
+///
+/// let text = "name: web\nimage: nginx\n";
+/// let lines = LineIndex::new(text);
+/// assert_eq!(lines.position(text, 17), Position { line: 2, col: 7 });
+/// assert_eq!(lines.offset(text, &Position { line: 2, col: 7 }), Some(17));
+/// assert_eq!(lines.line_range(1, text), Some(10..22));
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct LineIndex {
+    starts: Vec<usize>,
+}
+
+impl LineIndex {
+    /// Indexes the lines of a text
+    pub fn new(text: &str) -> Self {
+        // a byte order mark precedes the first line rather than being part of it
+        let bom = if text.starts_with(BOM) {
+            BOM.len_utf8()
+        } else {
+            0
+        };
+        let starts = std::iter::once(bom)
+            .chain(text.match_indices('\n').map(|(idx, _)| idx + 1))
+            .collect();
+        Self { starts }
+    }
+
+    /// Zero-based line containing a byte offset
+    pub fn line(
+        &self,
+        offset: usize,
+    ) -> usize {
+        match self.starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(line) => line.saturating_sub(1),
+        }
+    }
+
+    /// Byte range of a zero-based line, excluding its line ending
+    pub fn line_range(
+        &self,
+        line: usize,
+        text: &str,
+    ) -> Option<Range<usize>> {
+        let start = *self.starts.get(line)?;
+        let end = self
+            .starts
+            .get(line + 1)
+            .map_or(text.len(), |next| next - 1);
+        let end = if text[start..end].ends_with('\r') {
+            end - 1
+        } else {
+            end
+        };
+        Some(start..end)
+    }
+
+    /// Byte offset of the start of a zero-based line
+    pub fn start(
+        &self,
+        line: usize,
+    ) -> Option<usize> {
+        self.starts.get(line).cloned()
+    }
+
+    /// Number of lines in the text
+    pub fn len(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Returns true if no lines are indexed, as of a default index.
+    /// Any text, even an empty one, has at least one line
+    pub fn is_empty(&self) -> bool {
+        self.starts.is_empty()
+    }
+
+    /// Line and column of a byte offset within the indexed text
+    pub fn position(
+        &self,
+        text: &str,
+        offset: usize,
+    ) -> Position {
+        self.position_with(text, offset, Columns::default())
+    }
+
+    /// Byte offset of a line and column within the indexed text, if the line exists.
+    /// Columns past the end of a line are clamped to it
+    pub fn offset(
+        &self,
+        text: &str,
+        position: &Position,
+    ) -> Option<usize> {
+        self.line_range(position.line.checked_sub(1)?, text)?;
+        Some(self.offset_with(text, position, Columns::default()))
+    }
+
+    /// Byte offset of a position within the text, numbered as `columns` are, clamped to the
+    /// end of its line or of the text
+    pub(crate) fn offset_with(
+        &self,
+        text: &str,
+        position: &Position,
+        columns: Columns,
+    ) -> usize {
+        let range = match position
+            .line
+            .checked_sub(1)
+            .and_then(|line| self.line_range(line, text))
+        {
+            Some(range) => range,
+            None => return text.len(),
+        };
+        let mut col = columns.base;
+        for (idx, c) in text[range.clone()].char_indices() {
+            if col >= position.col {
+                return range.start + idx;
+            }
+            col += columns.unit.measure(c.encode_utf8(&mut [0; 4]));
+        }
+        range.end
+    }
+
+    /// Line and column of a byte offset within the text, numbered as `columns` are
+    pub(crate) fn position_with(
+        &self,
+        text: &str,
+        offset: usize,
+        columns: Columns,
+    ) -> Position {
+        let line = self.line(offset);
+        let start = self.starts[line];
+        Position {
+            line: line + 1,
+            col: columns.unit.measure(&text[start..offset.max(start)]) + columns.base,
+        }
+    }
+}
+
+/// How columns are numbered and measured
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Columns {
+    base: usize,
+    unit: ColumnUnit,
+}
+
+impl Columns {
+    /// The number of the first column
+    #[cfg(any(feature = "serde_json", feature = "serde_yaml"))]
+    pub fn base(&self) -> usize {
+        self.base
+    }
+}
+
+/// Text being indexed along with the offsets of each of its lines
+pub(crate) struct Source<'a> {
+    text: &'a str,
+    lines: LineIndex,
+    columns: Columns,
+    duplicate_keys: DuplicateKeys,
+    merge_keys: MergeKeys,
+    yaml_version: YamlVersion,
+}
+
+impl<'a> Source<'a> {
+    pub fn new(
+        text: &'a str,
+        options: &Options,
+    ) -> Self {
+        Self {
+            text,
+            lines: LineIndex::new(text),
+            columns: Columns {
+                base: if options.one_based { 1 } else { 0 },
+                unit: options.column_unit,
+            },
+            duplicate_keys: options.duplicate_keys,
+            merge_keys: options.merge_keys,
+            yaml_version: options.yaml_version,
+        }
+    }
+
+    /// The text being indexed
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// How columns are numbered and measured
+    pub fn columns(&self) -> Columns {
+        self.columns
+    }
+
+    /// How repeated mapping keys are handled
+    pub fn duplicate_keys(&self) -> DuplicateKeys {
+        self.duplicate_keys
+    }
+
+    pub fn into_lines(self) -> LineIndex {
+        self.lines
+    }
+
+    /// How merge keys are indexed
+    pub fn merge_keys(&self) -> MergeKeys {
+        self.merge_keys
+    }
+
+    /// The YAML version resolving plain scalars
+    pub fn yaml_version(&self) -> YamlVersion {
+        self.yaml_version
+    }
+
+    /// Line and column of a byte offset
+    pub fn position(
+        &self,
+        offset: usize,
+    ) -> Position {
+        self.lines.position_with(self.text, offset, self.columns)
+    }
+
+    /// Position of a yaml-rust marker's one-based line and zero-based column counted in chars
+    pub fn marker(
+        &self,
+        line: usize,
+        col: usize,
+    ) -> Position {
+        self.position(backend::offset(self.text, &self.lines, line, col))
+    }
+
+    pub fn span(
+        &self,
+        bytes: Range<usize>,
+    ) -> Span {
+        Span {
+            start: self.position(bytes.start),
+            end: self.position(bytes.end),
+            bytes,
+        }
+    }
+
+    /// Offset of the `-` indicator of a block sequence item, the first token following the end
+    /// of the previous item, or the start of the sequence, other than comments
+    pub fn indicator(
+        &self,
+        from: usize,
+    ) -> Option<usize> {
+        let rest = self.text.get(from..)?;
+        let (mut comment, mut prev) = (false, None);
+        for (idx, c) in rest.char_indices() {
+            match c {
+                '\n' => comment = false,
+                _ if comment => (),
+                '#' if prev.is_none_or(char::is_whitespace) => comment = true,
+                c if c.is_whitespace() => (),
+                '-' if rest[idx + 1..]
+                    .chars()
+                    .next()
+                    .is_none_or(char::is_whitespace)
+                    && self.compact(from + idx) =>
+                {
+                    return Some(from + idx)
+                }
+                _ => return None,
+            }
+            prev = Some(c);
+        }
+        None
+    }
+
+    /// Returns true when only indentation and the indicators of compact collections, such as
+    /// the first `-` of `- - x`, precede an offset on its line
+    fn compact(
+        &self,
+        offset: usize,
+    ) -> bool {
+        let line = &self.text[..offset];
+        line[line.rfind('\n').map_or(0, |idx| idx + 1)..]
+            .chars()
+            .all(|c| matches!(c, ' ' | '\t' | '-' | '?' | ':'))
+    }
+
+    /// Returns true if a flow collection starts at the given offset
+    pub fn is_flow(
+        &self,
+        offset: usize,
+    ) -> bool {
+        self.text[offset..].starts_with(['[', '{'])
+    }
+
+    /// The explicit tag of a collection starting at `start`, as written before it and any anchor
+    ///
+    /// Tags of collections aren't reported by the parser, so they're read from the text
+    pub fn collection_tag(
+        &self,
+        start: usize,
+    ) -> Option<&'a str> {
+        let separator = |c: char| c.is_whitespace() || matches!(c, '[' | '{' | ',');
+        let mut before = self.text[..start].trim_end_matches(separator);
+        loop {
+            let token_start = before
+                .char_indices()
+                .rev()
+                .find(|(_, c)| separator(*c))
+                .map_or(0, |(idx, c)| idx + c.len_utf8());
+            let token = &before[token_start..];
+            // tokens within comments aren't tags
+            let line =
+                &before[before[..token_start].rfind('\n').map_or(0, |idx| idx + 1)..token_start];
+            if line.contains(" #") || line.starts_with('#') {
+                return None;
+            }
+            match token.chars().next() {
+                Some('&') => before = before[..token_start].trim_end_matches(separator),
+                Some('!') => return Some(token),
+                _ => return None,
+            }
+        }
+    }
+
+    /// Byte offset just past the bracket closing a flow collection, given an offset
+    /// after its last entry
+    ///
+    /// Only separators, whitespace, and comments may follow a collection's last entry, so this
+    /// is the first closing bracket outside of a comment
+    pub fn flow_end(
+        &self,
+        from: usize,
+    ) -> usize {
+        let mut comment = false;
+        for (idx, c) in self.text[from..].char_indices() {
+            match c {
+                '#' => comment = true,
+                '\n' => comment = false,
+                ']' | '}' if !comment => return from + idx + 1,
+                _ => (),
+            }
+        }
+        self.text.len()
+    }
+
+    /// Byte range of a scalar marked at `start` given its style and parsed value
+    ///
+    /// Empty (implicit null) scalars have no source text of their own and
+    /// are placed at `prev`, the end of the preceding node
+    pub fn scalar(
+        &self,
+        start: usize,
+        style: ScalarStyle,
+        value: &str,
+        prev: usize,
+    ) -> Range<usize> {
+        let (start, end) = match style {
+            ScalarStyle::SingleQuoted => (start, self.quoted_end(start, '\'')),
+            ScalarStyle::DoubleQuoted => (start, self.quoted_end(start, '"')),
+            ScalarStyle::Literal | ScalarStyle::Folded => {
+                // block scalars are marked at their content rather than their header
+                let header = self.text[prev.min(start)..start]
+                    .find(['|', '>'])
+                    .map_or(start, |idx| prev + idx);
+                // which makes the marker's column the content's indentation, unless there is none
+                let indent =
+                    (!value.is_empty() && header != start).then(|| start - self.line_start(start));
+                (header, self.block_end(header, indent))
+            }
+            ScalarStyle::Plain => (start, self.plain_end(start, value)),
+        };
+        if end == start {
+            prev..prev
+        } else {
+            start..end
+        }
+    }
+
+    /// Byte range of an alias, `*name`, starting at `start`
+    pub fn alias(
+        &self,
+        start: usize,
+    ) -> Range<usize> {
+        let len = self.text[start..]
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| c.is_whitespace() || ",[]{}".contains(*c))
+            .map(|(idx, _)| idx)
+            .unwrap_or_else(|| self.text.len() - start);
+        start..start + len
+    }
+
+    fn quoted_end(
+        &self,
+        start: usize,
+        quote: char,
+    ) -> usize {
+        let mut chars = self.text[start..].char_indices().skip(1).peekable();
+        while let Some((idx, c)) = chars.next() {
+            match c {
+                '\\' if quote == '"' => {
+                    chars.next();
+                }
+                '\'' if quote == '\'' && chars.peek().map(|(_, c)| *c) == Some('\'') => {
+                    chars.next();
+                }
+                c if c == quote => return start + idx + 1,
+                _ => (),
+            }
+        }
+        self.text.len()
+    }
+
+    /// Byte offset of the end of a block scalar's content given the offset of its header and,
+    /// when known, the indentation of its content
+    fn block_end(
+        &self,
+        start: usize,
+        content_indent: Option<usize>,
+    ) -> usize {
+        let parent_indent = indentation(&self.text[self.line_start(start)..]);
+        let header_end = self.text[start..]
+            .find('\n')
+            .map_or(self.text.len(), |idx| start + idx);
+        let header = &self.text[start..header_end];
+        let header = header.find(" #").map_or(header, |idx| &header[..idx]);
+        let mut end = start + header.trim_end().len();
+        let mut offset = header_end + 1;
+        let mut content_indent = content_indent;
+        while offset < self.text.len() {
+            let line = self.text[offset..]
+                .find('\n')
+                .map_or(&self.text[offset..], |idx| {
+                    &self.text[offset..=offset + idx]
+                });
+            let body = line.trim_end_matches(['\n', '\r']);
+            if !body.trim().is_empty() {
+                let indent = indentation(body);
+                match content_indent {
+                    None if indent > parent_indent => content_indent = Some(indent),
+                    Some(content) if indent >= content => (),
+                    _ => break,
+                }
+                end = offset + body.trim_end().len();
+            }
+            offset += line.len();
+        }
+        end
+    }
+
+    /// Byte offset of the start of the line containing an offset
+    fn line_start(
+        &self,
+        offset: usize,
+    ) -> usize {
+        self.text[..offset].rfind('\n').map_or(0, |idx| idx + 1)
+    }
+
+    fn plain_end(
+        &self,
+        start: usize,
+        value: &str,
+    ) -> usize {
+        let mut source = self.text[start..].char_indices().peekable();
+        let mut value = value.chars().peekable();
+        let mut end = start;
+        while let (Some(&expected), Some(&(idx, actual))) = (value.peek(), source.peek()) {
+            if expected == actual {
+                end = start + idx + actual.len_utf8();
+                value.next();
+                source.next();
+            } else if expected.is_whitespace() && actual.is_whitespace() {
+                // line folding may collapse or rewrite whitespace
+                while value.peek().is_some_and(|c| c.is_whitespace()) {
+                    value.next();
+                }
+                while source.peek().is_some_and(|(_, c)| c.is_whitespace()) {
+                    source.next();
+                }
+            } else {
+                break;
+            }
+        }
+        end
+    }
+}
+
+/// Maps a byte offset within a scalar's parsed value to a byte offset within `raw`,
+/// the scalar's source text, undoing quoting, escapes, indentation, and line folding
+///
+/// Offsets within whitespace collapsed by folding map to the start of the source whitespace
+pub(crate) fn value_offset(
+    raw: &str,
+    style: ScalarStyle,
+    value: &str,
+    target: usize,
+) -> Option<usize> {
+    let (mut offset, end, indent) = match style {
+        ScalarStyle::SingleQuoted | ScalarStyle::DoubleQuoted => {
+            (1, raw.len().saturating_sub(1).max(1), None)
+        }
+        ScalarStyle::Literal | ScalarStyle::Folded => {
+            let body = raw.find('\n').map_or(raw.len(), |idx| idx + 1);
+            let indent = raw[body..]
+                .lines()
+                .find(|line| !line.trim().is_empty())
+                .map(indentation);
+            (body, raw.len(), indent)
+        }
+        ScalarStyle::Plain => (0, raw.len(), None),
+    };
+    let mut line_start = indent.is_some();
+    let mut chars = value.char_indices().peekable();
+    loop {
+        if let (true, Some(indent)) = (line_start, indent) {
+            let spaces = indentation(&raw[offset..end]).min(indent);
+            offset += spaces;
+            line_start = false;
+        }
+        let (idx, c) = match chars.peek() {
+            Some(&(idx, _)) if idx > target => return None,
+            Some(&(idx, c)) => (idx, c),
+            None => return (target == value.len()).then_some(offset),
+        };
+        let actual = raw[offset..end].chars().next();
+        let escaped = match actual {
+            Some('\\') if style == ScalarStyle::DoubleQuoted => raw[offset + 1..].chars().next(),
+            _ => None,
+        };
+        match (actual, escaped) {
+            // an escaped line break continues the line without adding anything to it
+            (_, Some(escaped @ ('\n' | '\r'))) => {
+                offset += 1 + escaped.len_utf8();
+                continue;
+            }
+            // as does whitespace which was folded away
+            (Some(actual), _) if actual.is_whitespace() && !c.is_whitespace() => {
+                offset += actual.len_utf8();
+                line_start = actual == '\n';
+                continue;
+            }
+            _ => (),
+        }
+        if idx == target {
+            return Some(offset);
+        }
+        match actual {
+            Some('\\') if style == ScalarStyle::DoubleQuoted => {
+                offset += match escaped? {
+                    'x' => 4,
+                    'u' => 6,
+                    'U' => 10,
+                    other => 1 + other.len_utf8(),
+                };
+                chars.next();
+            }
+            Some('\'') if style == ScalarStyle::SingleQuoted && c == '\'' => {
+                offset += 2;
+                chars.next();
+            }
+            Some(actual) if actual == c => {
+                offset += actual.len_utf8();
+                line_start = actual == '\n';
+                chars.next();
+            }
+            Some(actual) if actual.is_whitespace() => {
+                offset += actual.len_utf8();
+                line_start = actual == '\n';
+            }
+            _ if c.is_whitespace() => {
+                chars.next();
+            }
+            _ => return None,
+        }
+    }
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
@@ -0,0 +1,80 @@
+use crate::{NodeKind, Positions};
+use std::collections::HashMap;
+
+/// Figures about a [`Positions`](struct.Positions.html) table and the text it indexed,
+/// reported by [`Positions::stats`](struct.Positions.html#method.stats)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Stats {
+    /// The number of indexed pointers, as [`Positions::len`](struct.Positions.html#method.len) counts them
+    pub nodes: usize,
+    /// The number of tokens in the most deeply nested pointer, 0 when nothing is indexed
+    pub max_depth: usize,
+    /// The number of indexed pointers of each kind
+    pub kinds: HashMap<NodeKind, usize>,
+    /// The longest indexed pointer, the first in order of those as long
+    pub longest_pointer: Option<String>,
+    /// The number of lines in the text, not counting an empty line after a final line ending
+    pub lines: usize,
+}
+
+impl Positions {
+    /// Gathers [`Stats`](struct.Stats.html) about this table
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::NodeKind;
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let stats = lincolns::from_str("name: web\nports:\n  - 80\n  - 443\n")?.stats();
+    /// assert_eq!(stats.nodes, 4);
+    /// assert_eq!(stats.max_depth, 2);
+    /// assert_eq!(stats.kinds.get(&NodeKind::Integer), Some(&2));
+    /// assert_eq!(stats.longest_pointer.as_deref(), Some("/ports/0"));
+    /// assert_eq!(stats.lines, 4);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stats(&self) -> Stats {
+        let mut stats = Stats {
+            nodes: self.len(),
+            lines: self.line_count(),
+            ..Stats::default()
+        };
+        for entry in self.entries() {
+            stats.max_depth = stats.max_depth.max(entry.depth + 1);
+            *stats.kinds.entry(entry.kind).or_default() += 1;
+            let longest = stats.longest_pointer.as_ref().map_or(0, String::len);
+            if entry.pointer.len() > longest {
+                stats.longest_pointer = Some(entry.pointer.to_owned());
+            }
+        }
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_str, NodeKind, Result};
+
+    #[test]
+    fn gathers_stats() -> Result<()> {
+        let stats = from_str("")?.stats();
+        assert_eq!(
+            (
+                stats.nodes,
+                stats.max_depth,
+                stats.longest_pointer,
+                stats.lines
+            ),
+            (0, 0, None, 0)
+        );
+        let stats = from_str("a:\n  b: [1, {c: true}]\n  d: ~\n# end")?.stats();
+        assert_eq!(stats.nodes, 6);
+        assert_eq!(stats.max_depth, 4);
+        assert_eq!(stats.longest_pointer.as_deref(), Some("/a/b/1/c"));
+        assert_eq!(stats.kinds.get(&NodeKind::Mapping), Some(&2));
+        assert_eq!(stats.kinds.get(&NodeKind::Null), Some(&1));
+        assert_eq!(stats.kinds.get(&NodeKind::String), None);
+        assert_eq!(stats.lines, 4);
+        Ok(())
+    }
+}
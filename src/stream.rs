@@ -0,0 +1,112 @@
+use crate::{Position, ScalarStyle, Span};
+use std::vec;
+
+/// An event in a stream of YAML content, along with the JSON Pointer path of the node it belongs to
+///
+/// Streams are produced by [`events`](fn.events.html) and [`events_with`](fn.events_with.html).
+/// The root node's pointer is the empty string
+#[derive(Debug, PartialEq)]
+pub enum SpannedEvent {
+    DocumentStart,
+    DocumentEnd,
+    /// A mapping key, with the pointer of the entry it introduces
+    Key {
+        pointer: String,
+        value: String,
+        span: Span,
+    },
+    Scalar {
+        pointer: String,
+        value: String,
+        style: ScalarStyle,
+        tag: Option<String>,
+        span: Span,
+    },
+    /// An alias, `*name`, referring to an anchored node
+    Alias {
+        pointer: String,
+        span: Span,
+    },
+    SequenceStart {
+        pointer: String,
+    },
+    SequenceEnd {
+        pointer: String,
+        span: Span,
+    },
+    MappingStart {
+        pointer: String,
+    },
+    MappingEnd {
+        pointer: String,
+        span: Span,
+    },
+}
+
+/// An iterator over [`SpannedEvent`](enum.SpannedEvent.html)s and the position each occurred at
+///
+/// Start events are positioned at the start of their node and end events just past its end
+pub struct Events {
+    inner: vec::IntoIter<(SpannedEvent, Position)>,
+}
+
+impl Events {
+    pub(crate) fn new(events: Vec<(SpannedEvent, Position)>) -> Self {
+        Events {
+            inner: events.into_iter(),
+        }
+    }
+}
+
+impl Iterator for Events {
+    type Item = (SpannedEvent, Position);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{events, Position, Result, SpannedEvent};
+
+    #[test]
+    fn events_carry_pointers_and_positions() -> Result<()> {
+        let summary = events("a: &x\n  - b\nc: *x\n")?
+            .map(|(event, Position { line, col })| {
+                let name = match event {
+                    SpannedEvent::DocumentStart => "doc".to_string(),
+                    SpannedEvent::DocumentEnd => "/doc".to_string(),
+                    SpannedEvent::Key { pointer, .. } => format!("key {}", pointer),
+                    SpannedEvent::Scalar { pointer, value, .. } => format!("{} {}", pointer, value),
+                    SpannedEvent::Alias { pointer, .. } => format!("alias {}", pointer),
+                    SpannedEvent::SequenceStart { pointer } => format!("seq {}", pointer),
+                    SpannedEvent::SequenceEnd { pointer, .. } => format!("/seq {}", pointer),
+                    SpannedEvent::MappingStart { pointer } => format!("map {}", pointer),
+                    SpannedEvent::MappingEnd { pointer, .. } => format!("/map {}", pointer),
+                };
+                format!("{} {}:{}", name, line, col)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                "doc 1:0",
+                "map  1:0",
+                "key /a 1:0",
+                "seq /a 2:2",
+                "/a/0 b 2:4",
+                "/seq /a 2:5",
+                "key /c 3:0",
+                "alias /c 3:3",
+                "/map  3:5",
+                "/doc 4:0",
+            ]
+        );
+        Ok(())
+    }
+}
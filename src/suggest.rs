@@ -0,0 +1,115 @@
+use crate::Positions;
+
+impl Positions {
+    /// Suggests up to `limit` existing pointers resembling one which wasn't found,
+    /// closest first, for "did you mean" messages
+    ///
+    /// Pointers are ranked by edit distance, then by how much of their path they share,
+    /// and those too different to be likely typos are left out
+    ///
+    /// ```rust,edition2018
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("jobs:\n  build: {}\n  test: {}\n")?;
+    /// assert_eq!(positions.suggest("/jobs/buidl", 3), vec!["/jobs/build"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn suggest<P>(
+        &self,
+        ptr: P,
+        limit: usize,
+    ) -> Vec<&str>
+    where
+        P: AsRef<str>,
+    {
+        let ptr = ptr.as_ref();
+        // allow roughly one edit for every three characters of the final token
+        let token = ptr.rsplit('/').next().unwrap_or_default();
+        let threshold = token.chars().count().div_ceil(3);
+        let mut candidates = self
+            .iter()
+            .map(|(candidate, _)| candidate.as_str())
+            .filter(|candidate| *candidate != ptr)
+            .filter_map(|candidate| {
+                let distance = distance(ptr, candidate);
+                (distance <= threshold).then(|| {
+                    let shared = common_prefix(ptr, candidate);
+                    (distance, std::cmp::Reverse(shared), candidate)
+                })
+            })
+            .collect::<Vec<_>>();
+        candidates.sort();
+        candidates
+            .into_iter()
+            .take(limit)
+            .map(|(.., candidate)| candidate)
+            .collect()
+    }
+}
+
+/// Edit distance between two strings, in chars, counting insertions, deletions,
+/// substitutions, and transpositions of adjacent chars as one edit each
+pub(crate) fn distance(
+    a: &str,
+    b: &str,
+) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let mut rows = vec![(0..=b.len()).collect::<Vec<_>>()];
+    for i in 1..=a.len() {
+        let mut row = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            row[j] = (rows[i - 1][j - 1] + cost)
+                .min(rows[i - 1][j] + 1)
+                .min(row[j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                row[j] = row[j].min(rows[i - 2][j - 2] + 1);
+            }
+        }
+        rows.push(row);
+    }
+    rows[a.len()][b.len()]
+}
+
+/// Number of leading path tokens two pointers share
+fn common_prefix(
+    a: &str,
+    b: &str,
+) -> usize {
+    a.split('/')
+        .zip(b.split('/'))
+        .take_while(|(a, b)| a == b)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::distance;
+    use crate::{from_str, Result};
+
+    #[test]
+    fn distances() {
+        assert_eq!(distance("kitten", "sitting"), 3);
+        assert_eq!(distance("", "abc"), 3);
+        assert_eq!(distance("héllo", "hello"), 1);
+        assert_eq!(distance("buidl", "build"), 1);
+    }
+
+    #[test]
+    fn suggests_close_pointers() -> Result<()> {
+        let positions =
+            from_str("jobs:\n  build:\n    steps: []\n  built: {}\n  deploy: {}\nbuild: 1\n")?;
+        assert_eq!(
+            positions.suggest("/jobs/buidl", 5),
+            vec!["/jobs/build", "/jobs/built"]
+        );
+        assert_eq!(positions.suggest("/jobs/buidl", 1), vec!["/jobs/build"]);
+        assert_eq!(
+            positions.suggest("/jobs/build/step", 5),
+            vec!["/jobs/build/steps"]
+        );
+        assert!(positions.suggest("/nothing/like/it", 5).is_empty());
+        Ok(())
+    }
+}
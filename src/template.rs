@@ -0,0 +1,123 @@
+use std::borrow::Cow;
+
+/// Closing delimiters of the template expressions opened by `{` and the given character
+fn closing(open: u8) -> Option<&'static str> {
+    match open {
+        b'{' => Some("}}"),
+        b'%' => Some("%}"),
+        b'#' => Some("#}"),
+        _ => None,
+    }
+}
+
+/// Finds the byte range of the first template expression in `text`
+fn next_template(text: &str) -> Option<(usize, usize)> {
+    let mut at = 0;
+    while let Some(found) = text[at..].find('{') {
+        let start = at + found;
+        if let Some(close) = text.as_bytes().get(start + 1).copied().and_then(closing) {
+            let end = text[start + 2..].find(close)? + start + 4;
+            return Some((start, end));
+        }
+        at = start + 1;
+    }
+    None
+}
+
+/// Returns true when text holds nothing but whitespace and template expressions
+fn only_templates(mut text: &str) -> bool {
+    while let Some((start, end)) = next_template(text) {
+        if !text[..start].trim().is_empty() {
+            return false;
+        }
+        text = &text[end..];
+    }
+    text.trim().is_empty()
+}
+
+/// Masks the `{{ }}`, `{% %}`, and `{# #}` template expressions of Helm charts and Jinja
+/// templates so the YAML around them parses
+///
+/// Lines starting with templates and holding nothing else, such as `{{- if .Values.enabled }}`,
+/// become comments. Other templates have their ASCII characters replaced with `_`, reading
+/// as plain scalars. Characters are only replaced with ones of the same length, so byte
+/// offsets and columns of the masked text are those of the original
+pub(crate) fn mask(text: &str) -> Cow<'_, str> {
+    let mut masked: Option<Vec<u8>> = None;
+    let mut at = 0;
+    while let Some((start, end)) = next_template(&text[at..]) {
+        let (start, end) = (at + start, at + end);
+        let bytes = masked.get_or_insert_with(|| text.as_bytes().to_vec());
+        let line_start = text[..start].rfind('\n').map_or(0, |idx| idx + 1);
+        let line_end = text[end..].find('\n').map_or(text.len(), |idx| end + idx);
+        if text[line_start..start].trim().is_empty() && only_templates(&text[end..line_end]) {
+            // comment out each line the templates span
+            bytes[start] = b'#';
+            for (idx, _) in text[start..line_end].match_indices('\n') {
+                let line = start + idx + 1;
+                let indent = text[line..line_end]
+                    .find(|c: char| c != ' ' && c != '\t')
+                    .map(|indent| line + indent);
+                if let Some(first) = indent {
+                    bytes[first] = b'#';
+                }
+            }
+            at = line_end;
+        } else {
+            for byte in &mut bytes[start..end] {
+                if byte.is_ascii() && !byte.is_ascii_whitespace() {
+                    *byte = b'_';
+                }
+            }
+            at = end;
+        }
+    }
+    match masked {
+        // only ASCII bytes were replaced, with ASCII bytes, so the text is still utf8
+        Some(bytes) => Cow::Owned(String::from_utf8(bytes).unwrap_or_else(|_| text.to_owned())),
+        None => Cow::Borrowed(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mask;
+    use crate::{from_str, from_str_with, Options, Position, Result};
+
+    #[test]
+    fn masks_templates() {
+        assert_eq!(mask("a: 1\n"), "a: 1\n");
+        assert_eq!(
+            mask(
+                "{{- if .Values.on }}\nimage: {{ .Values.image | quote }}\n{{ end }}{{/* x */}}\n"
+            ),
+            "#{- if .Values.on }}\nimage: __ _____________ _ _____ __\n#{ end }}{{/* x */}}\n"
+        );
+        assert_eq!(
+            mask("a:\n  {% for x in\n     xs %}\n  b: {# é #}\n"),
+            "a:\n  #% for x in\n     #s %}\n  b: __ é __\n"
+        );
+    }
+
+    #[test]
+    fn indexes_templated_manifests() -> Result<()> {
+        let yaml = "metadata:\n  name: {{ .Release.Name }}-web\n  labels:\n    {{- include \"labels\" . | nindent 4 }}\nspec:\n  {{- if .Values.tls }}\n  tls: true\n  {{- end }}\n  replicas: {{ .Values.replicas }}\n";
+        assert!(from_str(yaml).is_err());
+        let positions = from_str_with(yaml, Options::default().templates(true))?;
+        assert_eq!(
+            positions.get("/metadata/name"),
+            Some(&Position { line: 2, col: 2 })
+        );
+        assert_eq!(
+            positions.get("/spec/tls"),
+            Some(&Position { line: 7, col: 2 })
+        );
+        assert_eq!(
+            positions
+                .span("/spec/replicas")
+                .map(|span| &yaml[span.bytes.clone()]),
+            Some("{{ .Values.replicas }}")
+        );
+        Ok(())
+    }
+}
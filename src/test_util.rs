@@ -0,0 +1,250 @@
+//! Generators for property testing code consuming [`Positions`](../struct.Positions.html),
+//! built on [arbitrary](https://docs.rs/arbitrary)
+//!
+//! [`Fixture`](struct.Fixture.html)s are small random documents paired with the pointers
+//! their index is expected to hold, sparing tests from crafting fixtures by hand
+//!
+//! ```rust,edition2018
+//! use arbitrary::{Arbitrary, Unstructured};
+//! use lincolns::test_util::Fixture;
+//! use std::collections::BTreeSet;
+//!
+//! # fn main() -> lincolns::Result<()>  {
+//! let bytes = [7; 64];
+//! let fixture = Fixture::arbitrary(&mut Unstructured::new(&bytes)).expect("too few bytes");
+//! let positions = lincolns::from_str(&fixture.text)?;
+//! assert_eq!(positions.keys().map(String::from).collect::<BTreeSet<_>>(), fixture.pointers);
+//! # Ok(())
+//! # }
+//! ```
+use crate::{path, Position};
+use arbitrary::{Arbitrary, Result, Unstructured};
+use std::collections::BTreeSet;
+
+/// Deepest nesting of generated collections
+const MAX_DEPTH: usize = 3;
+/// Most entries or items of a generated collection
+const MAX_CHILDREN: usize = 4;
+
+impl<'a> Arbitrary<'a> for Position {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Position {
+            line: u.int_in_range(1..=u32::MAX as usize)?,
+            col: u.int_in_range(0..=u32::MAX as usize)?,
+        })
+    }
+}
+
+/// A small random document, in block style YAML or JSON, and the pointers
+/// [`Positions::keys`](../struct.Positions.html#method.keys) is expected to return for it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fixture {
+    pub text: String,
+    /// Pointers of every node but the root, escaped as the index holds them
+    pub pointers: BTreeSet<String>,
+}
+
+impl<'a> Arbitrary<'a> for Fixture {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let node = Node::generate(u, 0)?;
+        let json = u.arbitrary()?;
+        let mut text = String::new();
+        if json {
+            node.json(&mut text);
+        } else {
+            node.yaml(&mut text, 0);
+        }
+        text.push('\n');
+        let mut pointers = BTreeSet::new();
+        node.pointers("", &mut pointers);
+        Ok(Fixture { text, pointers })
+    }
+}
+
+enum Node {
+    Scalar(String),
+    Sequence(Vec<Node>),
+    Mapping(Vec<(String, Node)>),
+}
+
+/// A word of lowercase letters and digits, starting with a letter so it reads as a plain scalar
+fn word(u: &mut Unstructured<'_>) -> Result<String> {
+    let len = u.int_in_range(1..=8)?;
+    (0..len)
+        .map(|idx| {
+            let chars: &[u8] = if idx == 0 {
+                b"abcdefghijklmnopqrstuvwxyz"
+            } else {
+                b"abcdefghijklmnopqrstuvwxyz0123456789"
+            };
+            u.choose(chars).map(|c| char::from(*c))
+        })
+        .collect()
+}
+
+impl Node {
+    fn generate(
+        u: &mut Unstructured<'_>,
+        depth: usize,
+    ) -> Result<Self> {
+        let kind = if depth < MAX_DEPTH {
+            u.int_in_range(0..=2)?
+        } else {
+            0
+        };
+        Ok(match kind {
+            0 => Node::Scalar(word(u)?),
+            1 => Node::Sequence(
+                (0..u.int_in_range(0..=MAX_CHILDREN)?)
+                    .map(|_| Node::generate(u, depth + 1))
+                    .collect::<Result<_>>()?,
+            ),
+            _ => {
+                let mut entries = Vec::<(String, Node)>::new();
+                for _ in 0..u.int_in_range(0..=MAX_CHILDREN)? {
+                    let key = word(u)?;
+                    // keys are unique, as repeated keys are indexed once
+                    if entries.iter().all(|(existing, _)| *existing != key) {
+                        entries.push((key, Node::generate(u, depth + 1)?));
+                    }
+                }
+                Node::Mapping(entries)
+            }
+        })
+    }
+
+    fn pointers(
+        &self,
+        pointer: &str,
+        pointers: &mut BTreeSet<String>,
+    ) {
+        let children: Vec<(String, &Node)> = match self {
+            Node::Scalar(_) => return,
+            Node::Sequence(items) => items
+                .iter()
+                .enumerate()
+                .map(|(idx, item)| (idx.to_string(), item))
+                .collect(),
+            Node::Mapping(entries) => entries
+                .iter()
+                .map(|(key, value)| (key.clone(), value))
+                .collect(),
+        };
+        for (token, child) in children {
+            let pointer = format!("{}/{}", pointer, path::escape(&token));
+            child.pointers(&pointer, pointers);
+            pointers.insert(pointer);
+        }
+    }
+
+    /// Renders an empty collection or a scalar inline, returning false for other nodes
+    fn inline(
+        &self,
+        text: &mut String,
+    ) -> bool {
+        match self {
+            Node::Scalar(value) => text.push_str(value),
+            Node::Sequence(items) if items.is_empty() => text.push_str("[]"),
+            Node::Mapping(entries) if entries.is_empty() => text.push_str("{}"),
+            _ => return false,
+        }
+        true
+    }
+
+    fn yaml(
+        &self,
+        text: &mut String,
+        indent: usize,
+    ) {
+        if self.inline(text) {
+            return;
+        }
+        let children: Vec<(String, &Node)> = match self {
+            Node::Sequence(items) => items.iter().map(|item| ("-".into(), item)).collect(),
+            Node::Mapping(entries) => entries
+                .iter()
+                .map(|(key, value)| (format!("{}:", key), value))
+                .collect(),
+            Node::Scalar(_) => return,
+        };
+        for (idx, (lead, child)) in children.into_iter().enumerate() {
+            if idx > 0 {
+                text.push('\n');
+            }
+            text.push_str(&" ".repeat(indent));
+            text.push_str(&lead);
+            text.push(' ');
+            if !child.inline(text) {
+                text.pop();
+                text.push('\n');
+                child.yaml(text, indent + 2);
+            }
+        }
+    }
+
+    fn json(
+        &self,
+        text: &mut String,
+    ) {
+        match self {
+            Node::Scalar(value) => {
+                text.push('"');
+                text.push_str(value);
+                text.push('"');
+            }
+            Node::Sequence(items) => {
+                text.push('[');
+                for (idx, item) in items.iter().enumerate() {
+                    if idx > 0 {
+                        text.push_str(", ");
+                    }
+                    item.json(text);
+                }
+                text.push(']');
+            }
+            Node::Mapping(entries) => {
+                text.push('{');
+                for (idx, (key, value)) in entries.iter().enumerate() {
+                    if idx > 0 {
+                        text.push_str(", ");
+                    }
+                    text.push('"');
+                    text.push_str(key);
+                    text.push_str("\": ");
+                    value.json(text);
+                }
+                text.push('}');
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::from_str;
+
+    #[test]
+    fn fixtures_index_as_expected() -> crate::Result<()> {
+        let mut seed = 1u64;
+        for _ in 0..500 {
+            let bytes = (0..256)
+                .map(|_| {
+                    seed = seed
+                        .wrapping_mul(6_364_136_223_846_793_005)
+                        .wrapping_add(1_442_695_040_888_963_407);
+                    (seed >> 56) as u8
+                })
+                .collect::<Vec<_>>();
+            let fixture = Fixture::arbitrary(&mut Unstructured::new(&bytes)).expect("no fixture");
+            let positions = from_str(&fixture.text)?;
+            assert_eq!(
+                positions.keys().map(String::from).collect::<BTreeSet<_>>(),
+                fixture.pointers,
+                "{}",
+                fixture.text
+            );
+        }
+        Ok(())
+    }
+}
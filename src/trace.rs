@@ -0,0 +1,54 @@
+#[cfg(feature = "tracing")]
+use std::time::Instant;
+
+/// A phase of loading, traced as a `tracing` span when the `tracing` feature is enabled
+/// and otherwise doing nothing
+pub(crate) struct Phase {
+    #[cfg(feature = "tracing")]
+    span: tracing::span::EnteredSpan,
+    #[cfg(feature = "tracing")]
+    started: Instant,
+}
+
+impl Phase {
+    #[cfg(feature = "tracing")]
+    pub(crate) fn enter(span: tracing::Span) -> Self {
+        Phase {
+            span: span.entered(),
+            started: Instant::now(),
+        }
+    }
+
+    /// Ends the phase, recording how many things it produced and how long it took
+    #[cfg(feature = "tracing")]
+    pub(crate) fn finish(
+        self,
+        count: usize,
+    ) {
+        let micros = self.started.elapsed().as_micros() as u64;
+        tracing::debug!(count, micros, "finished");
+        self.span.exit();
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    pub(crate) fn finish(
+        self,
+        _count: usize,
+    ) {
+    }
+}
+
+/// Starts a [`Phase`](trace/struct.Phase.html) named by a literal, with any span fields following it
+#[cfg(feature = "tracing")]
+macro_rules! phase {
+    ($name:literal $(, $($fields:tt)*)?) => {
+        $crate::trace::Phase::enter(tracing::debug_span!($name $(, $($fields)*)?))
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! phase {
+    ($name:literal $(, $($fields:tt)*)?) => {
+        $crate::trace::Phase {}
+    };
+}
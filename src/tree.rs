@@ -0,0 +1,107 @@
+use crate::{path, NodeKind, Position, Positions, Span, Walk};
+use std::borrow::Cow;
+
+/// An indexed node along with those nested within it, returned by
+/// [`Positions::tree`](struct.Positions.html#method.tree)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pointer: String,
+    pub position: Position,
+    pub span: Span,
+    pub kind: NodeKind,
+    /// Nodes nested within this one, in document order
+    pub children: Vec<Node>,
+}
+
+impl Node {
+    /// JSON Pointer path of the node
+    pub fn pointer(&self) -> &str {
+        &self.pointer
+    }
+
+    /// The unescaped last token of the node's pointer, being its mapping key or sequence index
+    pub fn key(&self) -> Cow<'_, str> {
+        let token = self
+            .pointer
+            .rfind('/')
+            .map_or(self.pointer.as_str(), |idx| &self.pointer[idx + 1..]);
+        path::unescape(token)
+    }
+}
+
+/// Nests the last of a stack of nodes within the one before it, or amongst the roots
+fn close(
+    stack: &mut Vec<Node>,
+    roots: &mut Vec<Node>,
+) {
+    if let Some(node) = stack.pop() {
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(node),
+            None => roots.push(node),
+        }
+    }
+}
+
+impl Positions {
+    /// Returns the indexed nodes as a tree, in document order, starting with
+    /// the entries of the document's root
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::NodeKind;
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("steps:\n  - run: make\n")?;
+    /// let tree = positions.tree();
+    /// let step = &tree[0].children[0];
+    /// assert_eq!((step.pointer(), step.kind), ("/steps/0", NodeKind::Mapping));
+    /// assert_eq!(step.children[0].key(), "run");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn tree(&self) -> Vec<Node> {
+        let mut roots = Vec::new();
+        let mut stack = Vec::new();
+        self.walk(|pointer, entry| {
+            while stack.len() > entry.depth {
+                close(&mut stack, &mut roots);
+            }
+            stack.push(Node {
+                pointer: pointer.to_owned(),
+                position: *entry.position,
+                span: entry.span.clone(),
+                kind: entry.kind,
+                children: Vec::new(),
+            });
+            Walk::Continue
+        });
+        while !stack.is_empty() {
+            close(&mut stack, &mut roots);
+        }
+        roots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_str, Result};
+
+    #[test]
+    fn tree_nests_nodes_in_document_order() -> Result<()> {
+        let positions = from_str("z:\n  b: [1, 2]\n  a: 3\ny: 4\n")?;
+        fn outline(nodes: &[crate::Node]) -> Vec<String> {
+            nodes
+                .iter()
+                .map(|node| {
+                    let children = outline(&node.children);
+                    if children.is_empty() {
+                        node.key().into_owned()
+                    } else {
+                        format!("{}({})", node.key(), children.join(" "))
+                    }
+                })
+                .collect()
+        }
+        assert_eq!(outline(&positions.tree()), vec!["z(b(0 1) a)", "y"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,244 @@
+use crate::{Position, Positions};
+use std::iter::FromIterator;
+
+/// A compact, read-only table of [`Position`](struct.Position.html)s stored as a trie
+/// of pointer tokens
+///
+/// Each token is stored once, however many pointers share it, rather than every
+/// pointer being stored in full, greatly reducing the memory held by large documents.
+/// Only positions are kept
+///
+/// ```rust,edition2018
+/// use lincolns::{from_str, Position, PositionTrie};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let trie = PositionTrie::from(from_str("spec:\n  containers:\n    - image: nginx\n")?);
+/// assert_eq!(
+///     trie.get("/spec/containers/0/image"),
+///     Some(&Position { line: 3, col: 6 })
+/// );
+/// assert_eq!(
+///     trie.under("/spec/containers").map(|(ptr, _)| ptr).collect::<Vec<_>>(),
+///     vec!["/spec/containers/0", "/spec/containers/0/image"]
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PositionTrie {
+    nodes: Vec<TrieNode>,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+struct TrieNode {
+    token: Box<str>,
+    position: Option<Position>,
+    /// Indexes of child nodes, ordered by token
+    children: Vec<usize>,
+}
+
+impl TrieNode {
+    fn new(token: &str) -> Self {
+        TrieNode {
+            token: token.into(),
+            position: None,
+            children: Vec::new(),
+        }
+    }
+}
+
+impl Default for PositionTrie {
+    fn default() -> Self {
+        PositionTrie {
+            nodes: vec![TrieNode::new("")],
+            len: 0,
+        }
+    }
+}
+
+impl PositionTrie {
+    /// Gets a node's position given its JSON Pointer path
+    pub fn get<P>(
+        &self,
+        ptr: P,
+    ) -> Option<&Position>
+    where
+        P: AsRef<str>,
+    {
+        self.find(ptr.as_ref())
+            .and_then(|node| self.nodes[node].position.as_ref())
+    }
+
+    /// Returns true if a JSON Pointer path is indexed
+    pub fn contains<P>(
+        &self,
+        ptr: P,
+    ) -> bool
+    where
+        P: AsRef<str>,
+    {
+        self.get(ptr).is_some()
+    }
+
+    /// Returns the number of indexed pointers
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if there are no indexed pointers
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns an iterator over pointers and positions, depth first with siblings ordered by token
+    pub fn iter(&self) -> impl Iterator<Item = (String, &Position)> {
+        self.descend(0, String::new())
+    }
+
+    /// Returns an iterator over the pointers and positions of everything nested within
+    /// the node a JSON Pointer path refers to, depth first with siblings ordered by token
+    pub fn under<P>(
+        &self,
+        ptr: P,
+    ) -> impl Iterator<Item = (String, &Position)>
+    where
+        P: AsRef<str>,
+    {
+        let ptr = ptr.as_ref();
+        let node = self.find(ptr);
+        let ptr = ptr.to_owned();
+        node.into_iter()
+            .flat_map(move |node| self.descend(node, ptr.clone()))
+    }
+
+    /// Index of the node of a pointer
+    fn find(
+        &self,
+        ptr: &str,
+    ) -> Option<usize> {
+        if ptr.is_empty() {
+            return Some(0);
+        }
+        ptr.strip_prefix('/')?
+            .split('/')
+            .try_fold(0, |node, token| self.child(node, token).ok())
+    }
+
+    /// Index of a node's child with a token or, when there is none, where it would be inserted
+    fn child(
+        &self,
+        node: usize,
+        token: &str,
+    ) -> Result<usize, usize> {
+        let children = &self.nodes[node].children;
+        children
+            .binary_search_by(|child| (*self.nodes[*child].token).cmp(token))
+            .map(|idx| children[idx])
+    }
+
+    /// Nodes nested within a node whose pointer is `ptr`
+    fn descend(
+        &self,
+        node: usize,
+        ptr: String,
+    ) -> impl Iterator<Item = (String, &Position)> {
+        let mut stack = self.nodes[node]
+            .children
+            .iter()
+            .rev()
+            .map(|child| (*child, ptr.clone()))
+            .collect::<Vec<_>>();
+        std::iter::from_fn(move || loop {
+            let (node, parent) = stack.pop()?;
+            let node = &self.nodes[node];
+            let ptr = format!("{}/{}", parent, node.token);
+            stack.extend(
+                node.children
+                    .iter()
+                    .rev()
+                    .map(|child| (*child, ptr.clone())),
+            );
+            if let Some(position) = &node.position {
+                return Some((ptr, position));
+            }
+        })
+    }
+
+    /// Indexes a pointer's position, replacing any already indexed
+    fn insert(
+        &mut self,
+        ptr: &str,
+        position: Position,
+    ) {
+        let mut node = 0;
+        for token in ptr.strip_prefix('/').unwrap_or(ptr).split('/') {
+            node = match self.child(node, token) {
+                Ok(child) => child,
+                Err(idx) => {
+                    self.nodes.push(TrieNode::new(token));
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children.insert(idx, child);
+                    child
+                }
+            };
+        }
+        if self.nodes[node].position.replace(position).is_none() {
+            self.len += 1;
+        }
+    }
+}
+
+/// Builds a trie from pointers and positions, the root pointer having no position of its own
+impl FromIterator<(String, Position)> for PositionTrie {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (String, Position)>,
+    {
+        let mut trie = PositionTrie::default();
+        for (ptr, position) in iter {
+            if !ptr.is_empty() {
+                trie.insert(&ptr, position);
+            }
+        }
+        trie.nodes.shrink_to_fit();
+        trie
+    }
+}
+
+/// Moves the positions of a table into a trie, releasing the table's memory as it goes
+impl From<Positions> for PositionTrie {
+    fn from(positions: Positions) -> Self {
+        positions.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_str, Result};
+
+    #[test]
+    fn trie_matches_table() -> Result<()> {
+        let positions = from_str("a:\n  b: [1, {c: 2}]\n  'd/e~f': 3\nab: 4\n")?;
+        let expected = positions
+            .iter()
+            .map(|(ptr, position)| (ptr.clone(), *position))
+            .collect::<Vec<_>>();
+        let trie = PositionTrie::from(positions);
+        assert_eq!(trie.len(), expected.len());
+        for (ptr, position) in &expected {
+            assert_eq!(trie.get(ptr), Some(position), "{}", ptr);
+        }
+        assert_eq!(trie.get("/a/d~1e~0f"), Some(&Position { line: 3, col: 2 }));
+        assert_eq!(trie.get("/a/b/2"), None);
+        assert_eq!(trie.get(""), None);
+        assert_eq!(
+            trie.under("/a/b").map(|(ptr, _)| ptr).collect::<Vec<_>>(),
+            vec!["/a/b/0", "/a/b/1", "/a/b/1/c"]
+        );
+        assert_eq!(trie.iter().count(), expected.len());
+        assert_eq!(trie.under("/missing").count(), 0);
+        Ok(())
+    }
+}
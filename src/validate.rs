@@ -0,0 +1,303 @@
+use crate::{kind::article, suggest::distance, NodeKind, Position, Positions};
+use std::fmt;
+
+/// A JSON Pointer path a document must contain, optionally as a particular kind of node
+#[derive(Debug, Clone, PartialEq)]
+pub struct Requirement {
+    pub pointer: String,
+    /// The kind of node required, if any
+    pub kind: Option<NodeKind>,
+}
+
+impl Requirement {
+    /// Requires a node at a JSON Pointer path, of any kind
+    pub fn new<P>(pointer: P) -> Self
+    where
+        P: Into<String>,
+    {
+        Requirement {
+            pointer: pointer.into(),
+            kind: None,
+        }
+    }
+
+    /// Requires the node to be of a particular kind
+    pub fn kind(
+        mut self,
+        kind: NodeKind,
+    ) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+}
+
+/// A [`Requirement`](struct.Requirement.html) a document doesn't meet
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// The required JSON Pointer path
+    pub pointer: String,
+    /// The kind of node required, if any
+    pub expected: Option<NodeKind>,
+    /// The kind of node found, or `None` when it's missing
+    pub found: Option<NodeKind>,
+    /// The pointer and position of the node found or, when it's missing,
+    /// of its nearest existing ancestor
+    pub nearest: Option<(String, Position)>,
+}
+
+impl Violation {
+    /// Returns true when the required node is missing, rather than of the wrong kind
+    pub fn is_missing(&self) -> bool {
+        self.found.is_none()
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        match (self.expected, self.found) {
+            (Some(expected), Some(found)) => write!(
+                f,
+                "expected {} to be {} {} but found {} {}",
+                self.pointer,
+                article(expected),
+                expected,
+                article(found),
+                found
+            ),
+            (Some(expected), None) => write!(
+                f,
+                "missing {}, expected {} {}",
+                self.pointer,
+                article(expected),
+                expected
+            ),
+            (None, _) => write!(f, "missing {}", self.pointer),
+        }
+    }
+}
+
+/// A node at a pointer none of the allowed pointers given to
+/// [`Positions::unknown`](struct.Positions.html#method.unknown) account for
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unknown {
+    pub pointer: String,
+    pub position: Position,
+    /// An allowed pointer resembling this one closely enough to be what was meant
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for Unknown {
+    fn fmt(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "unknown {}", self.pointer)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, ", did you mean {}?", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns true if a pointer's tokens match the leading tokens of an allowed pointer,
+/// `*` matching any token
+fn matches(
+    tokens: &[&str],
+    allowed: &[String],
+) -> bool {
+    allowed.len() >= tokens.len()
+        && tokens
+            .iter()
+            .zip(allowed)
+            .all(|(token, allowed)| allowed == "*" || allowed == token)
+}
+
+impl Positions {
+    /// Lists the nodes none of the `allowed` JSON Pointer paths account for, in document order,
+    /// such as misspelled keys
+    ///
+    /// An allowed pointer accounts for itself, its ancestors, and, unless other allowed
+    /// pointers reach beneath it, everything nested within it. A `*` token allows any key or index.
+    /// Only the outermost of unknown nodes are listed, suggesting the allowed pointer
+    /// each was likely meant to be
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{from_str, Position};
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = from_str("services:\n  web:\n    image: nginx\n    enviroment: {}\n")?;
+    /// let unknown = positions.unknown(&["/services/*/image", "/services/*/environment"]);
+    /// assert_eq!(
+    ///     unknown[0].to_string(),
+    ///     "unknown /services/web/enviroment, did you mean /services/web/environment?"
+    /// );
+    /// assert_eq!(unknown[0].position, Position { line: 4, col: 4 });
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn unknown<P>(
+        &self,
+        allowed: &[P],
+    ) -> Vec<Unknown>
+    where
+        P: AsRef<str>,
+    {
+        let allowed = allowed
+            .iter()
+            .map(|ptr| {
+                ptr.as_ref()
+                    .split('/')
+                    .skip(1)
+                    .map(str::to_owned)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        self.iter_by_position()
+            .filter(|(ptr, _)| !ptr.is_empty())
+            .filter_map(|(ptr, position)| {
+                let tokens = ptr.split('/').skip(1).collect::<Vec<_>>();
+                let (token, parent) = tokens.split_last()?;
+                // pointers reaching beneath the parent, which limit what it may contain
+                let mut siblings = allowed
+                    .iter()
+                    .filter(|allowed| allowed.len() > parent.len() && matches(parent, allowed))
+                    .peekable();
+                siblings.peek()?;
+                if allowed.iter().any(|allowed| matches(&tokens, allowed)) {
+                    return None;
+                }
+                let threshold = token.chars().count().div_ceil(3);
+                let suggestion = siblings
+                    .map(|allowed| allowed[parent.len()].as_str())
+                    .filter(|sibling| *sibling != "*")
+                    .map(|sibling| (distance(token, sibling), sibling))
+                    .filter(|(distance, _)| *distance <= threshold)
+                    .min()
+                    .map(|(_, sibling)| {
+                        let parent = &ptr[..ptr.len() - token.len() - 1];
+                        format!(
+                            "{}/{}",
+                            parent,
+                            sibling.replace('~', "~0").replace('/', "~1")
+                        )
+                    });
+                Some(Unknown {
+                    pointer: ptr.to_owned(),
+                    position: *position,
+                    suggestion,
+                })
+            })
+            .collect()
+    }
+
+    /// Checks that the document contains each required JSON Pointer path, as the kind of node
+    /// required, returning a [`Violation`](struct.Violation.html) for each requirement it fails,
+    /// in the order given
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{from_str, NodeKind, Position, Requirement};
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = from_str("spec:\n  ports: 80\n")?;
+    /// let violations = positions.validate(&[
+    ///     Requirement::new("/spec/image"),
+    ///     Requirement::new("/spec/ports").kind(NodeKind::Sequence),
+    ///     Requirement::new("/spec").kind(NodeKind::Mapping),
+    /// ]);
+    /// assert_eq!(violations.len(), 2);
+    /// assert_eq!(violations[0].to_string(), "missing /spec/image");
+    /// assert_eq!(
+    ///     violations[0].nearest,
+    ///     Some(("/spec".to_owned(), Position { line: 1, col: 0 }))
+    /// );
+    /// assert_eq!(
+    ///     violations[1].to_string(),
+    ///     "expected /spec/ports to be a sequence but found an integer"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn validate(
+        &self,
+        requirements: &[Requirement],
+    ) -> Vec<Violation> {
+        requirements
+            .iter()
+            .filter_map(|requirement| {
+                let found = self.kind(&requirement.pointer);
+                if found.is_some() && (requirement.kind.is_none() || requirement.kind == found) {
+                    return None;
+                }
+                Some(Violation {
+                    pointer: requirement.pointer.clone(),
+                    expected: requirement.kind,
+                    found,
+                    nearest: self
+                        .get_closest(&requirement.pointer)
+                        .map(|(ptr, position)| (ptr.to_owned(), *position)),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_str, NodeKind, Position, Requirement, Result};
+
+    #[test]
+    fn reports_outermost_unknown_nodes() -> Result<()> {
+        let positions = from_str(
+            "name: web\nnmae: typo\nports: [{port: 80, prot: TCP}]\nlabels: {any: thing}\nextra: {nested: 1}\n",
+        )?;
+        let unknown =
+            positions.unknown(&["/name", "/ports/*/port", "/ports/*/protocol", "/labels"]);
+        assert_eq!(
+            unknown.iter().map(ToString::to_string).collect::<Vec<_>>(),
+            vec![
+                "unknown /nmae, did you mean /name?",
+                "unknown /ports/0/prot, did you mean /ports/0/port?",
+                "unknown /extra",
+            ]
+        );
+        assert_eq!(unknown[1].position, Position { line: 3, col: 19 });
+        Ok(())
+    }
+
+    #[test]
+    fn reports_missing_and_mistyped_pointers() -> Result<()> {
+        let positions = from_str("a:\n  b: [1]\nc: x\n")?;
+        let violations = positions.validate(&[
+            Requirement::new("/a/b/0").kind(NodeKind::Integer),
+            Requirement::new("/a/b/1"),
+            Requirement::new("/c").kind(NodeKind::Mapping),
+            Requirement::new("/d/e").kind(NodeKind::String),
+        ]);
+        assert_eq!(
+            violations
+                .iter()
+                .map(|violation| (violation.to_string(), violation.nearest.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    "missing /a/b/1".to_owned(),
+                    Some(("/a/b".to_owned(), Position { line: 2, col: 2 }))
+                ),
+                (
+                    "expected /c to be a mapping but found a string".to_owned(),
+                    Some(("/c".to_owned(), Position { line: 3, col: 0 }))
+                ),
+                (
+                    "missing /d/e, expected a string".to_owned(),
+                    Some(("".to_owned(), Position { line: 1, col: 0 }))
+                ),
+            ]
+        );
+        assert!(violations[0].is_missing());
+        assert!(!violations[1].is_missing());
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+use crate::{kind, tree::Node, NodeKind, Position, Positions, Span, YamlVersion};
+
+/// A value loaded from content along with where it's located, returned by
+/// [`Positions::annotated`](struct.Positions.html#method.annotated)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotated {
+    pub value: Value,
+    pub position: Position,
+    pub span: Span,
+}
+
+/// A value whose nested values are [`Annotated`](struct.Annotated.html) with where they're located
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Sequence(Vec<Annotated>),
+    /// Entries in document order
+    Mapping(Vec<(String, Annotated)>),
+    /// An alias, `*name`, referring to an anchored node
+    Alias,
+}
+
+impl Value {
+    /// Resolves a scalar of a given kind, falling back on its text when it can't be represented
+    fn scalar(
+        kind: NodeKind,
+        text: &str,
+        version: YamlVersion,
+    ) -> Value {
+        let value = match kind {
+            NodeKind::Null => Some(Value::Null),
+            NodeKind::Bool => kind::boolean(version, text).map(Value::Bool),
+            NodeKind::Integer => kind::integer(version, text).flatten().map(Value::Integer),
+            NodeKind::Float => kind::float(version, text)
+                .or_else(|| text.parse().ok())
+                .map(Value::Float),
+            _ => None,
+        };
+        value.unwrap_or_else(|| Value::String(text.to_owned()))
+    }
+}
+
+impl Positions {
+    /// Returns the value a JSON Pointer path refers to, with every node nested within it
+    /// paired with where it's located, so content needn't be parsed again to be used
+    ///
+    /// The root pointer, `""`, refers to the first document
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::{Position, Value};
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("replicas: 3\nports: [80]\n")?;
+    /// let root = positions.annotated("").expect("no document");
+    /// match root.value {
+    ///     Value::Mapping(entries) => {
+    ///         assert_eq!(entries[0].0, "replicas");
+    ///         assert_eq!(entries[0].1.value, Value::Integer(3));
+    ///         assert_eq!(entries[1].1.position, Position { line: 2, col: 0 });
+    ///     }
+    ///     other => panic!("unexpected {:?}", other),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn annotated<P>(
+        &self,
+        ptr: P,
+    ) -> Option<Annotated>
+    where
+        P: AsRef<str>,
+    {
+        let ptr = ptr.as_ref();
+        let (position, span, kind, text) = self.describe(ptr)?;
+        let children = match kind {
+            NodeKind::Sequence | NodeKind::Mapping if ptr.is_empty() => self.tree(),
+            NodeKind::Sequence | NodeKind::Mapping => find(self.tree(), ptr)?.children,
+            _ => Vec::new(),
+        };
+        let value = self.value(kind, text, children);
+        Some(Annotated {
+            value,
+            position: *position,
+            span: span.clone(),
+        })
+    }
+
+    fn value(
+        &self,
+        kind: NodeKind,
+        text: Option<&str>,
+        children: Vec<Node>,
+    ) -> Value {
+        let annotate = |node: Node| {
+            let text = self.describe(node.pointer()).and_then(|(.., text)| text);
+            let key = node.key().into_owned();
+            let annotated = Annotated {
+                position: node.position,
+                span: node.span.clone(),
+                value: self.value(node.kind, text, node.children),
+            };
+            (key, annotated)
+        };
+        match kind {
+            NodeKind::Sequence => {
+                Value::Sequence(children.into_iter().map(|node| annotate(node).1).collect())
+            }
+            NodeKind::Mapping => Value::Mapping(children.into_iter().map(annotate).collect()),
+            NodeKind::Alias => Value::Alias,
+            kind => Value::scalar(kind, text.unwrap_or_default(), self.yaml_version()),
+        }
+    }
+}
+
+/// Finds the node at `ptr` by descending through its ancestors
+fn find(
+    nodes: Vec<Node>,
+    ptr: &str,
+) -> Option<Node> {
+    for node in nodes {
+        if node.pointer() == ptr {
+            return Some(node);
+        }
+        if ptr.starts_with(node.pointer()) && ptr[node.pointer().len()..].starts_with('/') {
+            return find(node.children, ptr);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_str, Result};
+
+    #[test]
+    fn annotates_values_with_positions() -> Result<()> {
+        let positions = from_str("a:\n  - 1\n  - 2.5\n  - true\n  - ~\n  - x\nb: {c: d}\n")?;
+        let a = positions.annotated("/a").expect("missing /a");
+        assert_eq!(a.position, Position { line: 1, col: 0 });
+        match a.value {
+            Value::Sequence(items) => {
+                let values: Vec<_> = items.into_iter().map(|item| item.value).collect();
+                assert_eq!(
+                    values,
+                    vec![
+                        Value::Integer(1),
+                        Value::Float(2.5),
+                        Value::Bool(true),
+                        Value::Null,
+                        Value::String("x".into())
+                    ]
+                );
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        let c = positions.annotated("/b/c").expect("missing /b/c");
+        assert_eq!(c.value, Value::String("d".into()));
+        assert_eq!(c.position, Position { line: 7, col: 4 });
+        match positions.annotated("").map(|root| root.value) {
+            Some(Value::Mapping(entries)) => {
+                let keys: Vec<_> = entries.iter().map(|(key, _)| key.as_str()).collect();
+                assert_eq!(keys, vec!["a", "b"]);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(positions.annotated("/missing"), None);
+        Ok(())
+    }
+}
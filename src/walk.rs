@@ -0,0 +1,137 @@
+use crate::{NodeKind, Position, Positions, Span};
+use std::collections::HashMap;
+
+/// A node listed by [`Positions::entries`](struct.Positions.html#method.entries)
+/// or visited by [`Positions::walk`](struct.Positions.html#method.walk)
+#[derive(Debug)]
+pub struct Entry<'a> {
+    /// JSON Pointer path of the node
+    pub pointer: &'a str,
+    /// Nesting depth, starting at 0 for the root's children
+    pub depth: usize,
+    pub position: &'a Position,
+    /// Span of the key of a mapping entry
+    pub key_span: Option<&'a Span>,
+    /// Span of the `-` indicator of a block sequence item
+    pub indicator_span: Option<&'a Span>,
+    /// Span of the value
+    pub span: &'a Span,
+    pub kind: NodeKind,
+    /// Parsed value of a scalar
+    pub value: Option<&'a str>,
+}
+
+/// Controls how a walk proceeds after visiting a node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Walk {
+    /// Continue on to the node's children, then its siblings
+    Continue,
+    /// Continue on to the node's siblings, skipping anything nested within it
+    SkipChildren,
+    /// End the walk
+    Stop,
+}
+
+/// Pointers of a node's ancestors followed by its own, e.g. `/a`, `/a/b` for `/a/b`
+fn lineage(pointer: &str) -> impl Iterator<Item = &str> {
+    pointer
+        .match_indices('/')
+        .skip(1)
+        .map(move |(idx, _)| &pointer[..idx])
+        .chain(std::iter::once(pointer))
+}
+
+impl Positions {
+    /// Visits each indexed node depth first in document order, calling `visit`
+    /// with its JSON Pointer path and [`Entry`](struct.Entry.html)
+    ///
+    /// ```rust,edition2018
+    /// use lincolns::Walk;
+    ///
+    /// # fn main() -> lincolns::Result<()>  {
+    /// let positions = lincolns::from_str("b:\n  c: 1\na: 2")?;
+    /// let mut visited = Vec::new();
+    /// positions.walk(|pointer, entry| {
+    ///     visited.push((pointer.to_owned(), entry.depth));
+    ///     Walk::Continue
+    /// });
+    /// assert_eq!(
+    ///     visited,
+    ///     vec![("/b".into(), 0), ("/b/c".into(), 1), ("/a".into(), 0)]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn walk<F>(
+        &self,
+        mut visit: F,
+    ) where
+        F: FnMut(&str, &Entry) -> Walk,
+    {
+        // order subtrees by their earliest position so that they stay together
+        let mut first = HashMap::<&str, &Position>::new();
+        for (pointer, position) in self.iter() {
+            for ancestor in lineage(pointer) {
+                let earliest = first.entry(ancestor).or_insert(position);
+                if position < *earliest {
+                    *earliest = position;
+                }
+            }
+        }
+        let mut nodes = self
+            .iter()
+            .map(|(pointer, position)| {
+                let order = lineage(pointer)
+                    .map(|ancestor| first[ancestor])
+                    .collect::<Vec<_>>();
+                (order, pointer, position)
+            })
+            .collect::<Vec<_>>();
+        nodes.sort();
+        let mut skipping: Option<String> = None;
+        for (order, pointer, _) in nodes {
+            if let Some(prefix) = &skipping {
+                if pointer.starts_with(prefix.as_str()) {
+                    continue;
+                }
+                skipping = None;
+            }
+            let entry = match self.entry_at(pointer) {
+                Some(entry) => Entry {
+                    depth: order.len() - 1,
+                    ..entry
+                },
+                None => continue,
+            };
+            match visit(pointer, &entry) {
+                Walk::Continue => (),
+                Walk::SkipChildren => skipping = Some(format!("{}/", pointer)),
+                Walk::Stop => return,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{from_str, Result, Walk};
+
+    #[test]
+    fn walk_skips_children_and_stops() -> Result<()> {
+        let positions = from_str("z:\n  - y: 1\n    x: 2\n  - 3\nm:\n  n: 4\nlast: 5\n")?;
+        let mut visited = Vec::new();
+        positions.walk(|pointer, _| {
+            visited.push(pointer.to_owned());
+            match pointer {
+                "/m" => Walk::SkipChildren,
+                "/last" => Walk::Stop,
+                _ => Walk::Continue,
+            }
+        });
+        assert_eq!(
+            visited,
+            vec!["/z", "/z/0", "/z/0/y", "/z/0/x", "/z/1", "/m", "/last"]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use crate::Position;
+
+/// Content skipped while indexing, listed by [`Positions::warnings`](struct.Positions.html#method.warnings)
+///
+/// Each names the pointer of the collection the content was skipped within
+/// and the position of the content itself
+#[derive(Debug, Clone, PartialEq)]
+pub enum Warning {
+    /// A mapping entry keyed by an alias, which has no pointer token
+    AliasKey { pointer: String, position: Position },
+    /// A mapping entry keyed by a collection containing an alias, which has no pointer token
+    ComplexKey { pointer: String, position: Position },
+    /// Content the indexer didn't expect where it occurred, ending the collection containing it
+    Unhandled { pointer: String, position: Position },
+}
+
+impl Warning {
+    /// JSON Pointer path of the collection content was skipped within
+    pub fn pointer(&self) -> &str {
+        match self {
+            Warning::AliasKey { pointer, .. }
+            | Warning::ComplexKey { pointer, .. }
+            | Warning::Unhandled { pointer, .. } => pointer,
+        }
+    }
+
+    /// Position of the skipped content
+    pub fn position(&self) -> &Position {
+        match self {
+            Warning::AliasKey { position, .. }
+            | Warning::ComplexKey { position, .. }
+            | Warning::Unhandled { position, .. } => position,
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{Options, Position, Positions};
+use wasm_bindgen::prelude::*;
+
+/// A [`Positions`](struct.Positions.html) table exported to JavaScript as `Positions`
+#[wasm_bindgen(js_name = Positions)]
+pub struct WasmPositions(Positions);
+
+/// A [`Position`](struct.Position.html) exported to JavaScript as `Position`
+#[wasm_bindgen(js_name = Position)]
+#[derive(Debug, Clone, Copy)]
+pub struct WasmPosition {
+    pub line: usize,
+    pub col: usize,
+}
+
+/// A pointer and its position exported to JavaScript as `Entry`
+#[wasm_bindgen(js_name = Entry, getter_with_clone)]
+#[derive(Debug, Clone)]
+pub struct WasmEntry {
+    pub pointer: String,
+    pub line: usize,
+    pub col: usize,
+}
+
+impl From<&Position> for WasmPosition {
+    fn from(position: &Position) -> Self {
+        WasmPosition {
+            line: position.line,
+            col: position.col,
+        }
+    }
+}
+
+/// Loads a lookup table from text, exported to JavaScript as `fromStr`.
+/// Columns are numbered starting at 1 when `oneBased` is true, as most editors do
+#[wasm_bindgen(js_name = fromStr)]
+pub fn wasm_from_str(
+    text: &str,
+    #[wasm_bindgen(js_name = oneBased)] one_based: Option<bool>,
+) -> Result<WasmPositions, JsError> {
+    let options = Options::default().one_based(one_based.unwrap_or_default());
+    Ok(WasmPositions(crate::from_str_with(text, options)?))
+}
+
+#[wasm_bindgen(js_class = Positions)]
+impl WasmPositions {
+    /// Gets the position of a JSON Pointer path, or `undefined` when there is none
+    pub fn get(
+        &self,
+        pointer: &str,
+    ) -> Option<WasmPosition> {
+        self.0.get(pointer).map(WasmPosition::from)
+    }
+
+    /// Lists every pointer and its position, in document order
+    pub fn entries(&self) -> Vec<WasmEntry> {
+        self.0
+            .iter_by_position()
+            .map(|(pointer, position)| WasmEntry {
+                pointer: pointer.to_owned(),
+                line: position.line,
+                col: position.col,
+            })
+            .collect()
+    }
+
+    /// The number of indexed pointers
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.0.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exports_lookups() {
+        let positions = wasm_from_str("b: 1\na: [2]\n", Some(true)).unwrap();
+        assert_eq!(positions.get("/a/0").map(|position| position.col), Some(5));
+        assert!(positions.get("/c").is_none());
+        assert_eq!(
+            positions
+                .entries()
+                .into_iter()
+                .map(|entry| entry.pointer)
+                .collect::<Vec<_>>(),
+            vec!["/b", "/a", "/a/0"]
+        );
+        assert_eq!(positions.length(), 3);
+    }
+}
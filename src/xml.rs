@@ -0,0 +1,188 @@
+use crate::{backend::Event, Backend, Error, Result, ScalarStyle};
+use roxmltree::{Document, Node};
+
+/// A [`Backend`](trait.Backend.html) for XML content built on [roxmltree](https://docs.rs/roxmltree)
+///
+/// Elements are indexed as mappings keyed by the names of their child elements, with children
+/// sharing a name collected into a sequence, attributes keyed by their name prefixed with `@`,
+/// and text keyed as `#text`. Elements with neither attributes nor child elements are indexed
+/// as the scalar of their text. Requires the `xml` feature
+///
+/// ```rust,edition2018
+/// use lincolns::{from_str_using, Options, Position, Xml};
+///
+/// # fn main() -> lincolns::Result<()>  {
+/// let pom = r#"<project>
+///   <dependencies>
+///     <dependency scope="test"><version>1.0</version></dependency>
+///     <dependency><version>2.0</version></dependency>
+///   </dependencies>
+/// </project>"#;
+/// let positions = from_str_using(&Xml, pom, Options::default())?;
+/// assert_eq!(
+///     positions.get("/project/dependencies/dependency/1/version"),
+///     Some(&Position { line: 4, col: 17 })
+/// );
+/// assert_eq!(
+///     positions.get_value("/project/dependencies/dependency/0/@scope"),
+///     Some(("test", &Position { line: 3, col: 16 }))
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Xml;
+
+impl Backend for Xml {
+    fn parse(
+        &self,
+        text: &str,
+        sink: &mut dyn FnMut(Event, usize),
+    ) -> Result<()> {
+        let document = Document::parse(text).map_err(|err| Error::Backend(Box::new(err)))?;
+        let root = document.root_element();
+        sink(Event::DocumentStart, root.range().start);
+        sink(Event::MappingStart { anchor: 0 }, root.range().start);
+        key(text, root, sink);
+        element(text, root, sink);
+        sink(Event::MappingEnd, root.range().end);
+        sink(Event::DocumentEnd, text.len());
+        Ok(())
+    }
+}
+
+fn scalar(
+    value: &str,
+    style: ScalarStyle,
+) -> Event {
+    Event::Scalar {
+        value: value.into(),
+        style,
+        tag: None,
+    }
+}
+
+/// Emits an element's name as a mapping key, located at its local name within its start tag
+fn key(
+    text: &str,
+    node: Node,
+    sink: &mut dyn FnMut(Event, usize),
+) {
+    let name = node.tag_name().name();
+    let start = node.range().start + 1;
+    let qualified = text[start..]
+        .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .unwrap_or(0);
+    sink(
+        scalar(name, ScalarStyle::Plain),
+        start + qualified.saturating_sub(name.len()),
+    );
+}
+
+/// Emits the events of an element's content
+fn element(
+    text: &str,
+    node: Node,
+    sink: &mut dyn FnMut(Event, usize),
+) {
+    let children = node.children().filter(Node::is_element).collect::<Vec<_>>();
+    let content = node
+        .children()
+        .find(|child| child.is_text() && !child.text().unwrap_or_default().trim().is_empty());
+    if node.attributes().len() == 0 && children.is_empty() {
+        match content.map(trimmed) {
+            Some((value, offset)) => sink(scalar(value, ScalarStyle::Plain), offset),
+            None => sink(scalar("", ScalarStyle::Plain), node.range().end),
+        }
+        return;
+    }
+    sink(Event::MappingStart { anchor: 0 }, node.range().start);
+    for attribute in node.attributes() {
+        let name = attribute.range_qname().start;
+        sink(
+            scalar(&format!("@{}", attribute.name()), ScalarStyle::Plain),
+            name,
+        );
+        let quote = attribute.range_value().start - 1;
+        let style = if text[quote..].starts_with('\'') {
+            ScalarStyle::SingleQuoted
+        } else {
+            ScalarStyle::DoubleQuoted
+        };
+        sink(scalar(attribute.value(), style), quote);
+    }
+    if let Some((value, offset)) = content.map(trimmed) {
+        sink(scalar("#text", ScalarStyle::Plain), offset);
+        sink(scalar(value, ScalarStyle::Plain), offset);
+    }
+    // children sharing a name are grouped in the order their names first appear
+    let mut groups: Vec<(&str, Vec<Node>)> = Vec::new();
+    for child in children {
+        let name = child.tag_name().name();
+        match groups.iter_mut().find(|(group, _)| *group == name) {
+            Some((_, nodes)) => nodes.push(child),
+            None => groups.push((name, vec![child])),
+        }
+    }
+    for (_, nodes) in groups {
+        key(text, nodes[0], sink);
+        match nodes.as_slice() {
+            [node] => element(text, *node, sink),
+            nodes => {
+                sink(Event::SequenceStart { anchor: 0 }, nodes[0].range().start);
+                for node in nodes {
+                    element(text, *node, sink);
+                }
+                let end = nodes.last().map_or(0, |node| node.range().end);
+                sink(Event::SequenceEnd, end);
+            }
+        }
+    }
+    sink(Event::MappingEnd, node.range().end);
+}
+
+/// A text node's content trimmed of surrounding whitespace, along with where it starts
+fn trimmed<'a>(node: Node<'a, '_>) -> (&'a str, usize) {
+    let text = node.text().unwrap_or_default();
+    let offset = node.range().start + (text.len() - text.trim_start().len());
+    (text.trim(), offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{from_str_using, NodeKind, Options, Position};
+
+    #[test]
+    fn indexes_elements_attributes_and_text() -> Result<()> {
+        let xml = "<?xml version=\"1.0\"?>\n<a id='1'>\n  hello\n  <b/>\n  <m:c xmlns:m=\"urn:m\">x</m:c>\n  <d>1</d>\n  <d><e>2</e></d>\n</a>\n";
+        let positions = from_str_using(&Xml, xml, Options::default())?;
+        assert_eq!(
+            positions.keys().collect::<Vec<_>>(),
+            vec![
+                "/a", "/a/#text", "/a/@id", "/a/b", "/a/c", "/a/d", "/a/d/0", "/a/d/1", "/a/d/1/e"
+            ]
+        );
+        assert_eq!(positions.get("/a"), Some(&Position { line: 2, col: 1 }));
+        assert_eq!(
+            positions.get_value("/a/@id").map(|(value, _)| value),
+            Some("1")
+        );
+        assert_eq!(
+            positions.get_value("/a/#text"),
+            Some(("hello", &Position { line: 3, col: 2 }))
+        );
+        assert_eq!(positions.kind("/a/b"), Some(NodeKind::String));
+        assert_eq!(positions.get("/a/c"), Some(&Position { line: 5, col: 5 }));
+        assert_eq!(positions.get("/a/d/1"), Some(&Position { line: 7, col: 2 }));
+        assert_eq!(
+            positions.get("/a/d/1/e"),
+            Some(&Position { line: 7, col: 6 })
+        );
+        assert!(matches!(
+            from_str_using(&Xml, "<a>", Options::default()),
+            Err(Error::Backend(_))
+        ));
+        Ok(())
+    }
+}